@@ -0,0 +1,455 @@
+//! Config file: defaults and named profiles for the command line, in TOML.
+//!
+//! Keys are long flag names, written with `-` or `_`. Top-level keys are defaults,
+//! and `[profile.NAME]` tables override them when `--profile NAME` is given:
+//!
+//! ```toml
+//! interval = "45..90"
+//! motion = "clock"
+//!
+//! [profile.meeting]
+//! activity = "keyboard"
+//! idle-threshold = 120
+//!
+//! [profile.overnight-build]
+//! motion = "none"
+//! until = 07:00:00
+//! ```
+//!
+//! Flags can also be set through `BUSYCRAB_*` environment variables, like
+//! `BUSYCRAB_IDLE_THRESHOLD=120`. The command line wins over the environment, which
+//! wins over the profile, which wins over the top-level defaults.
+
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, Command, CommandFactory, FromArgMatches};
+use std::collections::BTreeMap;
+use std::error::Error as _;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::{ImDocument, Item, TableLike, Value};
+
+use super::Args;
+use crate::error::{Error, Result};
+
+/// Prefix of the environment variables that set flags.
+pub const ENV_PREFIX: &str = "BUSYCRAB_";
+
+/// Flags that pick the config file and profile, so the file can't set them.
+const CONFIG_FLAGS: [&str; 2] = ["config", "profile"];
+
+/// A flag the config file sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    /// Value as it would be written on the command line
+    text: String,
+    /// Line the key is on, starting at 1
+    line: usize,
+}
+
+/// Flags set by one table of the file, by long name.
+type Entries = BTreeMap<String, Entry>;
+
+/// A parsed and checked config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigFile {
+    defaults: Entries,
+    profiles: BTreeMap<String, Entries>,
+}
+
+impl ConfigFile {
+    /// Reads and checks a config file.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the file.
+    /// - `Err(Error::Config)` if it can't be read, isn't TOML, or sets a flag that
+    ///   doesn't exist or to a value the flag doesn't take, naming the key and line.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            Error::config(format!(
+                "can't read config file {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        text.parse()
+            .map_err(|err| Error::config(format!("{} {}", path.display(), err)))
+    }
+
+    /// Gets the profile names, in alphabetical order.
+    pub fn profiles(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Gets the flags the file sets, by long name, with the values of `profile`
+    /// over the top-level ones.
+    ///
+    /// ### Returns
+    ///
+    /// - `Some` with the values as they would be written on the command line.
+    /// - `None` if there's no such profile.
+    pub fn values(&self, profile: Option<&str>) -> Option<BTreeMap<String, String>> {
+        Some(
+            self.layer(profile)?
+                .into_iter()
+                .map(|(key, (entry, _))| (key, entry.text))
+                .collect(),
+        )
+    }
+
+    /// Gets the entries `values` takes its values from, with the profile each came
+    /// from, if any.
+    fn layer(&self, profile: Option<&str>) -> Option<BTreeMap<String, (Entry, Option<&str>)>> {
+        let mut entries: BTreeMap<_, _> = self
+            .defaults
+            .iter()
+            .map(|(key, entry)| (key.clone(), (entry.clone(), None)))
+            .collect();
+        if let Some(name) = profile {
+            let (name, profile) = self.profiles.get_key_value(name)?;
+            for (key, entry) in profile {
+                entries.insert(key.clone(), (entry.clone(), Some(name.as_str())));
+            }
+        }
+        Some(entries)
+    }
+}
+
+impl FromStr for ConfigFile {
+    type Err = String;
+
+    /// Parses a config file, checking every key and value.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let document = ImDocument::parse(s).map_err(|err| {
+            let message = err.message().trim().lines().collect::<Vec<_>>().join("; ");
+            match err.span() {
+                Some(span) => format!("line {}: {}", line_of(s, span.start), message),
+                None => message,
+            }
+        })?;
+        let root = document.as_table();
+
+        let mut file = Self {
+            defaults: entries(s, root)?,
+            profiles: BTreeMap::new(),
+        };
+        let Some(profiles) = root.get("profile") else {
+            return Ok(file);
+        };
+        let profiles = profiles.as_table_like().ok_or_else(|| {
+            format!(
+                "line {}: profile must be a table",
+                key_line(s, root, "profile")
+            )
+        })?;
+        for (name, profile) in profiles.iter() {
+            let profile = profile.as_table_like().ok_or_else(|| {
+                format!(
+                    "line {}: profile.{} must be a table",
+                    key_line(s, profiles, name),
+                    name
+                )
+            })?;
+            file.profiles.insert(name.to_string(), entries(s, profile)?);
+        }
+        Ok(file)
+    }
+}
+
+/// Checks the keys and values of one table, leaving out the `profile` tables.
+fn entries(text: &str, table: &dyn TableLike) -> std::result::Result<Entries, String> {
+    let command = Args::command();
+    let mut entries = Entries::new();
+
+    for (key, item) in table.iter() {
+        let line = key_line(text, table, key);
+        let name = key.replace('_', "-");
+        if name == "profile" && item.is_table_like() {
+            continue;
+        }
+        let arg = command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(name.as_str()) && is_layered(arg))
+            .ok_or_else(|| match CONFIG_FLAGS.contains(&name.as_str()) {
+                true => format!("line {}: {} can't be set in the config file", line, name),
+                false => format!("line {}: unknown key '{}'", line, key),
+            })?;
+        let value_text = match item {
+            Item::Value(Value::String(text)) => text.value().clone(),
+            Item::Value(Value::Integer(number)) => number.value().to_string(),
+            Item::Value(Value::Float(number)) => number.value().to_string(),
+            Item::Value(Value::Boolean(flag)) => flag.value().to_string(),
+            Item::Value(Value::Datetime(datetime)) => datetime.value().to_string(),
+            _ => {
+                return Err(format!(
+                    "line {}: {}: expected a string, number or boolean",
+                    line, name
+                ));
+            }
+        };
+        check_value(arg, &value_text).map_err(|err| format!("line {}: {}: {}", line, name, err))?;
+
+        let entry = Entry {
+            text: value_text,
+            line,
+        };
+        if let Some(first) = entries.insert(name.clone(), entry) {
+            return Err(format!(
+                "line {}: {} is already set on line {}",
+                line, name, first.line
+            ));
+        }
+    }
+    Ok(entries)
+}
+
+/// Gets the line `key` of `table` is on.
+fn key_line(text: &str, table: &dyn TableLike, key: &str) -> usize {
+    let span = table
+        .get_key_value(key)
+        .and_then(|(key, item)| key.span().or_else(|| item.span()));
+    line_of(text, span.map_or(0, |span| span.start))
+}
+
+/// Gets the line of a byte offset in `text`, starting at 1.
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+/// Checks if the config file and environment can set `arg`.
+fn is_layered(arg: &Arg) -> bool {
+    matches!(arg.get_action(), ArgAction::Set | ArgAction::SetTrue)
+        && arg
+            .get_long()
+            .is_some_and(|long| !CONFIG_FLAGS.contains(&long))
+}
+
+/// Checks if `arg` is on or off rather than taking a value.
+fn is_switch(arg: &Arg) -> bool {
+    matches!(arg.get_action(), ArgAction::SetTrue)
+}
+
+/// Checks if `arg` can be given without a value, like `--http`.
+fn has_optional_value(arg: &Arg) -> bool {
+    arg.get_num_args()
+        .is_some_and(|range| range.min_values() == 0)
+}
+
+/// Checks `text` is a value `arg` takes. Switches and flags with an optional value
+/// take booleans like `true` or `off`.
+fn check_value(arg: &Arg, text: &str) -> std::result::Result<(), String> {
+    if parse_bool(text).is_some() && (is_switch(arg) || has_optional_value(arg)) {
+        return Ok(());
+    }
+    if is_switch(arg) {
+        return Err(format!("invalid value '{}' (expected true or false)", text));
+    }
+    // Parse the value alone, without the rules that tie the flag to others
+    Command::new("busycrab")
+        .no_binary_name(true)
+        .arg(
+            Arg::new("value")
+                .long("value")
+                .value_parser(arg.get_value_parser().clone()),
+        )
+        .try_get_matches_from([format!("--value={}", text)])
+        .map(drop)
+        .map_err(|err| match err.source() {
+            Some(cause) => format!("invalid value '{}': {}", text, cause),
+            None => clap_message(&err),
+        })
+}
+
+/// Reads a boolean written like `true`, `yes`, `on` or `1`.
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.to_lowercase().as_str() {
+        "y" | "yes" | "t" | "true" | "on" | "1" => Some(true),
+        "n" | "no" | "f" | "false" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Gets the message of a clap error on one line, without its `error: ` prefix or the
+/// usage after it.
+fn clap_message(err: &clap::Error) -> String {
+    let text = err.to_string();
+    let message = text.split("\n\n").next().unwrap_or_default();
+    let message = message.strip_prefix("error: ").unwrap_or(message);
+    message.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Gets the environment variable for a long flag name, like `BUSYCRAB_IDLE_THRESHOLD`.
+pub fn env_name(long: &str) -> String {
+    format!("{}{}", ENV_PREFIX, long.to_uppercase().replace('-', "_"))
+}
+
+/// Gets where the config file is looked for when `--config` isn't given:
+/// `busycrab/config.toml` in `$XDG_CONFIG_HOME`, in `~/.config` without it, or in
+/// `%APPDATA%` on Windows.
+///
+/// * `env` - Looks up environment variables
+pub fn default_path(env: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    let dir = match env("XDG_CONFIG_HOME") {
+        Some(dir) if Path::new(&dir).is_absolute() => PathBuf::from(dir),
+        _ if cfg!(windows) => PathBuf::from(env("APPDATA")?),
+        _ => PathBuf::from(env("HOME")?).join(".config"),
+    };
+    Some(dir.join("busycrab").join("config.toml"))
+}
+
+/// Parses `argv` into `Args`, filling in flags it leaves out from the environment,
+/// then the profile, then the config file's defaults. Exits on command line errors
+/// and `--help` like `Args::parse`.
+///
+/// The config file is `--config`, `BUSYCRAB_CONFIG` or `default_path`, which may
+/// be missing. The profile is `--profile` or `BUSYCRAB_PROFILE`.
+///
+/// * `env` - Looks up environment variables
+///
+/// ### Returns
+///
+/// - `Ok` with the args.
+/// - `Err(Error::Config)` if the config file is invalid, the profile isn't in it, an
+///   environment variable has a value its flag doesn't take, or the flags don't go
+///   together, naming the variable or file line of the flag that doesn't fit.
+///   Subcommands leave such flags out instead.
+pub fn load_args<I, T>(argv: I, env: impl Fn(&str) -> Option<String>) -> Result<Args>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let argv: Vec<OsString> = argv.into_iter().map(Into::into).collect();
+    let command = Args::command();
+    let matches = command.clone().get_matches_from(&argv);
+
+    let explicit_path = matches
+        .get_one::<PathBuf>("config")
+        .cloned()
+        .or_else(|| env(&env_name("config")).map(PathBuf::from));
+    let profile = matches
+        .get_one::<String>("profile")
+        .cloned()
+        .or_else(|| env(&env_name("profile")));
+    let file = match &explicit_path {
+        Some(path) => Some((path.clone(), ConfigFile::from_file(path)?)),
+        None => match default_path(&env) {
+            Some(path) if path.exists() => Some((path.clone(), ConfigFile::from_file(&path)?)),
+            _ => None,
+        },
+    };
+    // Each value with where it came from, for errors
+    let values: BTreeMap<String, (String, String)> = match (&file, profile.as_deref()) {
+        (Some((path, file)), profile) => file
+            .layer(profile)
+            .ok_or_else(|| {
+                Error::config(format!(
+                    "no profile '{}' in {} (it has: {})",
+                    profile.unwrap_or_default(),
+                    path.display(),
+                    file.profiles().collect::<Vec<_>>().join(", ")
+                ))
+            })?
+            .into_iter()
+            .map(|(key, (entry, profile))| {
+                let source = match profile {
+                    Some(profile) => format!(
+                        "{} line {}, profile {}",
+                        path.display(),
+                        entry.line,
+                        profile
+                    ),
+                    None => format!("{} line {}", path.display(), entry.line),
+                };
+                (key, (entry.text, source))
+            })
+            .collect(),
+        (None, Some(name)) => {
+            return Err(Error::config(format!(
+                "can't use profile '{}' without a config file",
+                name
+            )));
+        }
+        (None, None) => BTreeMap::new(),
+    };
+
+    let mut injected = Vec::new();
+    for arg in command.get_arguments().filter(|arg| is_layered(arg)) {
+        let long = arg.get_long().unwrap_or_default();
+        if matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine) {
+            continue;
+        }
+        let variable = env_name(long);
+        let (text, source) = match env(&variable) {
+            Some(text) => {
+                check_value(arg, &text)
+                    .map_err(|err| Error::config(format!("{}: {}", variable, err)))?;
+                (text, variable)
+            }
+            None => match values.get(long) {
+                Some((text, source)) => (text.clone(), source.clone()),
+                None => continue,
+            },
+        };
+        let flag: OsString = match parse_bool(&text) {
+            Some(true) if is_switch(arg) || has_optional_value(arg) => format!("--{}", long).into(),
+            Some(false) if is_switch(arg) || has_optional_value(arg) => continue,
+            _ => format!("--{}={}", long, text).into(),
+        };
+        injected.push(Injected {
+            long: long.to_string(),
+            flag,
+            source,
+        });
+    }
+
+    let bin = argv.first().cloned().unwrap_or_else(|| "busycrab".into());
+    let parse = |injected: &[&Injected]| {
+        let layered = std::iter::once(bin.clone())
+            .chain(injected.iter().map(|injected| injected.flag.clone()))
+            .chain(argv.iter().skip(1).cloned());
+        command.clone().try_get_matches_from(layered)
+    };
+    let mut kept: Vec<&Injected> = injected.iter().collect();
+    let matches = loop {
+        let err = match parse(&kept) {
+            Ok(matches) => break matches,
+            Err(err) => err,
+        };
+        // The command line parsed on its own, so an injected flag doesn't go with
+        // the others: find the first one that parses without it
+        let culprit = (0..kept.len()).find(|&index| {
+            let mut others = kept.clone();
+            others.remove(index);
+            parse(&others).is_ok()
+        });
+        match culprit {
+            // Subcommands don't use most flags, so leave out the ones that don't fit
+            Some(index) if matches.subcommand().is_some() => {
+                kept.remove(index);
+            }
+            Some(index) => {
+                let culprit = kept[index];
+                return Err(Error::config(format!(
+                    "{} (from {}): {}",
+                    culprit.long,
+                    culprit.source,
+                    clap_message(&err)
+                )));
+            }
+            None => return Err(Error::config(clap_message(&err))),
+        }
+    };
+    Args::from_arg_matches(&matches).map_err(|err| Error::config(clap_message(&err)))
+}
+
+/// A flag the environment or config file adds to the command line.
+struct Injected {
+    /// Long name of the flag
+    long: String,
+    /// The flag and its value as a command line argument
+    flag: OsString,
+    /// The environment variable, or the config file line and profile, it came from
+    source: String,
+}
@@ -1,16 +1,36 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use crate::control::http;
+use crate::error::Result;
+use crate::failure::{FailureMode, DEFAULT_MAX_RETRIES};
+use crate::idle::IdleSource;
+use crate::input::{ActivityKey, ActivityMode, InputBackend, Positioning};
+use crate::limit::{RunDuration, StopTime};
+use crate::pattern::PatternKind;
+use crate::platform::Backend;
+use crate::schedule::{HolidayAction, Schedule};
+use crate::variation::ValueRange;
+
+pub mod config;
 
 /// 🦀 BusyCrab - A utility that prevents sleep and fakes activity to keep your status green
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
-    /// Interval between mouse movements in seconds
-    #[arg(short, long, default_value_t = 60)]
-    pub interval: u64,
+    /// Interval between mouse movements in seconds, or a range like 45..90 to pick
+    /// from at random each cycle
+    #[arg(short, long, default_value_t = ValueRange::fixed(60))]
+    pub interval: ValueRange<u64>,
+
+    /// Distance in pixels for mouse movement, or a range like 1..4
+    #[arg(short, long, default_value_t = ValueRange::fixed(3))]
+    pub wiggle: ValueRange<i32>,
 
-    /// Distance in pixels for mouse movement
-    #[arg(short, long, default_value_t = 3)]
-    pub wiggle: i32,
+    /// Change each interval by up to this many percent either way
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..=100))]
+    pub jitter: u32,
 
     /// Display additional information during operation
     #[arg(short, long, default_value_t = false)]
@@ -19,4 +39,163 @@ pub struct Args {
     /// Select motion animation type (crab, matrix, mandelbrot, clock, none)
     #[arg(short, long, default_value = "crab")]
     pub motion: String,
+
+    /// Force a sleep prevention backend (native, logind, screensaver, x11, wayland, none).
+    /// The first available one is used when omitted
+    #[arg(short, long)]
+    pub backend: Option<Backend>,
+
+    /// Shape the mouse traces (horizontal, circle, square, random-walk, figure-eight, bezier)
+    #[arg(short, long, default_value_t = PatternKind::Horizontal)]
+    pub pattern: PatternKind,
+
+    /// Time in milliseconds the bezier pattern takes to glide out and back
+    #[arg(long, default_value_t = 800)]
+    pub pattern_duration: u64,
+
+    /// Seed for the random patterns, intervals and distances, to repeat the exact
+    /// same movements
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// How the mouse follows the pattern (relative, absolute). absolute puts the cursor
+    /// back exactly where it started, even at a screen edge
+    #[arg(long, default_value_t = Positioning::Relative)]
+    pub positioning: Positioning,
+
+    /// Input simulated each cycle (mouse, keyboard, both)
+    #[arg(short, long, default_value_t = ActivityMode::Mouse)]
+    pub activity: ActivityMode,
+
+    /// Key to tap when the activity includes the keyboard (f15, shift, scrolllock)
+    #[arg(short, long, default_value_t = ActivityKey::F15)]
+    pub key: ActivityKey,
+
+    /// How to move the mouse (enigo, uinput). uinput works on Wayland and the console
+    /// but needs write access to /dev/uinput
+    #[arg(long, default_value_t = InputBackend::Enigo)]
+    pub input: InputBackend,
+
+    /// What to do when sleep prevention fails (fail, warn, retry)
+    #[arg(long, default_value_t = FailureMode::Fail)]
+    pub on_failure: FailureMode,
+
+    /// Retries before giving up with --on-failure retry
+    #[arg(long, default_value_t = DEFAULT_MAX_RETRIES)]
+    pub max_retries: u32,
+
+    /// Skip cycles while the user has touched the keyboard or mouse within this many
+    /// seconds. 0 simulates activity every cycle
+    #[arg(long, default_value_t = 0)]
+    pub idle_threshold: u64,
+
+    /// Where to read the user's idle time (auto, x11, logind, input)
+    #[arg(long, default_value_t = IdleSource::Auto)]
+    pub idle_source: IdleSource,
+
+    /// Only run during these hours, like "Mon-Fri 09:00-18:00; break 12:00-13:00"
+    #[arg(long)]
+    pub schedule: Option<Schedule>,
+
+    /// Read the schedule from a file with one entry per line
+    #[arg(long, value_name = "PATH")]
+    pub schedule_file: Option<PathBuf>,
+
+    /// Only run during events of this .ics file, or of the .ics files in this directory
+    #[arg(long, value_name = "PATH")]
+    pub calendar: Option<PathBuf>,
+
+    /// Only count calendar events whose summary contains this text, like "Focus"
+    #[arg(long, value_name = "TEXT", requires = "calendar")]
+    pub calendar_filter: Option<String>,
+
+    /// File of holidays and days off (YYYY-MM-DD, MM-DD for every year, or ranges with ..)
+    #[arg(long, value_name = "PATH")]
+    pub holidays: Option<PathBuf>,
+
+    /// What to do on a holiday (pause, exit)
+    #[arg(long, default_value_t = HolidayAction::Pause, requires = "holidays")]
+    pub on_holiday: HolidayAction,
+
+    /// Stop after running this long, like 2h30m, 90m or 45s
+    #[arg(long)]
+    pub duration: Option<RunDuration>,
+
+    /// Stop at this local time, like 17:45 (tomorrow if it has passed today)
+    #[arg(long, value_name = "HH:MM")]
+    pub until: Option<StopTime>,
+
+    /// Serve a JSON API for status and control, and Prometheus metrics, on this
+    /// address, 127.0.0.1:7878 if none is given
+    #[arg(long, value_name = "ADDR", num_args = 0..=1, default_missing_value = http::DEFAULT_ADDR)]
+    pub http: Option<SocketAddr>,
+
+    /// Bearer token the HTTP API requires; needed to listen beyond loopback
+    #[arg(long, value_name = "TOKEN", requires = "http")]
+    pub http_token: Option<String>,
+
+    /// Publish io.github.guinetik.BusyCrab on the session bus for desktop widgets and
+    /// scripts (Linux only)
+    #[arg(long, default_value_t = false)]
+    pub dbus: bool,
+
+    /// Control socket to listen on, or to reach the running BusyCrab through.
+    /// Defaults to busycrab.sock in $XDG_RUNTIME_DIR (Unix only)
+    #[arg(long, value_name = "PATH", global = true)]
+    pub socket: Option<PathBuf>,
+
+    /// Config file with defaults and profiles. Defaults to busycrab/config.toml in
+    /// $XDG_CONFIG_HOME or ~/.config. Flags can also be set through BUSYCRAB_*
+    /// environment variables, like BUSYCRAB_INTERVAL
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Use the settings of this profile from the config file, like meeting
+    #[arg(long, value_name = "NAME", global = true)]
+    pub profile: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Args {
+    /// Parses the command line, filling in flags it leaves out from `BUSYCRAB_*`
+    /// environment variables, the profile and the config file. Exits on command line
+    /// errors and `--help` like `parse`.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the args.
+    /// - `Err(Error::Config)` if the config file, profile or environment is invalid.
+    pub fn load() -> Result<Self> {
+        config::load_args(std::env::args_os(), |name| std::env::var(name).ok())
+    }
+}
+
+/// Commands that run instead of the activity loop
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List sleep prevention backends and whether they're available
+    Backends,
+    /// Show what the running BusyCrab is doing
+    Status,
+    /// Pause the running BusyCrab, letting the system sleep
+    Pause,
+    /// Resume the running BusyCrab after a pause
+    Resume,
+    /// Shut the running BusyCrab down
+    Stop,
+}
+
+impl Command {
+    /// Gets the control socket request for commands that talk to a running BusyCrab.
+    pub fn control_request(&self) -> Option<&'static str> {
+        match self {
+            Command::Backends => None,
+            Command::Status => Some("status"),
+            Command::Pause => Some("pause"),
+            Command::Resume => Some("resume"),
+            Command::Stop => Some("stop"),
+        }
+    }
 }
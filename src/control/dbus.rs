@@ -0,0 +1,178 @@
+//! D-Bus service: publishes a running BusyCrab on the session bus, for desktop
+//! widgets and scripts.
+//!
+//! The `io.github.guinetik.BusyCrab` interface, at `/io/github/guinetik/BusyCrab`
+//! under the bus name of the same name, has:
+//!
+//! - **methods**: `Pause`, `Resume` and `Stop`
+//! - **properties**: `Active` (keeping the system awake right now), `CycleCount`
+//!   (activity cycles that simulated activity) and `Interval` (seconds until the
+//!   next cycle, as drawn for the current wait), with change notifications
+//! - **signals**: `ActivityPerformed(t cycle_count)` after each simulated activity
+//!
+//! ```text
+//! busctl --user call io.github.guinetik.BusyCrab /io/github/guinetik/BusyCrab \
+//!     io.github.guinetik.BusyCrab Pause
+//! ```
+
+use std::sync::Mutex;
+use zbus::blocking::connection::Builder;
+use zbus::blocking::Connection;
+use zbus::object_server::SignalEmitter;
+
+use super::{ControlHandle, CycleOutcome};
+use crate::error::{Error, Result};
+
+/// Well-known bus name the service owns, also the interface name.
+pub const SERVICE_NAME: &str = "io.github.guinetik.BusyCrab";
+
+/// Path of the BusyCrab object.
+pub const OBJECT_PATH: &str = "/io/github/guinetik/BusyCrab";
+
+/// The bus to publish the service on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bus {
+    /// The user's session bus
+    Session,
+    /// The bus at this address, such as a private `dbus-daemon`
+    Address(String),
+}
+
+/// Property values, as `(Active, CycleCount, Interval)`.
+type Properties = (bool, u64, u64);
+
+/// The object published at `OBJECT_PATH`.
+struct BusyCrabObject {
+    handle: ControlHandle,
+    /// Property values last announced
+    announced: Mutex<Properties>,
+}
+
+impl BusyCrabObject {
+    /// Reads the property values.
+    fn properties(&self) -> Properties {
+        let status = self.handle.status();
+        (status.is_active(), status.cycles, status.interval_secs)
+    }
+
+    /// Sends `PropertiesChanged` for properties that changed since the last call.
+    async fn announce_changes(&self, emitter: &SignalEmitter<'_>) -> zbus::Result<()> {
+        let current = self.properties();
+        let previous = std::mem::replace(
+            &mut *self.announced.lock().unwrap_or_else(|err| err.into_inner()),
+            current,
+        );
+        if current.0 != previous.0 {
+            self.active_changed(emitter).await?;
+        }
+        if current.1 != previous.1 {
+            self.cycle_count_changed(emitter).await?;
+        }
+        if current.2 != previous.2 {
+            self.interval_changed(emitter).await?;
+        }
+        Ok(())
+    }
+}
+
+#[zbus::interface(name = "io.github.guinetik.BusyCrab")]
+impl BusyCrabObject {
+    /// Stops simulating activity and lets the system sleep.
+    async fn pause(&self, #[zbus(signal_emitter)] emitter: SignalEmitter<'_>) {
+        self.handle.pause();
+        let _ = self.announce_changes(&emitter).await;
+    }
+
+    /// Picks up again after a pause.
+    async fn resume(&self, #[zbus(signal_emitter)] emitter: SignalEmitter<'_>) {
+        self.handle.resume();
+        let _ = self.announce_changes(&emitter).await;
+    }
+
+    /// Shuts BusyCrab down.
+    async fn stop(&self, #[zbus(signal_emitter)] emitter: SignalEmitter<'_>) {
+        self.handle.stop();
+        let _ = self.announce_changes(&emitter).await;
+    }
+
+    /// Whether BusyCrab is keeping the system awake right now.
+    #[zbus(property)]
+    fn active(&self) -> bool {
+        self.handle.status().is_active()
+    }
+
+    /// Activity cycles that simulated activity.
+    #[zbus(property)]
+    fn cycle_count(&self) -> u64 {
+        self.handle.status().cycles
+    }
+
+    /// Seconds between cycles, as drawn for the current wait.
+    #[zbus(property)]
+    fn interval(&self) -> u64 {
+        self.handle.status().interval_secs
+    }
+
+    /// Sent after each cycle that simulated activity.
+    #[zbus(signal)]
+    async fn activity_performed(emitter: &SignalEmitter<'_>, cycle_count: u64) -> zbus::Result<()>;
+}
+
+/// The published service. Dropping it leaves the bus.
+pub struct DbusService {
+    connection: Connection,
+}
+
+impl DbusService {
+    /// Publishes the service on `bus`.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the published service.
+    /// - `Err(Error::Control)` if the bus can't be reached or another BusyCrab owns
+    ///   `SERVICE_NAME`.
+    pub fn start(bus: &Bus, handle: ControlHandle) -> Result<Self> {
+        let status = handle.status();
+        let object = BusyCrabObject {
+            announced: Mutex::new((status.is_active(), status.cycles, status.interval_secs)),
+            handle,
+        };
+
+        let builder = match bus {
+            Bus::Session => Builder::session(),
+            Bus::Address(address) => Builder::address(address.as_str()),
+        };
+        let connection = builder
+            .and_then(|builder| builder.name(SERVICE_NAME))
+            .map(|builder| {
+                builder
+                    .allow_name_replacements(false)
+                    .replace_existing_names(false)
+            })
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, object))
+            .and_then(|builder| builder.build())
+            .map_err(|err| {
+                Error::control_source(format!("can't publish {} on D-Bus", SERVICE_NAME), err)
+            })?;
+
+        Ok(Self { connection })
+    }
+
+    /// Announces what a cycle did: `ActivityPerformed` if it simulated activity, and
+    /// `PropertiesChanged` for properties that changed. A bus that went away is ignored.
+    pub fn announce_cycle(&self, outcome: CycleOutcome, cycle_count: u64) {
+        let Ok(iface_ref) = self
+            .connection
+            .object_server()
+            .interface::<_, BusyCrabObject>(OBJECT_PATH)
+        else {
+            return;
+        };
+        let emitter = iface_ref.signal_emitter();
+        if outcome == CycleOutcome::Activity {
+            let _ = zbus::block_on(BusyCrabObject::activity_performed(emitter, cycle_count));
+        }
+
+        let _ = zbus::block_on(iface_ref.get().announce_changes(emitter));
+    }
+}
@@ -0,0 +1,300 @@
+//! HTTP API: an optional JSON API for dashboards and scripts, served by a running
+//! BusyCrab.
+//!
+//! | Request        | Response                                   |
+//! |----------------|--------------------------------------------|
+//! | `GET /status`  | The `Status`                               |
+//! | `POST /pause`  | The `Status` after pausing                 |
+//! | `POST /resume` | The `Status` after resuming                |
+//! | `POST /stop`   | The `Status` after asking BusyCrab to stop |
+//! | `GET /config`  | The `Settings`                             |
+//! | `PUT /config`  | The `Settings` after a `SettingsUpdate`    |
+//! | `GET /metrics` | The `Metrics`, for Prometheus              |
+//!
+//! ```text
+//! > PUT /config
+//! > {"interval":"45..90","motion":"clock"}
+//! < 200 {"interval":"45..90","wiggle":"3","motion":"clock"}
+//! ```
+//!
+//! Everything but `/metrics` answers with JSON. Failed requests get a 4xx status
+//! and `{"error":"..."}`. With a token, every
+//! request needs an `Authorization: Bearer TOKEN` header. Without one, the API only
+//! listens on loopback addresses, and only answers requests whose `Host` is a
+//! loopback name and that carry no `Origin` header, so web pages open in a browser
+//! can't reach it through simple requests or DNS rebinding.
+
+use serde::Serialize;
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use super::{ControlCommand, ControlHandle, SettingsUpdate};
+use crate::error::{Error, Result};
+use crate::metrics;
+
+/// Address `--http` listens on when none is given.
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+
+/// Largest request body read, in bytes.
+const MAX_BODY: u64 = 64 * 1024;
+
+/// Serves the HTTP API on a background thread until dropped.
+pub struct HttpServer {
+    addr: SocketAddr,
+    server: Arc<Server>,
+    stopping: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HttpServer {
+    /// Listens on `addr`. Port 0 picks a free port; `addr()` tells which.
+    ///
+    /// * `token` - Bearer token every request must carry; `None` lets any request in
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the running server.
+    /// - `Err(Error::Config)` if `addr` isn't a loopback address and there's no token.
+    /// - `Err(Error::Control)` if `addr` can't be listened on.
+    pub fn start(addr: SocketAddr, token: Option<String>, handle: ControlHandle) -> Result<Self> {
+        if !addr.ip().is_loopback() && token.is_none() {
+            return Err(Error::config(format!(
+                "the HTTP API needs a token to listen on {}, which isn't a loopback address",
+                addr
+            )));
+        }
+        let server = Server::http(addr)
+            .map_err(|err| Error::control_source(format!("can't listen on {}", addr), err))?;
+        let addr = server.server_addr().to_ip().unwrap_or(addr);
+
+        let server = Arc::new(server);
+        let server_clone = server.clone();
+        let stopping = Arc::new(AtomicBool::new(false));
+        let stopping_clone = stopping.clone();
+        let thread = thread::spawn(move || loop {
+            match server_clone.recv() {
+                Ok(request) => respond(request, token.as_deref(), &handle),
+                Err(_) if stopping_clone.load(Ordering::SeqCst) => break,
+                Err(_) => {}
+            }
+        });
+
+        Ok(Self {
+            addr,
+            server,
+            stopping,
+            thread: Some(thread),
+        })
+    }
+
+    /// Gets the address the API listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// What to answer a request with.
+struct Reply {
+    code: u16,
+    body: String,
+    content_type: &'static str,
+    /// Methods the path takes, for 405 replies
+    allow: Option<&'static str>,
+}
+
+impl Reply {
+    /// Creates a 200 reply with `value` as JSON.
+    fn json(value: &impl Serialize) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self {
+                code: 200,
+                body,
+                content_type: "application/json",
+                allow: None,
+            },
+            Err(err) => Self::error(500, &err.to_string()),
+        }
+    }
+
+    /// Creates a reply for a failed request.
+    fn error(code: u16, message: &str) -> Self {
+        Self {
+            code,
+            body: serde_json::json!({ "error": message }).to_string(),
+            content_type: "application/json",
+            allow: None,
+        }
+    }
+
+    /// Creates a 200 reply with the metrics.
+    fn metrics(handle: &ControlHandle) -> Self {
+        Self {
+            code: 200,
+            body: handle.metrics().render(handle.is_paused()),
+            content_type: metrics::CONTENT_TYPE,
+            allow: None,
+        }
+    }
+}
+
+/// Answers one request, ignoring clients that hang up.
+fn respond(mut request: Request, token: Option<&str>, handle: &ControlHandle) {
+    let reply = if !is_authorized(&request, token) {
+        Reply::error(401, "missing or wrong bearer token")
+    } else if token.is_none() && !is_local(&request) {
+        Reply::error(
+            403,
+            "requests from browsers or non-loopback hosts aren't allowed",
+        )
+    } else {
+        let mut body = String::new();
+        let read = request
+            .as_reader()
+            .take(MAX_BODY + 1)
+            .read_to_string(&mut body);
+        match read {
+            Err(_) => Reply::error(400, "request body isn't UTF-8 text"),
+            Ok(_) if body.len() as u64 > MAX_BODY => Reply::error(413, "request body too large"),
+            Ok(_) => {
+                let path = request.url().split('?').next().unwrap_or_default();
+                route(request.method(), path, &body, handle)
+            }
+        }
+    };
+
+    let mut response = Response::from_string(reply.body)
+        .with_status_code(reply.code)
+        .with_header(header("Content-Type", reply.content_type));
+    if reply.code == 401 {
+        response = response.with_header(header("WWW-Authenticate", "Bearer"));
+    }
+    if let Some(allow) = reply.allow {
+        response = response.with_header(header("Allow", allow));
+    }
+    let _ = request.respond(response);
+}
+
+/// Checks the request carries `token`, if there is one.
+fn is_authorized(request: &Request, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    request
+        .headers()
+        .iter()
+        .filter(|header| header.field.equiv("Authorization"))
+        .filter_map(|header| header.value.as_str().split_once(' '))
+        .any(|(scheme, given)| {
+            scheme.eq_ignore_ascii_case("Bearer") && tokens_match(given.trim(), token)
+        })
+}
+
+/// Checks a request has a loopback `Host` and no `Origin`, as requests from local
+/// tools do. Browsers send `Origin` with cross-origin requests, and a page that
+/// rebinds its own name to 127.0.0.1 still sends that name as the `Host`.
+fn is_local(request: &Request) -> bool {
+    header_value(request, "Origin").is_none()
+        && header_value(request, "Host").is_some_and(is_loopback_host)
+}
+
+/// Gets the value of the first `name` header.
+fn header_value<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+/// Checks `host`, with or without a port, is `localhost` or a loopback IP literal.
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => match rest.split_once(']') {
+            Some((name, _)) => name,
+            None => return false,
+        },
+        None => match host.rsplit_once(':') {
+            Some((name, port)) if port.parse::<u16>().is_ok() => name,
+            _ => host,
+        },
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Compares tokens in time that doesn't depend on where they differ.
+fn tokens_match(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Carries out the request for `path`.
+fn route(method: &Method, path: &str, body: &str, handle: &ControlHandle) -> Reply {
+    let command = match path {
+        "/pause" => Some(ControlCommand::Pause),
+        "/resume" => Some(ControlCommand::Resume),
+        "/stop" => Some(ControlCommand::Stop),
+        _ => None,
+    };
+    if let Some(command) = command {
+        if *method != Method::Post {
+            return not_allowed("POST");
+        }
+        handle.apply(command);
+        return Reply::json(&handle.status());
+    }
+
+    match (path, method) {
+        ("/status", Method::Get) => Reply::json(&handle.status()),
+        ("/status", _) => not_allowed("GET"),
+        ("/config", Method::Get) => Reply::json(&handle.settings()),
+        ("/config", Method::Put) => update_settings(body, handle),
+        ("/config", _) => not_allowed("GET, PUT"),
+        ("/metrics", Method::Get) => Reply::metrics(handle),
+        ("/metrics", _) => not_allowed("GET"),
+        _ => Reply::error(404, &format!("no such endpoint: {}", path)),
+    }
+}
+
+/// Applies the `SettingsUpdate` in `body`.
+fn update_settings(body: &str, handle: &ControlHandle) -> Reply {
+    let update = match serde_json::from_str::<SettingsUpdate>(body) {
+        Ok(update) => update,
+        Err(err) => return Reply::error(400, &format!("invalid settings: {}", err)),
+    };
+    match handle.update_settings(update) {
+        Ok(settings) => Reply::json(&settings),
+        Err(err) => Reply::error(400, &err.to_string()),
+    }
+}
+
+/// Creates a 405 reply for a path that only takes `allow`.
+fn not_allowed(allow: &'static str) -> Reply {
+    Reply {
+        allow: Some(allow),
+        ..Reply::error(405, &format!("method not allowed (use {})", allow))
+    }
+}
+
+/// Creates a header from names and values known to be valid.
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field, value).expect("valid header")
+}
@@ -0,0 +1,170 @@
+//! Pausing from the keyboard when BusyCrab runs in a terminal.
+//!
+//! Key presses have to arrive one at a time, without Enter, so on Unix the terminal
+//! stops buffering lines and echoing input while `KeyListener` is alive. Ctrl+C
+//! keeps working as before. `SIGTERM` and `SIGHUP` go through the same graceful
+//! shutdown as Ctrl+C, which drops the listener, so the terminal is put back then
+//! too.
+
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::{ControlCommand, ControlHandle};
+
+/// How long the reader waits for a key before checking if it should stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Checks if `key` toggles pausing: `p` or space.
+pub fn is_toggle_key(key: u8) -> bool {
+    matches!(key, b'p' | b'P' | b' ')
+}
+
+/// Reads key presses on a background thread while BusyCrab runs. Dropping it stops
+/// the thread, so no input is taken from stdin afterwards, and puts the terminal
+/// back the way it was.
+pub struct KeyListener {
+    #[cfg(unix)]
+    original: libc::termios,
+    /// Tells the reader thread to stop
+    stopping: Arc<AtomicBool>,
+    /// Reader thread, until it's joined
+    thread: Option<JoinHandle<()>>,
+}
+
+impl KeyListener {
+    /// Starts listening if stdin is a terminal.
+    ///
+    /// ### Returns
+    ///
+    /// - `Some` while keys are being read.
+    /// - `None` if stdin isn't a terminal or can't be switched to reading single keys.
+    pub fn start(handle: ControlHandle) -> Option<Self> {
+        if !std::io::stdin().is_terminal() {
+            return None;
+        }
+        let mut listener = Self::enable()?;
+        let stopping = listener.stopping.clone();
+        listener.thread = Some(thread::spawn(move || {
+            while handle.is_running() && !stopping.load(Ordering::SeqCst) {
+                match read_key(POLL_INTERVAL) {
+                    Ok(Some(key)) if is_toggle_key(key) => {
+                        handle.apply(ControlCommand::TogglePause)
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+        }));
+        Some(listener)
+    }
+
+    /// Stops line buffering and echo on the terminal.
+    #[cfg(unix)]
+    fn enable() -> Option<Self> {
+        // SAFETY: termios is plain data, filled in by tcgetattr before it's used.
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return None;
+        }
+        let mut single_keys = original;
+        single_keys.c_lflag &= !(libc::ICANON | libc::ECHO);
+        single_keys.c_cc[libc::VMIN] = 1;
+        single_keys.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &single_keys) } != 0 {
+            return None;
+        }
+        Some(Self {
+            original,
+            stopping: Arc::new(AtomicBool::new(false)),
+            thread: None,
+        })
+    }
+
+    /// The Windows console hands out single keys through `_getch` as it is.
+    #[cfg(not(unix))]
+    fn enable() -> Option<Self> {
+        cfg!(windows).then(|| Self {
+            stopping: Arc::new(AtomicBool::new(false)),
+            thread: None,
+        })
+    }
+}
+
+impl Drop for KeyListener {
+    fn drop(&mut self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        #[cfg(unix)]
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+/// Waits up to `timeout` for a key.
+///
+/// ### Returns
+///
+/// - `Ok` with the key, or `None` if none was pressed in time.
+/// - `Err` once stdin is closed or can't be read.
+#[cfg(unix)]
+fn read_key(timeout: Duration) -> io::Result<Option<u8>> {
+    let mut stdin = libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout = libc::c_int::try_from(timeout.as_millis()).unwrap_or(libc::c_int::MAX);
+    // SAFETY: poll only touches the one pollfd passed in.
+    match unsafe { libc::poll(&mut stdin, 1, timeout) } {
+        0 => return Ok(None),
+        ready if ready < 0 => return interrupted_or_error(),
+        _ => {}
+    }
+
+    // Read the descriptor directly: `io::stdin()` buffers, and would keep keys
+    // poll can't see
+    let mut key = 0u8;
+    // SAFETY: reads at most one byte into `key`.
+    match unsafe { libc::read(libc::STDIN_FILENO, (&mut key as *mut u8).cast(), 1) } {
+        1 => Ok(Some(key)),
+        0 => Err(io::ErrorKind::UnexpectedEof.into()),
+        _ => interrupted_or_error(),
+    }
+}
+
+/// Turns the last OS error into no key if a signal cut the wait short.
+#[cfg(unix)]
+fn interrupted_or_error() -> io::Result<Option<u8>> {
+    let err = io::Error::last_os_error();
+    match err.kind() {
+        io::ErrorKind::Interrupted => Ok(None),
+        _ => Err(err),
+    }
+}
+
+/// Waits up to `timeout` for a key.
+#[cfg(windows)]
+fn read_key(timeout: Duration) -> io::Result<Option<u8>> {
+    extern "C" {
+        fn _kbhit() -> std::os::raw::c_int;
+        fn _getch() -> std::os::raw::c_int;
+    }
+    // SAFETY: _kbhit and _getch take no arguments and only read from the console.
+    if unsafe { _kbhit() } == 0 {
+        thread::sleep(timeout);
+        return Ok(None);
+    }
+    let key = unsafe { _getch() };
+    Ok(Some(u8::try_from(key).unwrap_or(0)))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn read_key(_timeout: Duration) -> io::Result<Option<u8>> {
+    Err(io::ErrorKind::Unsupported.into())
+}
@@ -0,0 +1,187 @@
+//! # Control
+//!
+//! Pausing, resuming and stopping a running BusyCrab without restarting it.
+//! While paused, the activity loop keeps going but releases sleep prevention and
+//! doesn't simulate activity.
+//!
+//! - **signals**: `SIGUSR1` pauses and `SIGUSR2` resumes (Unix only)
+//! - **keys**: Pressing `p` or space in the terminal toggles pausing
+//! - **socket**: `busycrab status|pause|resume|stop` through a Unix domain socket
+//!   (Unix only)
+//! - **http**: A JSON API on localhost that can also change `Settings`
+//! - **dbus**: An `io.github.guinetik.BusyCrab` service on the session bus (Linux
+//!   only)
+//!
+//! Controls act on a `ControlHandle`, which shares its state with `BusyCrab` and
+//! reports it as a `Status`.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::error::Result;
+use crate::metrics::Metrics;
+
+#[cfg(target_os = "linux")]
+pub mod dbus;
+pub mod http;
+pub mod keys;
+#[cfg(unix)]
+pub mod signals;
+#[cfg(unix)]
+pub mod socket;
+pub mod settings;
+pub mod status;
+
+pub use settings::{Settings, SettingsUpdate};
+pub(crate) use status::RunState;
+pub use status::{CycleOutcome, Status};
+
+/// A request to change a running BusyCrab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Stop simulating activity and let the system sleep
+    Pause,
+    /// Pick up again after a pause
+    Resume,
+    /// Pause if running, resume if paused
+    TogglePause,
+    /// Shut down, like Ctrl+C
+    Stop,
+}
+
+impl fmt::Display for ControlCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            ControlCommand::Pause => "pause",
+            ControlCommand::Resume => "resume",
+            ControlCommand::TogglePause => "toggle",
+            ControlCommand::Stop => "stop",
+        })
+    }
+}
+
+impl FromStr for ControlCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pause" => Ok(ControlCommand::Pause),
+            "resume" => Ok(ControlCommand::Resume),
+            "toggle" => Ok(ControlCommand::TogglePause),
+            "stop" => Ok(ControlCommand::Stop),
+            _ => Err(format!(
+                "unknown command '{}' (expected pause, resume, toggle or stop)",
+                s
+            )),
+        }
+    }
+}
+
+/// Steers a running BusyCrab from another thread. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct ControlHandle {
+    running: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    state: Arc<Mutex<RunState>>,
+    metrics: Arc<Metrics>,
+}
+
+impl ControlHandle {
+    /// Creates a handle over BusyCrab's running and paused flags, with nothing to
+    /// report yet.
+    pub fn new(running: Arc<AtomicBool>, paused: Arc<AtomicBool>) -> Self {
+        Self::with_state(running, paused, Arc::default(), Arc::default())
+    }
+
+    /// Creates a handle that also reports the state and metrics the activity loop
+    /// keeps.
+    pub(crate) fn with_state(
+        running: Arc<AtomicBool>,
+        paused: Arc<AtomicBool>,
+        state: Arc<Mutex<RunState>>,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        Self {
+            running,
+            paused,
+            state,
+            metrics,
+        }
+    }
+
+    /// Carries out `command`.
+    pub fn apply(&self, command: ControlCommand) {
+        match command {
+            ControlCommand::Pause => self.paused.store(true, Ordering::SeqCst),
+            ControlCommand::Resume => self.paused.store(false, Ordering::SeqCst),
+            ControlCommand::TogglePause => {
+                self.paused.fetch_xor(true, Ordering::SeqCst);
+            }
+            ControlCommand::Stop => self.running.store(false, Ordering::SeqCst),
+        }
+    }
+
+    /// Pauses BusyCrab.
+    pub fn pause(&self) {
+        self.apply(ControlCommand::Pause);
+    }
+
+    /// Resumes BusyCrab.
+    pub fn resume(&self) {
+        self.apply(ControlCommand::Resume);
+    }
+
+    /// Shuts BusyCrab down.
+    pub fn stop(&self) {
+        self.apply(ControlCommand::Stop);
+    }
+
+    /// Checks if BusyCrab is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Checks if BusyCrab is still running, paused or not.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Takes a snapshot of what BusyCrab is doing.
+    pub fn status(&self) -> Status {
+        Status::capture(&self.lock_state(), self.is_running(), self.is_paused())
+    }
+
+    /// Gets the counters BusyCrab keeps about what it did.
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Gets the settings, including changes the activity loop hasn't applied yet.
+    pub fn settings(&self) -> Settings {
+        self.lock_state().upcoming_settings()
+    }
+
+    /// Changes settings. The activity loop applies the change within moments; until
+    /// then, later changes add to it.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the settings once the change is applied.
+    /// - `Err(Error::Config)` if the update is invalid, leaving the settings alone.
+    pub fn update_settings(&self, update: SettingsUpdate) -> Result<Settings> {
+        update.validate()?;
+        let mut state = self.lock_state();
+        state.pending_settings = Some(match state.pending_settings.take() {
+            Some(pending) => pending.merge(update),
+            None => update,
+        });
+        Ok(state.upcoming_settings())
+    }
+
+    /// Locks the shared state, even if a thread panicked while holding it.
+    fn lock_state(&self) -> MutexGuard<'_, RunState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
@@ -0,0 +1,94 @@
+//! Settings that can be changed while BusyCrab runs.
+//!
+//! Controls don't change BusyCrab directly. They leave a `SettingsUpdate` in the
+//! shared state, and the activity loop applies it as soon as it notices, without
+//! waiting for the current interval to run out.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::motion;
+use crate::variation::ValueRange;
+
+/// Settings a running BusyCrab can change without restarting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    /// Seconds between activities to draw from
+    pub interval: ValueRange<u64>,
+    /// Wiggle distances to draw from, in pixels
+    pub wiggle: ValueRange<i32>,
+    /// Animation, one of `motion::NAMES`
+    pub motion: String,
+}
+
+impl Default for Settings {
+    /// BusyCrab's defaults, without an animation.
+    fn default() -> Self {
+        Self {
+            interval: ValueRange::fixed(60),
+            wiggle: ValueRange::fixed(3),
+            motion: "none".to_string(),
+        }
+    }
+}
+
+/// Changes to `Settings`. Fields that are left out stay as they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SettingsUpdate {
+    /// New interval, like `90` or `"45..90"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<ValueRange<u64>>,
+    /// New wiggle distance, like `5` or `"1..4"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wiggle: Option<ValueRange<i32>>,
+    /// New animation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motion: Option<String>,
+}
+
+impl SettingsUpdate {
+    /// Checks that the new values can be used.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok(())` if they can.
+    /// - `Err(Error::Config)` for an interval of zero seconds or an unknown motion.
+    pub fn validate(&self) -> Result<()> {
+        if self.interval.is_some_and(|interval| interval.min == 0) {
+            return Err(Error::config("interval must be at least 1 second"));
+        }
+        if let Some(name) = &self.motion {
+            if !motion::NAMES.contains(&name.to_lowercase().as_str()) {
+                return Err(Error::config(format!(
+                    "unknown motion '{}' (expected {})",
+                    name,
+                    motion::NAMES.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Combines this update with a `later` one, whose fields win.
+    pub fn merge(self, later: SettingsUpdate) -> Self {
+        Self {
+            interval: later.interval.or(self.interval),
+            wiggle: later.wiggle.or(self.wiggle),
+            motion: later.motion.or(self.motion),
+        }
+    }
+
+    /// Changes `settings` by this update.
+    pub fn apply_to(&self, settings: &mut Settings) {
+        if let Some(interval) = self.interval {
+            settings.interval = interval;
+        }
+        if let Some(wiggle) = self.wiggle {
+            settings.wiggle = wiggle;
+        }
+        if let Some(motion) = &self.motion {
+            settings.motion = motion.to_lowercase();
+        }
+    }
+}
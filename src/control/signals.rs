@@ -0,0 +1,60 @@
+//! `SIGUSR1` and `SIGUSR2` handling.
+//!
+//! Signal handlers can't do much safely, so the handler only records the signal
+//! and the activity loop picks it up with `take_pending` while it waits.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use super::ControlCommand;
+use crate::error::{Error, Result};
+
+/// No signal since the last `take_pending`.
+const NONE: u8 = 0;
+/// `SIGUSR1` arrived last.
+const PAUSE: u8 = 1;
+/// `SIGUSR2` arrived last.
+const RESUME: u8 = 2;
+
+/// The last signal that arrived and wasn't taken yet.
+static PENDING: AtomicU8 = AtomicU8::new(NONE);
+
+extern "C" fn on_signal(signal: libc::c_int) {
+    let command = if signal == libc::SIGUSR1 {
+        PAUSE
+    } else {
+        RESUME
+    };
+    PENDING.store(command, Ordering::SeqCst);
+}
+
+/// Makes `SIGUSR1` request a pause and `SIGUSR2` a resume, instead of ending the
+/// process. Installing the handlers again is harmless.
+///
+/// ### Returns
+///
+/// - `Ok(())` once both handlers are installed.
+/// - `Err(Error::Unsupported)` if the handlers can't be installed.
+pub fn install() -> Result<()> {
+    for signal in [libc::SIGUSR1, libc::SIGUSR2] {
+        let handler = on_signal as extern "C" fn(libc::c_int) as *const () as libc::sighandler_t;
+        // SAFETY: The handler only stores to an atomic, which is async-signal-safe.
+        let previous = unsafe { libc::signal(signal, handler) };
+        if previous == libc::SIG_ERR {
+            return Err(Error::unsupported(format!(
+                "can't handle signal {}: {}",
+                signal,
+                std::io::Error::last_os_error()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Takes the command of the last signal that arrived since the previous call, if any.
+pub fn take_pending() -> Option<ControlCommand> {
+    match PENDING.swap(NONE, Ordering::SeqCst) {
+        PAUSE => Some(ControlCommand::Pause),
+        RESUME => Some(ControlCommand::Resume),
+        _ => None,
+    }
+}
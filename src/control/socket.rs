@@ -0,0 +1,286 @@
+//! Control socket: a Unix domain socket a running BusyCrab listens on, so that
+//! `busycrab status`, `pause`, `resume` and `stop` can reach it.
+//!
+//! The protocol is one JSON object per line. Requests name a command, and every
+//! request gets one response with the status after the command:
+//!
+//! ```text
+//! > {"command":"pause"}
+//! < {"ok":true,"status":{"pid":4242,"state":"paused",...}}
+//! > {"command":"dance"}
+//! < {"ok":false,"error":"unknown command 'dance' (expected status, pause, resume, toggle or stop)"}
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::{ControlCommand, ControlHandle, Status};
+use crate::error::{Error, Result};
+
+/// How long either side waits for the other before giving up on a connection.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A request sent to the control socket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Request {
+    /// `status` or a `ControlCommand`
+    pub command: String,
+}
+
+/// The answer to a `Request`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Response {
+    /// Whether the command was carried out
+    pub ok: bool,
+    /// Status after the command, when it was carried out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    /// Why the command failed, when it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Response {
+    /// Creates a response for a command that failed.
+    fn failure(error: String) -> Self {
+        Self {
+            ok: false,
+            status: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Gets the socket path for the current user: `$XDG_RUNTIME_DIR/busycrab.sock`, or
+/// a socket in a private per-user directory under the temp directory without
+/// `XDG_RUNTIME_DIR`.
+pub fn default_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("busycrab.sock"),
+        _ => fallback_dir().join("busycrab.sock"),
+    }
+}
+
+/// Gets the per-user directory the socket lives in without `XDG_RUNTIME_DIR`.
+fn fallback_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("busycrab-{}", current_uid()))
+}
+
+fn current_uid() -> u32 {
+    // SAFETY: getuid can't fail.
+    unsafe { libc::getuid() }
+}
+
+/// Creates `dir` with mode 0700, or checks that the existing one belongs to this
+/// user and nobody else can get into it. The temp directory is world-writable,
+/// so another user could have created it first.
+fn ensure_private_dir(dir: &Path) -> Result<()> {
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => return Ok(()),
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+        Err(err) => {
+            return Err(Error::control_source(
+                format!("can't create {}", dir.display()),
+                err,
+            ))
+        }
+    }
+    let metadata = std::fs::symlink_metadata(dir)
+        .map_err(|err| Error::control_source(format!("can't inspect {}", dir.display()), err))?;
+    if !metadata.is_dir()
+        || metadata.uid() != current_uid()
+        || metadata.permissions().mode() & 0o077 != 0
+    {
+        return Err(Error::control(format!(
+            "{} is not a private directory owned by this user",
+            dir.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Answers one request line.
+pub fn handle_line(line: &str, handle: &ControlHandle) -> Response {
+    let command = match serde_json::from_str::<Request>(line) {
+        Ok(request) => request.command,
+        Err(err) => return Response::failure(format!("invalid request: {}", err)),
+    };
+    if !command.eq_ignore_ascii_case("status") {
+        match command.parse::<ControlCommand>() {
+            Ok(command) => handle.apply(command),
+            Err(_) => {
+                return Response::failure(format!(
+                    "unknown command '{}' (expected status, pause, resume, toggle or stop)",
+                    command
+                ))
+            }
+        }
+    }
+    Response {
+        ok: true,
+        status: Some(handle.status()),
+        error: None,
+    }
+}
+
+/// Serves the control socket on a background thread until dropped, then removes
+/// the socket file.
+pub struct ControlServer {
+    path: PathBuf,
+    stopping: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ControlServer {
+    /// Listens on `path`, replacing a socket left behind by a BusyCrab that didn't
+    /// shut down cleanly. Anything at `path` that isn't a socket is left alone.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the running server.
+    /// - `Err(Error::Control)` if another BusyCrab is already listening on `path`,
+    ///   something other than a socket is in the way, or the socket can't be
+    ///   created.
+    pub fn start(path: impl Into<PathBuf>, handle: ControlHandle) -> Result<Self> {
+        let path = path.into();
+        if path.parent() == Some(fallback_dir().as_path()) {
+            ensure_private_dir(&fallback_dir())?;
+        }
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            if !metadata.file_type().is_socket() {
+                return Err(Error::control(format!(
+                    "{} exists and is not a socket",
+                    path.display()
+                )));
+            }
+            if UnixStream::connect(&path).is_ok() {
+                return Err(Error::control(format!(
+                    "another BusyCrab is already listening on {}",
+                    path.display()
+                )));
+            }
+            let _ = std::fs::remove_file(&path);
+        }
+        let listener = UnixListener::bind(&path).map_err(|err| {
+            Error::control_source(format!("can't listen on {}", path.display()), err)
+        })?;
+
+        let stopping = Arc::new(AtomicBool::new(false));
+        let stopping_clone = stopping.clone();
+        let thread = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopping_clone.load(Ordering::SeqCst) {
+                    break;
+                }
+                // Each connection gets its own thread, so a client that goes quiet
+                // doesn't hold up the others
+                if let Ok(stream) = stream {
+                    let handle = handle.clone();
+                    thread::spawn(move || serve(stream, &handle));
+                }
+            }
+        });
+
+        Ok(Self {
+            path,
+            stopping,
+            thread: Some(thread),
+        })
+    }
+
+    /// Gets the socket path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        // Wake the accept loop so it sees the flag
+        self.stopping.store(true, Ordering::SeqCst);
+        let _ = UnixStream::connect(&self.path);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Answers requests on one connection until the client hangs up.
+fn serve(stream: UnixStream, handle: &ControlHandle) {
+    let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = handle_line(&line, handle);
+        let Ok(mut text) = serde_json::to_string(&response) else {
+            return;
+        };
+        text.push('\n');
+        if writer.write_all(text.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+/// Sends `command` to the BusyCrab listening on `path`.
+///
+/// ### Returns
+///
+/// - `Ok` with the status after the command.
+/// - `Err(Error::Control)` if nothing is listening, the connection fails or the
+///   command is rejected.
+pub fn send(path: impl AsRef<Path>, command: &str) -> Result<Status> {
+    let path = path.as_ref();
+    let stream = UnixStream::connect(path).map_err(|err| match err.kind() {
+        ErrorKind::NotFound | ErrorKind::ConnectionRefused => {
+            Error::control(format!("no BusyCrab is running ({})", path.display()))
+        }
+        _ => Error::control_source(format!("can't connect to {}", path.display()), err),
+    })?;
+    let io_error = |err| Error::control_source("control socket connection failed", err);
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .map_err(io_error)?;
+    stream
+        .set_write_timeout(Some(IO_TIMEOUT))
+        .map_err(io_error)?;
+
+    let request = Request {
+        command: command.to_string(),
+    };
+    let mut text = serde_json::to_string(&request)
+        .map_err(|err| Error::control_source("can't encode request", err))?;
+    text.push('\n');
+    (&stream).write_all(text.as_bytes()).map_err(io_error)?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(io_error)?;
+    let response: Response = serde_json::from_str(&line)
+        .map_err(|err| Error::control_source("invalid response from BusyCrab", err))?;
+    match (response.ok, response.status) {
+        (true, Some(status)) => Ok(status),
+        _ => Err(Error::control(
+            response
+                .error
+                .unwrap_or_else(|| "BusyCrab gave no status".to_string()),
+        )),
+    }
+}
@@ -0,0 +1,145 @@
+//! What a running BusyCrab reports about itself.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use super::{Settings, SettingsUpdate};
+use crate::limit::RunDuration;
+use crate::platform::Backend;
+
+/// What an activity cycle did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleOutcome {
+    /// Activity was simulated
+    Activity,
+    /// The user was active, so only sleep prevention was refreshed
+    UserActive,
+    /// Paused through a control
+    Paused,
+    /// Outside the schedule or calendar
+    OffSchedule,
+    /// On a holiday
+    Holiday,
+}
+
+impl CycleOutcome {
+    /// Name used in status reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            CycleOutcome::Activity => "active",
+            CycleOutcome::UserActive => "user-active",
+            CycleOutcome::Paused => "paused",
+            CycleOutcome::OffSchedule => "off-schedule",
+            CycleOutcome::Holiday => "holiday",
+        }
+    }
+}
+
+/// Live state the activity loop shares with controls.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RunState {
+    /// When `run` started
+    pub started: Option<Instant>,
+    /// Sleep prevention backend in use
+    pub backend: Option<Backend>,
+    /// Activity cycles that simulated activity
+    pub cycles: u64,
+    /// Interval drawn for the current wait
+    pub interval: Duration,
+    /// When the next cycle is due
+    pub next_cycle: Option<Instant>,
+    /// What the last cycle did
+    pub last_outcome: Option<CycleOutcome>,
+    /// Settings in effect
+    pub settings: Settings,
+    /// Settings changed through a control, until the activity loop applies them
+    pub pending_settings: Option<SettingsUpdate>,
+}
+
+impl RunState {
+    /// Gets the settings in effect once the pending changes are applied.
+    pub fn upcoming_settings(&self) -> Settings {
+        let mut settings = self.settings.clone();
+        if let Some(update) = &self.pending_settings {
+            update.apply_to(&mut settings);
+        }
+        settings
+    }
+}
+
+/// A snapshot of a running BusyCrab, as sent over the control socket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    /// Process ID
+    pub pid: u32,
+    /// Seconds since BusyCrab started
+    pub uptime_secs: u64,
+    /// `active`, `user-active`, `paused`, `off-schedule`, `holiday` or `stopping`
+    pub state: String,
+    /// Whether BusyCrab is paused
+    pub paused: bool,
+    /// Activity cycles that simulated activity
+    pub cycles: u64,
+    /// Sleep prevention backend
+    pub backend: String,
+    /// Seconds between cycles, as drawn for the current wait
+    pub interval_secs: u64,
+    /// Seconds until the next cycle, unless paused or stopping
+    pub next_activity_secs: Option<u64>,
+}
+
+impl Status {
+    /// Takes a snapshot of `state`.
+    pub(crate) fn capture(state: &RunState, running: bool, paused: bool) -> Self {
+        let now = Instant::now();
+        let state_name = if !running {
+            "stopping"
+        } else if paused {
+            CycleOutcome::Paused.name()
+        } else {
+            state.last_outcome.map_or("active", CycleOutcome::name)
+        };
+        Self {
+            pid: std::process::id(),
+            uptime_secs: state
+                .started
+                .map_or(0, |started| now.duration_since(started).as_secs()),
+            state: state_name.to_string(),
+            paused,
+            cycles: state.cycles,
+            backend: state
+                .backend
+                .map_or_else(|| "unknown".to_string(), |backend| backend.to_string()),
+            interval_secs: state.interval.as_secs(),
+            next_activity_secs: state
+                .next_cycle
+                .filter(|_| running && !paused)
+                .map(|next| next.saturating_duration_since(now).as_secs()),
+        }
+    }
+
+    /// Checks if BusyCrab is keeping the system awake: running, not paused, and
+    /// neither off schedule nor on a holiday.
+    pub fn is_active(&self) -> bool {
+        matches!(self.state.as_str(), "active" | "user-active")
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "BusyCrab (pid {}): {}", self.pid, self.state)?;
+        writeln!(
+            f,
+            "  Uptime: {}",
+            RunDuration(Duration::from_secs(self.uptime_secs))
+        )?;
+        writeln!(f, "  Activity cycles: {}", self.cycles)?;
+        writeln!(f, "  Backend: {}", self.backend)?;
+        write!(f, "  Interval: {} seconds", self.interval_secs)?;
+        if let Some(next) = self.next_activity_secs {
+            write!(f, "\n  Next activity in: {} seconds", next)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! # Errors
+//!
+//! The error type returned by every fallible BusyCrab API.
+//!
+//! Variants tell apart the kinds of failure a caller may want to handle differently:
+//! a backend that can't work on this OS at all, a backend that failed (usually with
+//! the underlying OS or D-Bus error as `source`), input simulation failing, idle
+//! detection failing, invalid configuration, and failing to talk to a running BusyCrab.
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use crate::platform::Backend;
+
+/// Boxed underlying error kept as the `source` of an `Error`.
+type Source = Box<dyn StdError + Send + Sync + 'static>;
+
+/// Errors returned by BusyCrab.
+#[derive(Debug)]
+pub enum Error {
+    /// The requested feature or backend doesn't exist on this platform
+    Unsupported(String),
+    /// A sleep-prevention backend failed
+    Backend {
+        /// Backend that failed
+        backend: Backend,
+        /// What went wrong
+        message: String,
+        /// Underlying OS, D-Bus, X11 or Wayland error, if any
+        source: Option<Source>,
+    },
+    /// Simulating mouse or keyboard input failed
+    Input {
+        /// What went wrong
+        message: String,
+        /// Underlying error, if any
+        source: Option<Source>,
+    },
+    /// Reading how long the user has been idle failed
+    Idle {
+        /// What went wrong
+        message: String,
+        /// Underlying error, if any
+        source: Option<Source>,
+    },
+    /// The configuration is invalid
+    Config(String),
+    /// Talking to a running BusyCrab through its control socket failed
+    Control {
+        /// What went wrong
+        message: String,
+        /// Underlying I/O or protocol error, if any
+        source: Option<Source>,
+    },
+}
+
+impl Error {
+    /// Creates an `Unsupported` error.
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Error::Unsupported(message.into())
+    }
+
+    /// Creates a `Backend` error without an underlying cause.
+    pub fn backend(backend: Backend, message: impl Into<String>) -> Self {
+        Error::Backend {
+            backend,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Creates a `Backend` error caused by `source`.
+    pub fn backend_source(
+        backend: Backend,
+        message: impl Into<String>,
+        source: impl Into<Source>,
+    ) -> Self {
+        Error::Backend {
+            backend,
+            message: message.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Creates an `Input` error without an underlying cause.
+    pub fn input(message: impl Into<String>) -> Self {
+        Error::Input {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Creates an `Input` error caused by `source`.
+    pub fn input_source(message: impl Into<String>, source: impl Into<Source>) -> Self {
+        Error::Input {
+            message: message.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Creates an `Idle` error without an underlying cause.
+    pub fn idle(message: impl Into<String>) -> Self {
+        Error::Idle {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Creates an `Idle` error caused by `source`.
+    pub fn idle_source(message: impl Into<String>, source: impl Into<Source>) -> Self {
+        Error::Idle {
+            message: message.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Creates a `Config` error.
+    pub fn config(message: impl Into<String>) -> Self {
+        Error::Config(message.into())
+    }
+
+    /// Creates a `Control` error without an underlying cause.
+    pub fn control(message: impl Into<String>) -> Self {
+        Error::Control {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Creates a `Control` error caused by `source`.
+    pub fn control_source(message: impl Into<String>, source: impl Into<Source>) -> Self {
+        Error::Control {
+            message: message.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Gets the backend that failed, for `Backend` errors.
+    pub fn failed_backend(&self) -> Option<Backend> {
+        match self {
+            Error::Backend { backend, .. } => Some(*backend),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Unsupported(message) => write!(f, "{}", message),
+            Error::Backend {
+                backend, message, ..
+            } => write!(f, "{} backend: {}", backend, message),
+            Error::Input { message, .. } => write!(f, "input simulation failed: {}", message),
+            Error::Idle { message, .. } => write!(f, "idle detection failed: {}", message),
+            Error::Config(message) => write!(f, "invalid configuration: {}", message),
+            Error::Control { message, .. } => write!(f, "control failed: {}", message),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Backend {
+                source: Some(source),
+                ..
+            }
+            | Error::Input {
+                source: Some(source),
+                ..
+            }
+            | Error::Idle {
+                source: Some(source),
+                ..
+            }
+            | Error::Control {
+                source: Some(source),
+                ..
+            } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Result type used across BusyCrab.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Adds backend context to errors from the libraries backends are built on.
+/// Only the Linux backends wrap library errors so far.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) trait ResultExt<T> {
+    /// Turns the error into an `Error::Backend` with `message`, keeping it as the source.
+    fn backend_context(self, backend: Backend, message: &str) -> Result<T>;
+}
+
+impl<T, E: Into<Source>> ResultExt<T> for std::result::Result<T, E> {
+    fn backend_context(self, backend: Backend, message: &str) -> Result<T> {
+        self.map_err(|err| Error::backend_source(backend, message, err))
+    }
+}
@@ -0,0 +1,124 @@
+//! # Failure Policy
+//!
+//! Decides what the activity loop does when sleep prevention fails. Simulated activity
+//! keeps the session awake on its own in many setups, so a broken backend doesn't have
+//! to stop BusyCrab.
+//!
+//! - **fail**: Stop on the first failure (the default)
+//! - **warn**: Report the failure and keep moving the mouse
+//! - **retry**: Try again with exponential backoff, stopping once the retries run out
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Delay before the first retry; each later retry waits twice as long.
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest wait between two retries.
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Retries used by `--on-failure retry` unless `--max-retries` says otherwise.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// What to do when sleep prevention fails during a cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailurePolicy {
+    /// Stop the activity loop with the error
+    #[default]
+    FailFast,
+    /// Report the error and carry on with the cycle
+    Warn,
+    /// Retry with exponential backoff, stopping with the last error when retries run out
+    Retry {
+        /// Retries after the first failed attempt
+        max_retries: u32,
+        /// Delay before the first retry
+        initial_delay: Duration,
+    },
+}
+
+impl FailurePolicy {
+    /// Creates a retry policy with the default initial delay.
+    pub fn retry(max_retries: u32) -> Self {
+        FailurePolicy::Retry {
+            max_retries,
+            initial_delay: DEFAULT_RETRY_DELAY,
+        }
+    }
+
+    /// Gets the delay before retry number `retry` (starting at 0), or `None` once
+    /// the policy has no retries left.
+    pub fn retry_delay(self, retry: u32) -> Option<Duration> {
+        match self {
+            FailurePolicy::Retry {
+                max_retries,
+                initial_delay,
+            } if retry < max_retries => {
+                let factor = 2u32.checked_pow(retry).unwrap_or(u32::MAX);
+                Some(initial_delay.saturating_mul(factor).min(MAX_RETRY_DELAY))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `--on-failure` values; `FailureMode::policy` turns one into a `FailurePolicy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailureMode {
+    /// Stop on the first failure
+    #[default]
+    Fail,
+    /// Report failures and keep going
+    Warn,
+    /// Retry with exponential backoff
+    Retry,
+}
+
+impl FailureMode {
+    /// Builds the policy for this mode. `max_retries` only matters for `Retry`.
+    pub fn policy(self, max_retries: u32) -> FailurePolicy {
+        match self {
+            FailureMode::Fail => FailurePolicy::FailFast,
+            FailureMode::Warn => FailurePolicy::Warn,
+            FailureMode::Retry => FailurePolicy::retry(max_retries),
+        }
+    }
+}
+
+impl fmt::Display for FailureMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            FailureMode::Fail => "fail",
+            FailureMode::Warn => "warn",
+            FailureMode::Retry => "retry",
+        })
+    }
+}
+
+impl FromStr for FailureMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fail" => Ok(FailureMode::Fail),
+            "warn" => Ok(FailureMode::Warn),
+            "retry" => Ok(FailureMode::Retry),
+            _ => Err(format!(
+                "unknown failure mode '{}' (expected fail, warn or retry)",
+                s
+            )),
+        }
+    }
+}
+
+/// Sleep prevention failures seen during a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FailureStats {
+    /// Failed attempts to prevent sleep, including failed retries
+    pub failures: u64,
+    /// Retries made under `FailurePolicy::Retry`
+    pub retries: u64,
+    /// Cycles that succeeded after at least one retry
+    pub recovered: u64,
+}
@@ -0,0 +1,166 @@
+//! Idle time from the timestamps of evdev input events.
+//!
+//! Every event the kernel queues on a `/dev/input/event*` node carries the wall-clock
+//! time it happened, so the newest event read is the last input. Opening the nodes
+//! needs root or membership of the `input` group. Only events from after the nodes
+//! are opened are seen, and devices plugged in later aren't watched.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::IdleDetector;
+use crate::error::{Error, Result};
+
+/// Default directory holding the evdev nodes.
+pub const INPUT_DIR: &str = "/dev/input";
+
+/// Size of the kernel's `__kernel_ulong_t`, used for both halves of an event's time.
+const WORD: usize = std::mem::size_of::<libc::c_ulong>();
+
+/// Size of a `struct input_event`: seconds, microseconds, type, code and value.
+const EVENT_SIZE: usize = 2 * WORD + 8;
+
+/// Reads the events queued on the `event*` nodes and keeps the newest time.
+pub struct InputIdleDetector {
+    dir: PathBuf,
+    /// Open event nodes, once the first idle time was read
+    devices: Vec<File>,
+    /// When the nodes were opened, standing in for the last input until there is one
+    watching_since: Option<SystemTime>,
+    /// Time of the newest event read
+    last_input: Option<SystemTime>,
+}
+
+impl InputIdleDetector {
+    /// Creates a detector that looks at `/dev/input`.
+    pub fn new() -> Self {
+        Self::with_dir(INPUT_DIR)
+    }
+
+    /// Creates a detector that looks at the `event*` files in `dir`.
+    pub fn with_dir(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            devices: Vec::new(),
+            watching_since: None,
+            last_input: None,
+        }
+    }
+
+    /// Opens every `event*` node in the directory without blocking reads.
+    fn open_devices(&self) -> Result<Vec<File>> {
+        let entries = std::fs::read_dir(&self.dir).map_err(|err| {
+            Error::idle_source(format!("Failed to list {}", self.dir.display()), err)
+        })?;
+        let nodes: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+            .map(|entry| entry.path())
+            .collect();
+        if nodes.is_empty() {
+            return Err(Error::idle(format!(
+                "No input devices found in {}",
+                self.dir.display()
+            )));
+        }
+
+        let mut last_error = None;
+        let devices: Vec<File> = nodes
+            .iter()
+            .filter_map(|path| {
+                OpenOptions::new()
+                    .read(true)
+                    .custom_flags(libc::O_NONBLOCK)
+                    .open(path)
+                    .map_err(|err| last_error = Some(err))
+                    .ok()
+            })
+            .collect();
+        match last_error {
+            Some(err) if devices.is_empty() => Err(Error::idle_source(
+                format!("Failed to open the input devices in {}", self.dir.display()),
+                err,
+            )),
+            _ => Ok(devices),
+        }
+    }
+}
+
+impl Default for InputIdleDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdleDetector for InputIdleDetector {
+    fn name(&self) -> &'static str {
+        "input"
+    }
+
+    fn idle_time(&mut self) -> Result<Duration> {
+        if self.watching_since.is_none() {
+            self.devices = self.open_devices()?;
+            self.watching_since = Some(SystemTime::now());
+        }
+
+        // A node that can't be read any more was unplugged
+        let last_input = &mut self.last_input;
+        self.devices.retain_mut(|device| match read_newest(device) {
+            Ok(newest) => {
+                *last_input = (*last_input).max(newest);
+                true
+            }
+            Err(_) => false,
+        });
+        if self.devices.is_empty() {
+            self.watching_since = None;
+            return Err(Error::idle(format!(
+                "All input devices in {} went away",
+                self.dir.display()
+            )));
+        }
+
+        let last_input = self
+            .last_input
+            .or(self.watching_since)
+            .unwrap_or(UNIX_EPOCH);
+        Ok(SystemTime::now()
+            .duration_since(last_input)
+            .unwrap_or(Duration::ZERO))
+    }
+}
+
+/// Reads the events queued on `device` and gets the time of the newest one.
+fn read_newest(device: &mut File) -> io::Result<Option<SystemTime>> {
+    let mut buffer = [0; EVENT_SIZE * 64];
+    let mut newest = None;
+    loop {
+        let len = match device.read(&mut buffer) {
+            Ok(0) => return Ok(newest),
+            Ok(len) => len,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(newest),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        // The kernel only hands out whole events
+        for event in buffer[..len].chunks_exact(EVENT_SIZE) {
+            newest = newest.max(Some(event_time(event)));
+        }
+    }
+}
+
+/// Gets the time of an event from its raw bytes.
+// `c_ulong` is only `u64` on 64-bit targets
+#[allow(clippy::unnecessary_cast)]
+fn event_time(event: &[u8]) -> SystemTime {
+    let word = |at: usize| {
+        let mut bytes = [0; WORD];
+        bytes.copy_from_slice(&event[at..at + WORD]);
+        libc::c_ulong::from_ne_bytes(bytes) as u64
+    };
+    let micros = word(WORD).min(999_999);
+    UNIX_EPOCH + Duration::from_secs(word(0)) + Duration::from_micros(micros)
+}
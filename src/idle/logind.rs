@@ -0,0 +1,97 @@
+//! Idle time from systemd-logind's idle hint.
+//!
+//! Desktops tell logind when the session goes idle, usually after their own idle delay,
+//! so this source is coarse: it reads zero until the desktop decides the user is idle.
+
+use std::time::Duration;
+
+use zbus::blocking::Connection;
+
+use super::IdleDetector;
+use crate::error::{Error, Result};
+use crate::platform::linux::connect;
+
+const LOGIND_SERVICE: &str = "org.freedesktop.login1";
+const LOGIND_PATH: &str = "/org/freedesktop/login1";
+const LOGIND_MANAGER: &str = "org.freedesktop.login1.Manager";
+
+/// Reads `IdleHint` and `IdleSinceHintMonotonic` from the logind manager.
+pub struct LogindIdleDetector {
+    /// Bus to talk to; `None` means the system bus
+    address: Option<String>,
+    /// Connection, once made
+    connection: Option<Connection>,
+}
+
+impl LogindIdleDetector {
+    /// Creates a detector that uses the system bus.
+    pub fn new() -> Self {
+        Self {
+            address: None,
+            connection: None,
+        }
+    }
+
+    /// Creates a detector that talks to the bus at `address` instead of the system bus.
+    /// Used for testing against a private `dbus-daemon`.
+    pub fn with_address(address: &str) -> Self {
+        Self {
+            address: Some(address.to_string()),
+            connection: None,
+        }
+    }
+
+    /// Reads a property of the logind manager.
+    fn property<T>(connection: &Connection, name: &str) -> Result<T>
+    where
+        T: TryFrom<zbus::zvariant::OwnedValue>,
+        T::Error: Into<zbus::Error>,
+    {
+        let proxy =
+            zbus::blocking::Proxy::new(connection, LOGIND_SERVICE, LOGIND_PATH, LOGIND_MANAGER)
+                .map_err(|err| Error::idle_source("Failed to reach systemd-logind", err))?;
+        proxy
+            .get_property(name)
+            .map_err(|err| Error::idle_source(format!("Failed to read logind's {}", name), err))
+    }
+}
+
+impl Default for LogindIdleDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdleDetector for LogindIdleDetector {
+    fn name(&self) -> &'static str {
+        "logind"
+    }
+
+    fn idle_time(&mut self) -> Result<Duration> {
+        if self.connection.is_none() {
+            let connection =
+                connect(self.address.as_deref(), Connection::system).map_err(|err| {
+                    Error::idle_source("Failed to connect to the D-Bus system bus", err)
+                })?;
+            self.connection = Some(connection);
+        }
+        let connection = self.connection.as_ref().unwrap();
+
+        if !Self::property::<bool>(connection, "IdleHint")? {
+            return Ok(Duration::ZERO);
+        }
+        let since: u64 = Self::property(connection, "IdleSinceHintMonotonic")?;
+        Ok(monotonic_now().saturating_sub(Duration::from_micros(since)))
+    }
+}
+
+/// Reads `CLOCK_MONOTONIC`, the clock logind's monotonic timestamps use.
+fn monotonic_now() -> Duration {
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: clock_gettime only writes to the timespec we pass
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+    Duration::new(now.tv_sec as u64, now.tv_nsec as u32)
+}
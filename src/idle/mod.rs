@@ -0,0 +1,151 @@
+//! # Idle Detection
+//!
+//! Finds out how long ago the user last touched the keyboard or mouse, so BusyCrab
+//! can leave the cursor alone while someone is actually working.
+//!
+//! Linux has several sources, none of which works everywhere:
+//!
+//! - **x11**: The X server's idle counter from the MIT-SCREEN-SAVER extension
+//! - **logind**: systemd-logind's `IdleHint`/`IdleSinceHint`, set by the desktop
+//!   once it considers the session idle. Only used when asked for: while BusyCrab
+//!   keeps the session from going idle (such as with logind's `idle` lock or GNOME's
+//!   idle flag) the hint stays off, so the user always reads as active
+//! - **input**: Timestamps of the events read from `/dev/input/event*` (needs
+//!   access to the devices)
+//!
+//! BusyCrab's own simulated input resets these too, so `BusyCrab` only treats input
+//! newer than its last cycle as the user's.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+#[cfg(target_os = "linux")]
+mod input;
+#[cfg(target_os = "linux")]
+mod logind;
+#[cfg(target_os = "linux")]
+mod x11;
+
+#[cfg(target_os = "linux")]
+pub use input::InputIdleDetector;
+#[cfg(target_os = "linux")]
+pub use logind::LogindIdleDetector;
+#[cfg(target_os = "linux")]
+pub use x11::X11IdleDetector;
+
+/// Reports how long the user has been idle.
+pub trait IdleDetector {
+    /// Name used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Gets the time since the last keyboard or mouse input.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the idle time.
+    /// - `Err(Error::Idle)` if the source can't be read.
+    fn idle_time(&mut self) -> Result<Duration>;
+}
+
+/// Idle time sources, as chosen with `--idle-source`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdleSource {
+    /// The first source in `CHAIN` that works
+    #[default]
+    Auto,
+    /// `X11IdleDetector`
+    X11,
+    /// `LogindIdleDetector`, which BusyCrab's own idle inhibit holds at zero
+    Logind,
+    /// `InputIdleDetector`
+    Input,
+}
+
+impl IdleSource {
+    /// Sources tried by `Auto`, in order of preference. Logind is left out because
+    /// its idle hint stays off while sleep prevention is held.
+    pub const CHAIN: [IdleSource; 2] = [IdleSource::X11, IdleSource::Input];
+
+    /// Name used on the command line and in logs.
+    pub fn name(self) -> &'static str {
+        match self {
+            IdleSource::Auto => "auto",
+            IdleSource::X11 => "x11",
+            IdleSource::Logind => "logind",
+            IdleSource::Input => "input",
+        }
+    }
+
+    /// Creates the detector and checks that it can read an idle time.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with a working detector.
+    /// - `Err` with the reason if the source can't be used (for `Auto`, the reason
+    ///   the last source failed).
+    pub fn create(self) -> Result<Box<dyn IdleDetector>> {
+        match self {
+            IdleSource::Auto => {
+                let mut last_error = Error::unsupported("No idle time source on this platform");
+                for source in IdleSource::CHAIN {
+                    match source.create() {
+                        Ok(detector) => return Ok(detector),
+                        Err(err) => last_error = err,
+                    }
+                }
+                Err(last_error)
+            }
+            _ => {
+                let mut detector = create_os(self)?;
+                detector.idle_time()?;
+                Ok(detector)
+            }
+        }
+    }
+}
+
+impl fmt::Display for IdleSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for IdleSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(IdleSource::Auto),
+            "x11" => Ok(IdleSource::X11),
+            "logind" => Ok(IdleSource::Logind),
+            "input" => Ok(IdleSource::Input),
+            _ => Err(format!(
+                "unknown idle source '{}' (expected auto, x11, logind or input)",
+                s
+            )),
+        }
+    }
+}
+
+/// Creates the detector for a single source.
+#[cfg(target_os = "linux")]
+fn create_os(source: IdleSource) -> Result<Box<dyn IdleDetector>> {
+    match source {
+        IdleSource::X11 => Ok(Box::new(X11IdleDetector::new()?)),
+        IdleSource::Logind => Ok(Box::new(LogindIdleDetector::new())),
+        IdleSource::Input => Ok(Box::new(InputIdleDetector::new())),
+        IdleSource::Auto => unreachable!("auto is resolved by IdleSource::create"),
+    }
+}
+
+/// Creates the detector for a single source.
+#[cfg(not(target_os = "linux"))]
+fn create_os(source: IdleSource) -> Result<Box<dyn IdleDetector>> {
+    Err(Error::unsupported(format!(
+        "The {} idle source is only available on Linux",
+        source
+    )))
+}
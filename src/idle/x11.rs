@@ -0,0 +1,52 @@
+//! X11 idle time from the MIT-SCREEN-SAVER extension, like `xprintidle`.
+
+use std::time::Duration;
+
+use x11rb::connection::Connection as _;
+use x11rb::protocol::screensaver;
+use x11rb::protocol::xproto::Window;
+use x11rb::rust_connection::RustConnection;
+
+use super::IdleDetector;
+use crate::error::{Error, Result};
+
+/// Reads the X server's time since the last input event.
+pub struct X11IdleDetector {
+    connection: RustConnection,
+    root: Window,
+}
+
+impl X11IdleDetector {
+    /// Connects to the X server named by `DISPLAY`.
+    pub fn new() -> Result<Self> {
+        let display = std::env::var("DISPLAY")
+            .ok()
+            .filter(|display| !display.is_empty())
+            .ok_or_else(|| Error::idle("DISPLAY is not set"))?;
+        Self::with_display(&display)
+    }
+
+    /// Connects to the X server at `display`.
+    pub fn with_display(display: &str) -> Result<Self> {
+        let (connection, screen) = x11rb::connect(Some(display))
+            .map_err(|err| Error::idle_source("Failed to connect to the X server", err))?;
+        let root = connection.setup().roots[screen].root;
+        Ok(Self { connection, root })
+    }
+}
+
+impl IdleDetector for X11IdleDetector {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
+    fn idle_time(&mut self) -> Result<Duration> {
+        let info = screensaver::query_info(&self.connection, self.root)
+            .map_err(|err| Error::idle_source("Lost connection to the X server", err))?
+            .reply()
+            .map_err(|err| {
+                Error::idle_source("The X server doesn't support MIT-SCREEN-SAVER", err)
+            })?;
+        Ok(Duration::from_millis(info.ms_since_user_input.into()))
+    }
+}
@@ -0,0 +1,37 @@
+//! Looks up keys on the X keyboard map, for keys Enigo can't name to xdo.
+//!
+//! Keycodes depend on the keyboard and its layout, so they're resolved from the
+//! keysym on the running server rather than hard-coded.
+
+use x11rb::{connection::Connection as _, protocol::xproto, rust_connection::RustConnection};
+
+/// The `Scroll_Lock` keysym.
+pub const XK_SCROLL_LOCK: xproto::Keysym = 0xff14;
+
+/// Finds the keycode that produces `keysym` on the X server named by `DISPLAY`.
+/// Returns `None` if the server can't be reached or no key produces it.
+pub fn keycode_for(keysym: xproto::Keysym) -> Option<xproto::Keycode> {
+    let display = std::env::var("DISPLAY").ok().filter(|d| !d.is_empty())?;
+    keycode_on(&display, keysym)
+}
+
+/// Finds the keycode that produces `keysym` on the X server at `display`.
+pub fn keycode_on(display: &str, keysym: xproto::Keysym) -> Option<xproto::Keycode> {
+    let (connection, _) = x11rb::connect(Some(display)).ok()?;
+    find_keycode(&connection, keysym)
+}
+
+/// Searches the keyboard mapping of `connection` for `keysym`.
+fn find_keycode(connection: &RustConnection, keysym: xproto::Keysym) -> Option<xproto::Keycode> {
+    let setup = connection.setup();
+    let (min, max) = (setup.min_keycode, setup.max_keycode);
+    let reply = xproto::get_keyboard_mapping(connection, min, max - min + 1)
+        .ok()?
+        .reply()
+        .ok()?;
+    let per_keycode = usize::from(reply.keysyms_per_keycode).max(1);
+    let index = reply.keysyms.iter().position(|&sym| sym == keysym)?;
+    u8::try_from(index / per_keycode)
+        .ok()
+        .and_then(|offset| min.checked_add(offset))
+}
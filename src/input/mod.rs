@@ -0,0 +1,231 @@
+//! # Input Module
+//!
+//! Ways of simulating input. `MouseController` implementations live here, except for
+//! the Enigo-based default controllers at the crate root.
+//!
+//! - **enigo**: Moves the real pointer through Enigo (X11 on Linux, native APIs elsewhere)
+//! - **uinput**: Creates a virtual pointer through `/dev/uinput` (Linux only)
+//!
+//! It also defines what counts as activity (mouse movement, key taps, or both) and
+//! whether the pointer is moved relative to where it is or to exact positions.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Result;
+use crate::{DefaultMouseController, MouseController};
+
+#[cfg(target_os = "linux")]
+pub mod keymap;
+pub mod pointer;
+#[cfg(target_os = "linux")]
+pub mod uinput;
+
+#[cfg(target_os = "linux")]
+pub use uinput::UinputMouseController;
+
+/// A way of moving the mouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputBackend {
+    /// Enigo, the default
+    #[default]
+    Enigo,
+    /// Linux uinput virtual pointer
+    Uinput,
+}
+
+impl InputBackend {
+    /// Name used on the command line and in logs.
+    pub fn name(self) -> &'static str {
+        match self {
+            InputBackend::Enigo => "enigo",
+            InputBackend::Uinput => "uinput",
+        }
+    }
+
+    /// Creates the mouse controller for this backend.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with a ready controller.
+    /// - `Err(Error::Input)` if the device can't be created, or `Error::Unsupported`
+    ///   for uinput outside Linux.
+    pub fn create(self) -> Result<Box<dyn MouseController>> {
+        match self {
+            InputBackend::Enigo => Ok(Box::new(DefaultMouseController::new())),
+            #[cfg(target_os = "linux")]
+            InputBackend::Uinput => Ok(Box::new(UinputMouseController::new()?)),
+            #[cfg(not(target_os = "linux"))]
+            InputBackend::Uinput => Err(crate::Error::unsupported(
+                "The uinput mouse controller is only available on Linux",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for InputBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for InputBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "enigo" => Ok(InputBackend::Enigo),
+            "uinput" => Ok(InputBackend::Uinput),
+            _ => Err(format!(
+                "unknown input backend '{}' (expected enigo or uinput)",
+                s
+            )),
+        }
+    }
+}
+
+/// How the pointer is moved along a wiggle pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Positioning {
+    /// Move by offsets from wherever the pointer is, the default. Works with every
+    /// controller, but a move clamped at a screen edge shifts the pointer for good
+    #[default]
+    Relative,
+    /// Remember where the pointer started, move to exact positions around it and
+    /// put it back there. Falls back to relative moves if the controller can't tell
+    /// where the pointer is
+    Absolute,
+}
+
+impl fmt::Display for Positioning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Positioning::Relative => "relative",
+            Positioning::Absolute => "absolute",
+        })
+    }
+}
+
+impl FromStr for Positioning {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "relative" => Ok(Positioning::Relative),
+            "absolute" => Ok(Positioning::Absolute),
+            _ => Err(format!(
+                "unknown positioning '{}' (expected relative or absolute)",
+                s
+            )),
+        }
+    }
+}
+
+/// Which kind of input each activity cycle simulates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActivityMode {
+    /// Wiggle the mouse, the default
+    #[default]
+    Mouse,
+    /// Tap a key
+    Keyboard,
+    /// Wiggle the mouse, then tap a key
+    Both,
+}
+
+impl ActivityMode {
+    /// Checks if this mode moves the mouse.
+    pub fn uses_mouse(self) -> bool {
+        matches!(self, ActivityMode::Mouse | ActivityMode::Both)
+    }
+
+    /// Checks if this mode taps a key.
+    pub fn uses_keyboard(self) -> bool {
+        matches!(self, ActivityMode::Keyboard | ActivityMode::Both)
+    }
+}
+
+impl fmt::Display for ActivityMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            ActivityMode::Mouse => "mouse",
+            ActivityMode::Keyboard => "keyboard",
+            ActivityMode::Both => "both",
+        })
+    }
+}
+
+impl FromStr for ActivityMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mouse" => Ok(ActivityMode::Mouse),
+            "keyboard" => Ok(ActivityMode::Keyboard),
+            "both" => Ok(ActivityMode::Both),
+            _ => Err(format!(
+                "unknown activity '{}' (expected mouse, keyboard or both)",
+                s
+            )),
+        }
+    }
+}
+
+/// Keys that register as activity without doing anything visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActivityKey {
+    /// F15, which hardly any keyboard has or application uses
+    #[default]
+    F15,
+    /// Shift on its own
+    Shift,
+    /// Scroll Lock, tapped twice so the lock state doesn't change. Not available
+    /// on macOS, and on Linux only if the X keyboard map has it
+    ScrollLock,
+}
+
+impl ActivityKey {
+    /// Name used on the command line and in logs.
+    pub fn name(self) -> &'static str {
+        match self {
+            ActivityKey::F15 => "f15",
+            ActivityKey::Shift => "shift",
+            ActivityKey::ScrollLock => "scrolllock",
+        }
+    }
+
+    /// Number of taps that leave the key's state unchanged.
+    pub fn taps(self) -> u32 {
+        match self {
+            ActivityKey::ScrollLock => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for ActivityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for ActivityKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "f15" => Ok(ActivityKey::F15),
+            "shift" => Ok(ActivityKey::Shift),
+            #[cfg(not(target_os = "macos"))]
+            "scrolllock" | "scroll-lock" => Ok(ActivityKey::ScrollLock),
+            #[cfg(target_os = "macos")]
+            "scrolllock" | "scroll-lock" => {
+                Err("Mac keyboards have no Scroll Lock (use f15 or shift)".to_string())
+            }
+            _ => Err(format!(
+                "unknown key '{}' (expected f15, shift or scrolllock)",
+                s
+            )),
+        }
+    }
+}
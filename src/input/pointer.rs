@@ -0,0 +1,92 @@
+//! Reads where the pointer is, for absolute positioning.
+//!
+//! Enigo 0.1 reports (0, 0) when it can't tell, which is also a valid position, so
+//! the pointer is queried directly and failures come back as `None`.
+
+#[cfg(target_os = "linux")]
+use x11rb::{connection::Connection as _, protocol::xproto, rust_connection::RustConnection};
+
+/// Queries the pointer position from the windowing system.
+pub struct PointerReader {
+    /// X display to read from, when `DISPLAY` is set
+    #[cfg(target_os = "linux")]
+    display: Option<String>,
+    /// X server connection and root window, once the position was first read
+    #[cfg(target_os = "linux")]
+    x11: std::cell::OnceCell<Option<(RustConnection, xproto::Window)>>,
+}
+
+impl PointerReader {
+    /// Creates a reader for the current session. On Linux it reads from the X server
+    /// named by `DISPLAY`, if any.
+    ///
+    /// No connection is made until the position is read.
+    pub fn new() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            Self {
+                display: std::env::var("DISPLAY")
+                    .ok()
+                    .filter(|display| !display.is_empty()),
+                x11: std::cell::OnceCell::new(),
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        Self {}
+    }
+
+    /// Creates a reader for the X server at `display`.
+    #[cfg(target_os = "linux")]
+    pub fn with_display(display: &str) -> Self {
+        Self {
+            display: Some(display.to_string()),
+            x11: std::cell::OnceCell::new(),
+        }
+    }
+
+    /// Connects to the X server on first use. A failed attempt isn't retried.
+    #[cfg(target_os = "linux")]
+    fn x11(&self) -> Option<&(RustConnection, xproto::Window)> {
+        self.x11
+            .get_or_init(|| {
+                let (connection, screen) = x11rb::connect(Some(self.display.as_deref()?)).ok()?;
+                let root = connection.setup().roots[screen].root;
+                Some((connection, root))
+            })
+            .as_ref()
+    }
+
+    /// Gets the pointer position in screen coordinates.
+    /// Returns `None` if it can't be read.
+    pub fn location(&self) -> Option<(i32, i32)> {
+        #[cfg(target_os = "linux")]
+        {
+            let (connection, root) = self.x11()?;
+            let reply = xproto::query_pointer(connection, *root)
+                .ok()?
+                .reply()
+                .ok()?;
+            // The pointer is on another screen, so the coordinates aren't for this one
+            reply
+                .same_screen
+                .then(|| (reply.root_x.into(), reply.root_y.into()))
+        }
+        #[cfg(target_os = "windows")]
+        {
+            use winapi::shared::windef::POINT;
+            use winapi::um::winuser::GetCursorPos;
+
+            let mut point = POINT { x: 0, y: 0 };
+            // SAFETY: point is a valid POINT to write to.
+            (unsafe { GetCursorPos(&mut point) } != 0).then_some((point.x, point.y))
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+        None
+    }
+}
+
+impl Default for PointerReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,226 @@
+//! # uinput Mouse Controller
+//!
+//! Creates a virtual pointer device through the kernel's `/dev/uinput` and moves it by
+//! writing `REL_X`/`REL_Y` events. The kernel treats it like a real mouse, so it works
+//! under X11, any Wayland compositor and even on a bare console, where Enigo can't.
+//!
+//! Opening `/dev/uinput` usually needs extra permissions; see `UinputMouseController::new`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::MouseController;
+
+/// Default location of the uinput device.
+pub const UINPUT_PATH: &str = "/dev/uinput";
+
+/// Name the virtual device shows up with in `/proc/bus/input/devices` and `libinput list-devices`.
+pub const DEVICE_NAME: &str = "BusyCrab virtual pointer";
+
+// Event types and codes from <linux/input-event-codes.h>
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const SYN_REPORT: u16 = 0x00;
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const BTN_LEFT: u16 = 0x110;
+const BUS_VIRTUAL: u16 = 0x06;
+
+// ioctl requests from <linux/uinput.h>
+const UI_DEV_CREATE: u64 = 0x5501;
+const UI_DEV_DESTROY: u64 = 0x5502;
+const UI_DEV_SETUP: u64 = ioc_write(3, std::mem::size_of::<libc::uinput_setup>());
+const UI_SET_EVBIT: u64 = ioc_write(100, std::mem::size_of::<libc::c_int>());
+const UI_SET_KEYBIT: u64 = ioc_write(101, std::mem::size_of::<libc::c_int>());
+const UI_SET_RELBIT: u64 = ioc_write(102, std::mem::size_of::<libc::c_int>());
+
+/// Longest sysfs name `UI_GET_SYSNAME` can return.
+const SYSNAME_LEN: usize = 64;
+
+/// Builds `_IOW('U', nr, size)`.
+const fn ioc_write(nr: u64, size: usize) -> u64 {
+    (1 << 30) | ((size as u64) << 16) | ((b'U' as u64) << 8) | nr
+}
+
+/// Builds `_IOR('U', nr, size)`.
+const fn ioc_read(nr: u64, size: usize) -> u64 {
+    (2 << 30) | ((size as u64) << 16) | ((b'U' as u64) << 8) | nr
+}
+
+/// Mouse controller backed by a uinput virtual pointer.
+pub struct UinputMouseController {
+    /// Open handle to `/dev/uinput`; the device lives as long as it does
+    device: File,
+}
+
+impl UinputMouseController {
+    /// Creates the virtual pointer through `/dev/uinput`.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the controller once the device exists.
+    /// - `Err(Error::Input)` explaining how to fix access if `/dev/uinput` is missing
+    ///   or not writable.
+    pub fn new() -> Result<Self> {
+        Self::with_path(UINPUT_PATH)
+    }
+
+    /// Creates the virtual pointer through the uinput device at `path`.
+    pub fn with_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let device = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)
+            .map_err(|err| open_error(path, err))?;
+
+        let controller = Self { device };
+        controller
+            .setup()
+            .map_err(|err| Error::input_source("Failed to create the uinput device", err))?;
+        Ok(controller)
+    }
+
+    /// Declares a relative pointer with a left button and creates it.
+    /// Without a button, libinput doesn't treat the device as a mouse.
+    fn setup(&self) -> io::Result<()> {
+        self.ioctl_int(UI_SET_EVBIT, EV_REL)?;
+        self.ioctl_int(UI_SET_RELBIT, REL_X)?;
+        self.ioctl_int(UI_SET_RELBIT, REL_Y)?;
+        self.ioctl_int(UI_SET_EVBIT, EV_KEY)?;
+        self.ioctl_int(UI_SET_KEYBIT, BTN_LEFT)?;
+
+        // SAFETY: uinput_setup is plain old data, so all zeroes is a valid value
+        let mut setup: libc::uinput_setup = unsafe { std::mem::zeroed() };
+        setup.id.bustype = BUS_VIRTUAL;
+        setup.id.version = 1;
+        for (dst, src) in setup.name.iter_mut().zip(DEVICE_NAME.bytes()) {
+            *dst = src as libc::c_char;
+        }
+        // SAFETY: the fd is open and UI_DEV_SETUP reads exactly one uinput_setup
+        check(unsafe { libc::ioctl(self.device.as_raw_fd(), UI_DEV_SETUP as _, &setup) })?;
+        // SAFETY: the fd is open and UI_DEV_CREATE takes no argument
+        check(unsafe { libc::ioctl(self.device.as_raw_fd(), UI_DEV_CREATE as _) })
+    }
+
+    /// Runs an ioctl that takes an `int` argument.
+    fn ioctl_int(&self, request: u64, value: u16) -> io::Result<()> {
+        // SAFETY: the fd is open and the request takes an int by value
+        check(unsafe {
+            libc::ioctl(
+                self.device.as_raw_fd(),
+                request as _,
+                libc::c_int::from(value),
+            )
+        })
+    }
+
+    /// Gets the device's directory in sysfs, like `/sys/devices/virtual/input/input42`.
+    pub fn sys_path(&self) -> Option<PathBuf> {
+        let mut name = [0u8; SYSNAME_LEN];
+        // SAFETY: the fd is open and the kernel writes at most SYSNAME_LEN bytes
+        let len = unsafe {
+            libc::ioctl(
+                self.device.as_raw_fd(),
+                ioc_read(44, SYSNAME_LEN) as _,
+                name.as_mut_ptr(),
+            )
+        };
+        if len < 0 {
+            return None;
+        }
+        let end = name.iter().position(|byte| *byte == 0).unwrap_or(SYSNAME_LEN);
+        let name = std::str::from_utf8(&name[..end]).ok()?;
+        Some(Path::new("/sys/devices/virtual/input").join(name))
+    }
+
+    /// Gets the evdev node the kernel created for the device, like `/dev/input/event7`.
+    /// udev may need a moment to create it after the controller is made.
+    pub fn event_node(&self) -> Option<PathBuf> {
+        std::fs::read_dir(self.sys_path()?)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .find(|name| name.starts_with("event"))
+            .map(|name| Path::new("/dev/input").join(name))
+    }
+
+    /// Writes one relative motion report.
+    fn emit_motion(&mut self, x: i32, y: i32) -> io::Result<()> {
+        let mut events = Vec::with_capacity(3);
+        if x != 0 {
+            events.push(event(EV_REL, REL_X, x));
+        }
+        if y != 0 {
+            events.push(event(EV_REL, REL_Y, y));
+        }
+        events.push(event(EV_SYN, SYN_REPORT, 0));
+
+        // SAFETY: input_event is plain old data with no padding between the events
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                events.as_ptr() as *const u8,
+                std::mem::size_of_val(events.as_slice()),
+            )
+        };
+        self.device.write_all(bytes)
+    }
+}
+
+impl MouseController for UinputMouseController {
+    fn mouse_move_relative(&mut self, x: i32, y: i32) {
+        // The trait can't report errors; a broken device just stops moving the pointer
+        let _ = self.emit_motion(x, y);
+    }
+}
+
+impl Drop for UinputMouseController {
+    fn drop(&mut self) {
+        // SAFETY: the fd is still open; closing it would destroy the device anyway
+        unsafe {
+            libc::ioctl(self.device.as_raw_fd(), UI_DEV_DESTROY as _);
+        }
+    }
+}
+
+/// Creates an input event; the kernel fills in the timestamp.
+fn event(type_: u16, code: u16, value: i32) -> libc::input_event {
+    // SAFETY: input_event is plain old data, so all zeroes is a valid value
+    let mut event: libc::input_event = unsafe { std::mem::zeroed() };
+    event.type_ = type_;
+    event.code = code;
+    event.value = value;
+    event
+}
+
+/// Turns an ioctl return value into a `Result`.
+fn check(result: libc::c_int) -> io::Result<()> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Explains why the uinput device couldn't be opened and how to fix it.
+fn open_error(path: &Path, err: io::Error) -> Error {
+    let message = match err.kind() {
+        ErrorKind::PermissionDenied => format!(
+            "No permission to write to {}. Add a udev rule such as \
+             'KERNEL==\"uinput\", GROUP=\"input\", MODE=\"0660\"' and add yourself to the \
+             input group, or use --input enigo",
+            path.display()
+        ),
+        ErrorKind::NotFound => format!(
+            "{} does not exist. Load the uinput kernel module with 'modprobe uinput'",
+            path.display()
+        ),
+        _ => format!("Failed to open {}", path.display()),
+    };
+    Error::input_source(message, err)
+}
@@ -2,36 +2,72 @@
 //! A utility that prevents sleep and simulates mouse activity to keep your system active.
 //! ## Features
 //! * Prevents system sleep
-//! * Simulates mouse movement
+//! * Simulates mouse movement or key taps
 //! * Shows terminal animations
 //! * Handles Ctrl+C for clean shutdown
+//! * Pauses and resumes on signals or a key press
 //! ## Core components
 //! * `BusyCrab`: Main application struct
 //! * `platform`: Platform-specific functionality
+//! * `input`: Mouse controllers
+//! * `idle`: User idle time detection
+//! * `control`: Pausing, resuming and stopping a running BusyCrab
+//! * `metrics`: Counters a running BusyCrab keeps about what it did
+//! * `Error`: Errors returned by fallible APIs
 //! * `motion`: Terminal animations
+//! * `pattern`: Shapes for the mouse to trace
+//! * `schedule`: Working hours, calendar events and holidays BusyCrab runs by
+//! * `limit`: Time limits that stop BusyCrab on their own
+//! * `variation`: Random ranges and jitter for the interval and wiggle distance
 
-use enigo::{Enigo, MouseControllable};
+use enigo::{Enigo, Key, KeyboardControllable, MouseControllable};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 use std::{
     io::{self, Write},
+    net::SocketAddr,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        Arc, Mutex, MutexGuard,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 pub mod cli;
+pub mod control;
+pub mod error;
+pub mod failure;
+pub mod idle;
+pub mod input;
+pub mod limit;
+pub mod metrics;
 pub mod motion;
+pub mod pattern;
 pub mod platform;
+pub mod schedule;
+pub mod variation;
 
-use motion::crab::CrabMotion;
-use motion::matrix::MatrixMotion;
-use motion::mandelbrot::MandelbrotMotion;
-use motion::clock::ClockMotion;
 use motion::Motion;
+use idle::IdleDetector;
+use control::keys::KeyListener;
+use control::http::HttpServer;
+use control::{ControlHandle, CycleOutcome, RunState, Settings};
+use metrics::Metrics;
+use pattern::horizontal::HorizontalPattern;
+use pattern::WigglePattern;
+use chrono::NaiveDate;
+use schedule::{Calendar, Clock, HolidayAction, Holidays, Schedule, SystemClock};
+pub use error::{Error, Result};
+pub use input::{ActivityKey, ActivityMode, Positioning};
+pub use failure::{FailurePolicy, FailureStats};
+pub use platform::Backend;
+pub use platform::InhibitGuard;
+pub use platform::InhibitStatus;
 pub use platform::Platform;
 pub use platform::PlatformTrait;
+pub use variation::ValueRange;
 
 /// Mouse control operations trait.
 /// Abstracts mouse movement for real control and testing.
@@ -41,11 +77,30 @@ pub trait MouseController {
     /// * `x` - Horizontal movement in pixels
     /// * `y` - Vertical movement in pixels
     fn mouse_move_relative(&mut self, x: i32, y: i32);
+
+    /// Gets the cursor position in screen coordinates.
+    /// Returns `None` if the controller can't tell, which is the default.
+    fn location(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// Moves the mouse cursor to a position in screen coordinates.
+    /// The default moves relative to `location`, and does nothing if that's unknown.
+    ///
+    /// * `x` - Horizontal position in pixels
+    /// * `y` - Vertical position in pixels
+    fn mouse_move_to(&mut self, x: i32, y: i32) {
+        if let Some((current_x, current_y)) = self.location() {
+            self.mouse_move_relative(x - current_x, y - current_y);
+        }
+    }
 }
 
 /// Default mouse controller using Enigo.
 pub struct DefaultMouseController {
     enigo: Enigo,
+    #[cfg(not(target_os = "macos"))]
+    pointer: input::pointer::PointerReader,
 }
 
 /// Default mouse controller implementation.
@@ -54,15 +109,113 @@ impl DefaultMouseController {
     pub fn new() -> Self {
         Self {
             enigo: Enigo::new(),
+            #[cfg(not(target_os = "macos"))]
+            pointer: input::pointer::PointerReader::new(),
+        }
+    }
+
+    /// Creates a controller that reads the pointer position from the X server at
+    /// `display` instead of `DISPLAY`.
+    #[cfg(target_os = "linux")]
+    pub fn with_display(display: &str) -> Self {
+        Self {
+            enigo: Enigo::new(),
+            pointer: input::pointer::PointerReader::with_display(display),
         }
     }
 }
 
+impl Default for DefaultMouseController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// MouseController implementation.
 impl MouseController for DefaultMouseController {
     fn mouse_move_relative(&mut self, x: i32, y: i32) {
         self.enigo.mouse_move_relative(x, y);
     }
+
+    /// Returns `None` if the position can't be read, such as without a display.
+    fn location(&self) -> Option<(i32, i32)> {
+        // macOS always knows where the pointer is
+        #[cfg(target_os = "macos")]
+        return Some(self.enigo.mouse_location());
+        #[cfg(not(target_os = "macos"))]
+        self.pointer.location()
+    }
+
+    fn mouse_move_to(&mut self, x: i32, y: i32) {
+        self.enigo.mouse_move_to(x, y);
+    }
+}
+
+/// Keyboard control operations trait.
+/// Abstracts key presses for real control and testing.
+pub trait KeyboardController {
+    /// Presses and releases a key.
+    ///
+    /// * `key` - Key to tap
+    fn key_tap(&mut self, key: ActivityKey);
+}
+
+/// Default keyboard controller using Enigo.
+pub struct DefaultKeyboardController {
+    enigo: Enigo,
+    /// Keycode of Scroll Lock on the X keyboard map, looked up on first use
+    #[cfg(target_os = "linux")]
+    scroll_lock: std::cell::OnceCell<Option<u8>>,
+}
+
+/// Default keyboard controller implementation.
+impl DefaultKeyboardController {
+    /// Creates a new controller.
+    pub fn new() -> Self {
+        Self {
+            enigo: Enigo::new(),
+            #[cfg(target_os = "linux")]
+            scroll_lock: std::cell::OnceCell::new(),
+        }
+    }
+}
+
+impl Default for DefaultKeyboardController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// KeyboardController implementation.
+impl KeyboardController for DefaultKeyboardController {
+    fn key_tap(&mut self, key: ActivityKey) {
+        let key = match key {
+            ActivityKey::F15 => Key::F15,
+            ActivityKey::Shift => Key::Shift,
+            // enigo sends "Scroll_Lock " with a trailing space on Linux, which xdo can't
+            // resolve, so send whichever keycode the keyboard map has for it
+            #[cfg(target_os = "linux")]
+            ActivityKey::ScrollLock => {
+                let keycode = *self.scroll_lock.get_or_init(|| {
+                    let keycode = input::keymap::keycode_for(input::keymap::XK_SCROLL_LOCK);
+                    if keycode.is_none() {
+                        println!("Scroll Lock isn't on the keyboard map, so it won't be tapped");
+                    }
+                    keycode
+                });
+                match keycode {
+                    Some(keycode) => Key::Raw(keycode.into()),
+                    None => return,
+                }
+            }
+            #[cfg(target_os = "windows")]
+            ActivityKey::ScrollLock => Key::Scroll,
+            // `--key` rejects Scroll Lock on macOS, since Mac keyboards don't have it
+            #[cfg(target_os = "macos")]
+            ActivityKey::ScrollLock => return,
+        };
+        self.enigo.key_click(key);
+    }
 }
 
 /// Type alias for managing the animation thread and its control flag.
@@ -80,20 +233,112 @@ impl MouseController for DefaultMouseController {
 /// a boolean flag (for example, to signal the animation thread to stop).
 type AnimationThread = Option<(thread::JoinHandle<()>, Arc<Mutex<bool>>)>;
 
+/// Controls that are active while `run` runs; dropping them shuts them down and
+/// puts the terminal back.
+struct Controls {
+    /// Reads the pause key, if stdin is a terminal
+    _keys: Option<KeyListener>,
+    /// Answers `busycrab status` and friends, if configured
+    #[cfg(unix)]
+    _socket: Option<control::socket::ControlServer>,
+    /// Serves the HTTP API, if configured
+    _http: Option<HttpServer>,
+}
+
+/// Input read by an idle detector this soon after a cycle ended counts as the cycle's
+/// own, not the user's. Covers the gap between the input and the detector noticing it.
+const OWN_INPUT_SLACK: Duration = Duration::from_secs(1);
+
 /// Main application struct.
 pub struct BusyCrab {
     /// Mouse controller
     mouse: Box<dyn MouseController>,
+    /// Keyboard controller
+    keyboard: Box<dyn KeyboardController>,
+    /// Kind of input simulated each cycle
+    activity: ActivityMode,
+    /// Key tapped when the activity includes the keyboard
+    activity_key: ActivityKey,
     /// Platform implementation
     platform: Box<dyn PlatformTrait>,
-    /// Time between activities
+    /// Backend the platform implementation belongs to
+    backend: Backend,
+    /// Time between activities, drawn for the current cycle
     interval: Duration,
-    /// Mouse movement distance
+    /// Mouse movement distance, drawn for the current cycle
     wiggle_distance: i32,
+    /// Seconds between activities to draw from
+    interval_range: ValueRange<u64>,
+    /// Wiggle distances to draw from
+    wiggle_range: ValueRange<i32>,
+    /// Random change to each interval, in percent
+    jitter_percent: u32,
+    /// Draws the interval and wiggle distance for each cycle
+    rng: ChaCha8Rng,
+    /// Whether wiggles move by offsets or to exact positions
+    positioning: Positioning,
+    /// Shape the mouse traces
+    pattern: Box<dyn WigglePattern>,
     /// Verbose mode flag
     verbose: bool,
     /// Optional animation
     motion: Option<Box<dyn Motion + Send>>,
+    /// Name of the animation, `none` without one
+    motion_name: String,
+    /// The animation thread while `run` runs, holding the motion
+    animation: AnimationThread,
+    /// Cleared to stop the activity loop (Ctrl+C sets it to false)
+    running: Arc<AtomicBool>,
+    /// Set to pause, cleared to resume, by the controls
+    paused: Arc<AtomicBool>,
+    /// Whether the last cycle saw the pause, to act on changes once
+    pause_applied: bool,
+    /// What the activity loop is doing, for status reports
+    run_state: Arc<Mutex<RunState>>,
+    /// Counters about what the activity loop and the animation did
+    metrics: Arc<Metrics>,
+    /// Where `run` listens for `busycrab status` and friends; `None` means nowhere
+    control_socket: Option<PathBuf>,
+    /// Where `run` serves the HTTP API; `None` means nowhere
+    http_addr: Option<SocketAddr>,
+    /// Bearer token the HTTP API requires
+    http_token: Option<String>,
+    /// Bus `run` publishes the D-Bus service on; `None` means none
+    #[cfg(target_os = "linux")]
+    dbus_bus: Option<control::dbus::Bus>,
+    /// The D-Bus service while `run` runs
+    #[cfg(target_os = "linux")]
+    dbus_service: Option<control::dbus::DbusService>,
+    /// What to do when sleep prevention fails
+    failure_policy: FailurePolicy,
+    /// Sleep prevention failures so far
+    failure_stats: FailureStats,
+    /// Tells how long the user has been idle; `None` simulates activity every cycle
+    idle_detector: Option<Box<dyn IdleDetector>>,
+    /// Idle time after which the user counts as away
+    idle_threshold: Duration,
+    /// When the user last touched the keyboard or mouse, as far as we know
+    last_user_input: Option<Instant>,
+    /// When the last cycle ended, to tell our own input from the user's
+    last_cycle_end: Option<Instant>,
+    /// Times BusyCrab is active in; `None` means always
+    schedule: Option<Schedule>,
+    /// Calendar events BusyCrab is active during; `None` means always
+    calendar: Option<Calendar>,
+    /// Days BusyCrab takes off; `None` means none
+    holidays: Option<Holidays>,
+    /// Whether a holiday pauses BusyCrab or shuts it down
+    holiday_action: HolidayAction,
+    /// The holiday last announced, so it's only announced once
+    announced_holiday: Option<NaiveDate>,
+    /// Tells the time the schedule is checked against
+    clock: Box<dyn Clock>,
+    /// Whether the last cycle fell outside the schedule
+    off_schedule: bool,
+    /// How long `run` may run before shutting down
+    time_limit: Option<Duration>,
+    /// When the current run stops, set by `run` from the time limit
+    deadline: Option<Instant>,
 }
 
 /// BusyCrab implementation.
@@ -103,14 +348,11 @@ impl BusyCrab {
     /// * `interval_secs` - Seconds between mouse movements
     /// * `wiggle_distance` - Pixels to move the mouse
     pub fn new(interval_secs: u64, wiggle_distance: i32) -> Self {
-        Self {
-            mouse: Box::new(DefaultMouseController::new()),
-            platform: Box::new(Platform::new()),
-            interval: Duration::from_secs(interval_secs),
+        Self::with_mouse_controller(
+            interval_secs,
             wiggle_distance,
-            verbose: false,
-            motion: None,
-        }
+            Box::new(DefaultMouseController::new()),
+        )
     }
 
     /// Creates an instance with custom mouse controller.
@@ -120,14 +362,12 @@ impl BusyCrab {
         wiggle_distance: i32,
         mouse_controller: Box<dyn MouseController>,
     ) -> Self {
-        Self {
-            mouse: mouse_controller,
-            platform: Box::new(Platform::new()),
-            interval: Duration::from_secs(interval_secs),
+        Self::for_testing(
+            interval_secs,
             wiggle_distance,
-            verbose: false,
-            motion: None,
-        }
+            mouse_controller,
+            Box::new(Platform::new()),
+        )
     }
 
     /// Creates a fully customizable instance for testing.
@@ -139,72 +379,552 @@ impl BusyCrab {
     ) -> Self {
         Self {
             mouse: mouse_controller,
+            keyboard: Box::new(DefaultKeyboardController::new()),
+            activity: ActivityMode::default(),
+            activity_key: ActivityKey::default(),
             platform,
+            backend: Backend::default(),
             interval: Duration::from_secs(interval_secs),
             wiggle_distance,
+            interval_range: ValueRange::fixed(interval_secs),
+            wiggle_range: ValueRange::fixed(wiggle_distance),
+            jitter_percent: 0,
+            rng: ChaCha8Rng::from_rng(&mut rand::rng()),
+            positioning: Positioning::default(),
+            pattern: Box::new(HorizontalPattern::new()),
             verbose: false,
             motion: None,
+            motion_name: "none".to_string(),
+            animation: None,
+            running: Arc::new(AtomicBool::new(true)),
+            paused: Arc::new(AtomicBool::new(false)),
+            pause_applied: false,
+            run_state: Arc::default(),
+            metrics: Arc::default(),
+            control_socket: None,
+            http_addr: None,
+            http_token: None,
+            #[cfg(target_os = "linux")]
+            dbus_bus: None,
+            #[cfg(target_os = "linux")]
+            dbus_service: None,
+            failure_policy: FailurePolicy::default(),
+            failure_stats: FailureStats::default(),
+            idle_detector: None,
+            idle_threshold: Duration::ZERO,
+            last_user_input: None,
+            last_cycle_end: None,
+            schedule: None,
+            calendar: None,
+            holidays: None,
+            holiday_action: HolidayAction::default(),
+            announced_holiday: None,
+            clock: Box::new(SystemClock),
+            off_schedule: false,
+            time_limit: None,
+            deadline: None,
         }
     }
 
     /// Starts the main application loop.
-    pub fn run(&mut self) -> Result<(), &'static str> {
+    ///
+    /// Sleep prevention is released when the loop ends, whether through Ctrl+C,
+    /// the time limit running out or because a cycle failed. Fails right away if the
+    /// HTTP API is configured but can't be started.
+    pub fn run(&mut self) -> Result<()> {
+        self.deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        self.record_start();
         self.display_startup_info();
-        let animation_thread = self.start_animation_thread();
-        let running = self.setup_shutdown_signal();
-        self.run_activity_loop(running.clone())?;
-        self.cleanup_resources(animation_thread);
-        self.display_shutdown_message();
-        Ok(())
+        let controls = self.setup_controls()?;
+        #[cfg(target_os = "linux")]
+        self.start_dbus_service();
+        self.start_animation_thread();
+        self.setup_shutdown_signal();
+        let result = self.run_activity_loop();
+        drop(controls);
+        #[cfg(target_os = "linux")]
+        {
+            self.dbus_service = None;
+        }
+        self.release_sleep_prevention();
+        self.stop_animation_thread();
+        self.display_failure_summary();
+        if result.is_ok() {
+            self.display_shutdown_message();
+        }
+        result
     }
 
     /// Shows startup info.
     fn display_startup_info(&self) {
         println!("🦀 BusyCrab started. Press Ctrl+C to exit.");
-        println!(
+        print!(
             "Running with interval: {} seconds, wiggle: {} pixels",
-            self.interval.as_secs(),
-            self.wiggle_distance
+            self.interval_range, self.wiggle_range
         );
+        if self.jitter_percent > 0 {
+            print!(", jitter: {}%", self.jitter_percent);
+        }
+        println!();
+        println!("Sleep prevention backend: {}", self.backend);
+        if let Some(schedule) = &self.schedule {
+            println!("Schedule: {}", schedule);
+        }
+        if let Some(calendar) = &self.calendar {
+            println!("Calendar: {}", calendar);
+            for skipped in calendar.skipped() {
+                println!("Calendar: {}", skipped);
+            }
+        }
+        if let Some(holidays) = &self.holidays {
+            println!("Holidays: {} (on holidays: {})", holidays, self.holiday_action);
+        }
+        if let Some(limit) = self.time_limit {
+            println!("Stopping after: {}", limit::RunDuration(limit));
+        }
     }
 
-    /// Sets up Ctrl+C handler.
-    fn setup_shutdown_signal(&self) -> Arc<AtomicBool> {
-        let running = Arc::new(AtomicBool::new(true));
-        let r = running.clone();
+    /// Sets up Ctrl+C handler. `SIGTERM` and `SIGHUP` shut down the same way, so
+    /// the terminal is put back even when BusyCrab is killed.
+    ///
+    /// The handler can only be installed once per process, so later instances
+    /// keep working but are only stopped through `shutdown_handle`.
+    fn setup_shutdown_signal(&self) {
+        let r = self.running.clone();
 
-        ctrlc::set_handler(move || {
-            println!("\n🦀 Caught Ctrl+C, shutting down gracefully...");
+        let result = ctrlc::set_handler(move || {
+            println!("\n🦀 Caught Ctrl+C or a termination signal, shutting down gracefully...");
             r.store(false, Ordering::SeqCst);
+        });
+        if result.is_err() && self.verbose {
+            println!("Ctrl+C handler already installed");
+        }
+    }
+
+    /// Sets up pausing through `SIGUSR1`/`SIGUSR2`, the control socket, the HTTP API
+    /// and, in a terminal, the `p` key. The controls shut down when the returned value
+    /// is dropped.
+    fn setup_controls(&self) -> Result<Controls> {
+        #[cfg(unix)]
+        if let Err(err) = control::signals::install() {
+            if self.verbose {
+                println!("Pause signals unavailable: {}", err);
+            }
+        }
+
+        let http = self.start_http_api()?;
+        let keys = KeyListener::start(self.control_handle());
+        if keys.is_some() {
+            println!("Press p to pause or resume.");
+        }
+        Ok(Controls {
+            _keys: keys,
+            #[cfg(unix)]
+            _socket: self.start_control_socket(),
+            _http: http,
         })
-        .expect("Error setting Ctrl+C handler");
+    }
+
+    /// Publishes the D-Bus service if it's configured. BusyCrab runs on without it if
+    /// it can't be published, such as when another instance owns the name.
+    #[cfg(target_os = "linux")]
+    fn start_dbus_service(&mut self) {
+        let Some(bus) = &self.dbus_bus else {
+            return;
+        };
+        match control::dbus::DbusService::start(bus, self.control_handle()) {
+            Ok(service) => {
+                if self.verbose {
+                    println!("D-Bus service: {}", control::dbus::SERVICE_NAME);
+                }
+                self.dbus_service = Some(service);
+            }
+            Err(err) => println!("D-Bus service unavailable: {}", err),
+        }
+    }
 
-        running
+    /// Starts the HTTP API if it's configured.
+    fn start_http_api(&self) -> Result<Option<HttpServer>> {
+        let Some(addr) = self.http_addr else {
+            return Ok(None);
+        };
+        let server = HttpServer::start(addr, self.http_token.clone(), self.control_handle())?;
+        println!("HTTP API: http://{}", server.addr());
+        Ok(Some(server))
+    }
+
+    /// Starts the control socket if one is configured. BusyCrab runs on without it
+    /// if it can't be started, such as when another instance holds it.
+    #[cfg(unix)]
+    fn start_control_socket(&self) -> Option<control::socket::ControlServer> {
+        let path = self.control_socket.as_ref()?;
+        match control::socket::ControlServer::start(path, self.control_handle()) {
+            Ok(server) => {
+                if self.verbose {
+                    println!("Control socket: {}", path.display());
+                }
+                Some(server)
+            }
+            Err(err) => {
+                println!("Control socket unavailable: {}", err);
+                None
+            }
+        }
     }
 
     /// Runs the main loop.
-    fn run_activity_loop(&mut self, running: Arc<AtomicBool>) -> Result<(), &'static str> {
+    fn run_activity_loop(&mut self) -> Result<()> {
         let mut activity_count = 0;
-        while running.load(Ordering::SeqCst) {
+        while self.running.load(Ordering::SeqCst) && !self.stop_at_deadline() {
+            self.apply_pending_settings();
             self.execute_activity_cycle(&mut activity_count)?;
-            if !self.wait_for_next_cycle(&running) {
+            if !self.wait_for_next_cycle() {
                 break;
             }
         }
         Ok(())
     }
 
+    /// Releases sleep prevention, logging failures in verbose mode.
+    fn release_sleep_prevention(&self) {
+        if let Err(err) = self.platform.release() {
+            if self.verbose {
+                println!();
+                println!("Failed to release sleep prevention: {}", err);
+            }
+        }
+    }
+
     /// Executes one activity cycle.
-    pub fn execute_activity_cycle(&mut self, activity_count: &mut u64) -> Result<(), &'static str> {
-        self.platform.prevent_sleep()?;
-        self.simulate_activity();
-        
-        *activity_count += 1;
-        self.log_activity_status(*activity_count);
-        
+    ///
+    /// Sleep prevention failures are handled according to the failure policy; the
+    /// cycle only fails if the policy gives up. With an idle detector, activity is only
+    /// simulated once the user has been idle for the threshold, but sleep prevention
+    /// is refreshed either way. Outside the schedule and on holidays, sleep prevention
+    /// is released and nothing else happens, unless the holiday action shuts BusyCrab down.
+    /// The same goes for while BusyCrab is paused. Each cycle is counted in the metrics
+    /// and, while the D-Bus service is published, announced on it. A cycle cut short
+    /// by shutdown while waiting to retry does nothing more and isn't counted.
+    pub fn execute_activity_cycle(&mut self, activity_count: &mut u64) -> Result<()> {
+        let Some(outcome) = self.run_cycle(activity_count)? else {
+            return Ok(());
+        };
+        self.record_cycle(outcome, *activity_count);
+        self.metrics.record_cycle(outcome);
+        #[cfg(target_os = "linux")]
+        if let Some(service) = &self.dbus_service {
+            service.announce_cycle(outcome, *activity_count);
+        }
         Ok(())
     }
 
+    /// Does the work of `execute_activity_cycle`, telling what it did.
+    /// Returns `None` if BusyCrab started shutting down partway through.
+    fn run_cycle(&mut self, activity_count: &mut u64) -> Result<Option<CycleOutcome>> {
+        self.draw_cycle_values();
+        if self.check_paused() {
+            return Ok(Some(CycleOutcome::Paused));
+        }
+        if self.check_holiday() {
+            return Ok(Some(CycleOutcome::Holiday));
+        }
+        if !self.is_scheduled() {
+            self.pause_outside_schedule();
+            return Ok(Some(CycleOutcome::OffSchedule));
+        }
+        if self.off_schedule {
+            self.off_schedule = false;
+            self.log_schedule_change("Back in scheduled hours, resuming");
+        }
+
+        let user_active = self.user_is_active();
+        if !self.refresh_sleep_prevention()? {
+            return Ok(None);
+        }
+
+        let outcome = if user_active {
+            self.log_user_active();
+            CycleOutcome::UserActive
+        } else {
+            self.simulate_activity();
+            *activity_count += 1;
+            self.log_activity_status(*activity_count);
+            CycleOutcome::Activity
+        };
+
+        self.last_cycle_end = Some(Instant::now());
+        Ok(Some(outcome))
+    }
+
+    /// Notes when the run started, the backend it uses and the settings, for status
+    /// reports.
+    fn record_start(&self) {
+        let mut state = self.lock_run_state();
+        state.started = Some(Instant::now());
+        state.backend = Some(self.backend);
+        state.interval = self.interval;
+        state.settings = self.current_settings();
+    }
+
+    /// Notes what a cycle did and when the next one is due, for status reports.
+    fn record_cycle(&self, outcome: CycleOutcome, activity_count: u64) {
+        let mut state = self.lock_run_state();
+        state.cycles = activity_count;
+        state.last_outcome = Some(outcome);
+        self.record_next_cycle(&mut state);
+    }
+
+    /// Notes when the next cycle is due, after the interval just drawn.
+    fn record_next_cycle(&self, state: &mut RunState) {
+        state.interval = self.interval;
+        state.next_cycle = Some(Instant::now() + self.interval);
+    }
+
+    /// Locks the state shared with controls, even if a control panicked holding it.
+    fn lock_run_state(&self) -> MutexGuard<'_, RunState> {
+        self.run_state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Gets the settings controls can change.
+    fn current_settings(&self) -> Settings {
+        Settings {
+            interval: self.interval_range,
+            wiggle: self.wiggle_range,
+            motion: self.motion_name.clone(),
+        }
+    }
+
+    /// Applies settings changed through a control, switching the animation if it
+    /// changed. Returns `true` if there were changes.
+    fn apply_pending_settings(&mut self) -> bool {
+        let Some(update) = self.lock_run_state().pending_settings.take() else {
+            return false;
+        };
+        if let Some(range) = update.interval {
+            self.interval_range = range;
+        }
+        if let Some(range) = update.wiggle {
+            self.wiggle_range = range;
+        }
+        if let Some(name) = update.motion {
+            if !name.eq_ignore_ascii_case(&self.motion_name) {
+                self.switch_motion(&name);
+            }
+        }
+
+        let settings = self.current_settings();
+        if self.verbose {
+            print!("\r");
+            io::stdout().flush().unwrap();
+            println!(
+                "Settings changed: interval {} seconds, wiggle {} pixels, motion {}",
+                settings.interval, settings.wiggle, settings.motion
+            );
+        }
+        self.lock_run_state().settings = settings;
+        true
+    }
+
+    /// Replaces the animation with the motion called `name` while `run` runs,
+    /// restarting the animation thread.
+    fn switch_motion(&mut self, name: &str) {
+        if self.animation.is_some() {
+            self.stop_animation_thread();
+            // Clear what the old motion left on screen
+            print!("\x1B[2J\x1B[H");
+            io::stdout().flush().unwrap();
+        }
+        self.set_motion(name);
+        self.start_animation_thread();
+    }
+
+    /// Checks if the schedule and calendar, if any, allow activity right now.
+    fn is_scheduled(&self) -> bool {
+        let now = self.clock.now();
+        self.schedule
+            .as_ref()
+            .is_none_or(|schedule| schedule.is_active(now))
+            && self
+                .calendar
+                .as_ref()
+                .is_none_or(|calendar| calendar.is_active(now))
+    }
+
+    /// Releases sleep prevention when a cycle first falls outside the schedule.
+    fn pause_outside_schedule(&mut self) {
+        if self.off_schedule {
+            return;
+        }
+        self.off_schedule = true;
+        self.log_schedule_change("Outside scheduled hours, pausing");
+        self.release_sleep_prevention();
+    }
+
+    /// Checks if BusyCrab is paused, releasing sleep prevention when it was just paused.
+    /// Returns `true` while paused.
+    fn check_paused(&mut self) -> bool {
+        let paused = self.paused.load(Ordering::SeqCst);
+        if paused == self.pause_applied {
+            return paused;
+        }
+        self.pause_applied = paused;
+
+        print!("\r");
+        io::stdout().flush().unwrap();
+        if paused {
+            println!("⏸ Paused, letting the system sleep");
+            self.release_sleep_prevention();
+        } else {
+            println!("▶ Resumed");
+        }
+        paused
+    }
+
+    /// Checks if today is a holiday, announcing it the first time and pausing or
+    /// shutting down according to the holiday action. Returns `true` on a holiday.
+    fn check_holiday(&mut self) -> bool {
+        let today = self.clock.now().date();
+        let Some(holiday) = self.holidays.as_ref().and_then(|holidays| holidays.on(today)) else {
+            return false;
+        };
+        if self.announced_holiday == Some(today) {
+            return true;
+        }
+
+        let day = match &holiday.name {
+            Some(name) => format!("Day off today ({})", name),
+            None => "Day off today".to_string(),
+        };
+        self.announced_holiday = Some(today);
+        print!("\r");
+        io::stdout().flush().unwrap();
+        match self.holiday_action {
+            HolidayAction::Pause => println!("🦀 {}, pausing", day),
+            HolidayAction::Exit => {
+                if self.running.swap(false, Ordering::SeqCst) {
+                    println!("🦀 {}, shutting down gracefully...", day);
+                }
+            }
+        }
+
+        // Resuming afterwards is logged like coming back into the schedule
+        if !self.off_schedule {
+            self.off_schedule = true;
+            self.release_sleep_prevention();
+        }
+        true
+    }
+
+    /// Logs entering or leaving the schedule if verbose mode is on.
+    fn log_schedule_change(&self, message: &str) {
+        if self.verbose {
+            print!("\r");
+            io::stdout().flush().unwrap();
+            println!("{}", message);
+        }
+    }
+
+    /// Draws this cycle's wiggle distance and the interval until the next cycle.
+    fn draw_cycle_values(&mut self) {
+        self.wiggle_distance = self.wiggle_range.sample(&mut self.rng);
+        let secs = self.interval_range.sample(&mut self.rng);
+        let secs = variation::jitter_secs(secs, self.jitter_percent, &mut self.rng);
+        self.interval = Duration::from_secs(secs);
+    }
+
+    /// Checks if the user touched the keyboard or mouse within the idle threshold.
+    ///
+    /// Input that isn't newer than the last cycle may be our own wiggle (or a backend
+    /// resetting the idle timer), so only newer input moves the user's last input time.
+    /// Detector errors are logged in verbose mode and count as idle.
+    fn user_is_active(&mut self) -> bool {
+        let Some(detector) = self.idle_detector.as_mut() else {
+            return false;
+        };
+
+        match detector.idle_time() {
+            Ok(idle) => {
+                let own_input = self
+                    .last_cycle_end
+                    .is_some_and(|end| idle + OWN_INPUT_SLACK >= end.elapsed());
+                if !own_input {
+                    self.last_user_input = Instant::now().checked_sub(idle);
+                }
+            }
+            Err(err) => {
+                if self.verbose {
+                    print!("\r");
+                    io::stdout().flush().unwrap();
+                    println!("Idle time unavailable ({}): {}", detector.name(), err);
+                }
+            }
+        }
+
+        self.last_user_input
+            .is_some_and(|input| input.elapsed() < self.idle_threshold)
+    }
+
+    /// Logs a skipped cycle if verbose mode is on.
+    fn log_user_active(&self) {
+        if self.verbose {
+            print!("\r");
+            io::stdout().flush().unwrap();
+            println!(
+                "User active, skipping. Next check in {} seconds.",
+                self.interval.as_secs()
+            );
+        }
+    }
+
+    /// Refreshes sleep prevention, applying the failure policy when it fails. Each
+    /// attempt is counted in the metrics. Returns `false` if BusyCrab started shutting
+    /// down while waiting to retry.
+    fn refresh_sleep_prevention(&mut self) -> Result<bool> {
+        let mut retry = 0;
+        loop {
+            let result = self.platform.refresh();
+            self.metrics.record_sleep_prevention(self.backend, result.is_ok());
+            let err = match result {
+                Ok(()) => {
+                    if retry > 0 {
+                        self.failure_stats.recovered += 1;
+                    }
+                    return Ok(true);
+                }
+                Err(err) => err,
+            };
+            self.failure_stats.failures += 1;
+
+            match self.failure_policy {
+                FailurePolicy::FailFast => return Err(err),
+                FailurePolicy::Warn => {
+                    self.log_failure(&err, "continuing without it");
+                    return Ok(true);
+                }
+                FailurePolicy::Retry { .. } => {
+                    let Some(delay) = self.failure_policy.retry_delay(retry) else {
+                        return Err(err);
+                    };
+                    self.log_failure(&err, &format!("retrying in {:?}", delay));
+                    if !self.sleep_while_running(delay) {
+                        return Ok(false);
+                    }
+                    retry += 1;
+                    self.failure_stats.retries += 1;
+                }
+            }
+        }
+    }
+
+    /// Logs a sleep prevention failure if verbose mode is on.
+    fn log_failure(&self, err: &Error, action: &str) {
+        if self.verbose {
+            print!("\r");
+            io::stdout().flush().unwrap();
+            println!("Sleep prevention failed: {}; {}", err, action);
+        }
+    }
+
     /// Logs activity if verbose mode is on.
     fn log_activity_status(&self, activity_count: u64) {
         if self.verbose {
@@ -218,68 +938,182 @@ impl BusyCrab {
         }
     }
 
-    /// Waits until next cycle.
-    fn wait_for_next_cycle(&self, running: &Arc<AtomicBool>) -> bool {
+    /// Waits until next cycle. Settings changed while waiting take effect right away,
+    /// starting a fresh wait with the new interval.
+    fn wait_for_next_cycle(&mut self) -> bool {
+        loop {
+            if !self.sleep_while_running(self.interval) {
+                return false;
+            }
+            if !self.apply_pending_settings() {
+                return true;
+            }
+            self.draw_cycle_values();
+            let mut state = self.lock_run_state();
+            self.record_next_cycle(&mut state);
+        }
+    }
+
+    /// Sleeps for `duration`, waking early on shutdown, when BusyCrab is paused or
+    /// resumed, when settings change, or when the time limit is up. Returns `false` if
+    /// BusyCrab is shutting down.
+    fn sleep_while_running(&self, duration: Duration) -> bool {
         let step_sleep = Duration::from_millis(200);
-        let mut remaining = self.interval;
-        while remaining > Duration::from_millis(0) && running.load(Ordering::SeqCst) {
-            let sleep_time = if remaining < step_sleep {
-                remaining
-            } else {
-                step_sleep
-            };
+        let mut remaining = duration;
+        while remaining > Duration::from_millis(0)
+            && self.running.load(Ordering::SeqCst)
+            && !self.stop_at_deadline()
+            && !self.pause_changed()
+            && self.lock_run_state().pending_settings.is_none()
+        {
+            let mut sleep_time = remaining.min(step_sleep);
+            if let Some(left) = self.remaining_time() {
+                sleep_time = sleep_time.min(left);
+            }
             thread::sleep(sleep_time);
             remaining = remaining.saturating_sub(sleep_time);
         }
-        running.load(Ordering::SeqCst)
+        self.running.load(Ordering::SeqCst) && !self.stop_at_deadline()
+    }
+
+    /// Applies pause and resume signals, then checks if the pause changed since the
+    /// last cycle.
+    fn pause_changed(&self) -> bool {
+        #[cfg(unix)]
+        if let Some(command) = control::signals::take_pending() {
+            self.control_handle().apply(command);
+        }
+        self.paused.load(Ordering::SeqCst) != self.pause_applied
     }
 
-    /// Cleans up resources.
-    fn cleanup_resources(&self, animation_thread: AnimationThread) {
-        if let Some((handle, running_flag)) = animation_thread {
+    /// Stops the activity loop the same way Ctrl+C does once the time limit is up.
+    /// Returns `true` if the time limit is up.
+    fn stop_at_deadline(&self) -> bool {
+        if self.deadline.is_none_or(|deadline| Instant::now() < deadline) {
+            return false;
+        }
+        if self.running.swap(false, Ordering::SeqCst) {
+            println!("\n🦀 Time limit reached, shutting down gracefully...");
+        }
+        true
+    }
+
+    /// Stops the animation thread, if it's running.
+    fn stop_animation_thread(&mut self) {
+        if let Some((handle, running_flag)) = self.animation.take() {
             if let Ok(mut flag) = running_flag.lock() {
                 *flag = false;
             }
 
-            if let Err(_) = handle.join() {
-                if self.verbose {
-                    println!("");
-                    println!("Animation thread did not exit cleanly");
-                }
+            if handle.join().is_err() && self.verbose {
+                println!();
+                println!("Animation thread did not exit cleanly");
             }
         }
     }
 
+    /// Shows how often sleep prevention failed, if it did or in verbose mode.
+    fn display_failure_summary(&self) {
+        let stats = self.failure_stats;
+        if stats.failures == 0 && !self.verbose {
+            return;
+        }
+        println!();
+        println!(
+            "Sleep prevention failures: {} ({} retries, {} recovered)",
+            stats.failures, stats.retries, stats.recovered
+        );
+    }
+
     /// Shows shutdown message.
     fn display_shutdown_message(&self) {
-        println!("");
+        println!();
         println!("🦀 BusyCrab shut down successfully.");
     }
 
-    /// Moves mouse slightly to simulate activity.
+    /// Simulates activity with the mouse, the keyboard or both, depending on the activity mode.
     pub fn simulate_activity(&mut self) {
+        if self.activity.uses_mouse() {
+            self.wiggle_mouse();
+        }
+        if self.activity.uses_keyboard() {
+            self.tap_key();
+        }
+    }
+
+    /// Moves mouse slightly, following the wiggle pattern.
+    ///
+    /// In absolute mode the pattern is traced around the starting position, which is
+    /// restored at the end even if a screen edge clamped some of the moves.
+    fn wiggle_mouse(&mut self) {
+        if self.verbose {
+            print!("\r");
+            io::stdout().flush().unwrap();
+            println!(
+                "Moving mouse by {} pixels ({})",
+                self.wiggle_distance,
+                self.pattern.name()
+            );
+        }
+
+        let start = match self.positioning {
+            Positioning::Absolute => self.mouse.location(),
+            Positioning::Relative => None,
+        };
+        if self.positioning == Positioning::Absolute && start.is_none() && self.verbose {
+            println!("Mouse position unavailable, moving relatively");
+        }
+
+        let mut offset = (0, 0);
+        for step in self.pattern.steps(self.wiggle_distance) {
+            match start {
+                Some((x, y)) => {
+                    offset = (offset.0 + step.dx, offset.1 + step.dy);
+                    self.mouse.mouse_move_to(x + offset.0, y + offset.1);
+                }
+                None => self.mouse.mouse_move_relative(step.dx, step.dy),
+            }
+            if !step.delay.is_zero() {
+                thread::sleep(step.delay);
+            }
+        }
+
+        if let Some((x, y)) = start {
+            if self.mouse.location() != Some((x, y)) {
+                self.mouse.mouse_move_to(x, y);
+            }
+        }
+    }
+
+    /// Taps the activity key.
+    fn tap_key(&mut self) {
         if self.verbose {
             print!("\r");
             io::stdout().flush().unwrap();
-            println!("Moving mouse by {} pixels", self.wiggle_distance);
+            println!("Tapping {} key", self.activity_key);
+        }
+
+        for _ in 0..self.activity_key.taps() {
+            self.keyboard.key_tap(self.activity_key);
         }
-        
-        self.mouse.mouse_move_relative(self.wiggle_distance, 0);
-        thread::sleep(Duration::from_millis(100));
-        self.mouse.mouse_move_relative(-self.wiggle_distance, 0);
     }
 
     /// Starts animation thread if configured.
-    fn start_animation_thread(&mut self) -> AnimationThread {
-        if let Some(motion) = self.motion.take() {
+    fn start_animation_thread(&mut self) {
+        self.animation = if let Some(mut motion) = self.motion.take() {
+            motion.set_deadline(self.deadline);
             let running = Arc::new(Mutex::new(true));
             let running_clone = running.clone();
+            let paused = self.paused.clone();
+            let metrics = self.metrics.clone();
 
             let animation_thread = thread::spawn(move || {
                 let animation_interval = Duration::from_millis(50);
                 let mut motion = motion;
                 while *running_clone.lock().unwrap() {
+                    motion.set_paused(paused.load(Ordering::SeqCst));
                     motion.update();
+                    metrics.record_frame();
                     thread::sleep(animation_interval);
                 }
             });
@@ -287,7 +1121,7 @@ impl BusyCrab {
             Some((animation_thread, running))
         } else {
             None
-        }
+        };
     }
 
     /// Sets verbose mode.
@@ -298,31 +1132,262 @@ impl BusyCrab {
 
     /// Sets animation type.
     pub fn with_motion(mut self, motion_type: &str) -> Self {
-        self.motion = match motion_type.to_lowercase().as_str() {
-            "crab" => Some(Box::new(CrabMotion::new())),
-            "matrix" => Some(Box::new(MatrixMotion::new())),
-            "mandelbrot" => Some(Box::new(MandelbrotMotion::new())),
-            "clock" => Some(Box::new(ClockMotion::new())),
-            "none" | _ => None,
+        self.set_motion(motion_type);
+        self
+    }
+
+    /// Replaces the animation, which is none for unknown types.
+    fn set_motion(&mut self, motion_type: &str) {
+        self.motion = motion::from_name(motion_type);
+        self.motion_name = match &self.motion {
+            Some(_) => motion_type.to_lowercase(),
+            None => "none".to_string(),
         };
+    }
+
+    /// Sets the shape the mouse traces.
+    pub fn with_pattern(mut self, pattern: Box<dyn WigglePattern>) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Draws each cycle's interval, in seconds, from `range`.
+    pub fn with_interval_range(mut self, range: ValueRange<u64>) -> Self {
+        self.interval = Duration::from_secs(range.min);
+        self.interval_range = range;
+        self
+    }
+
+    /// Draws each cycle's wiggle distance, in pixels, from `range`.
+    pub fn with_wiggle_range(mut self, range: ValueRange<i32>) -> Self {
+        self.wiggle_distance = range.min;
+        self.wiggle_range = range;
         self
     }
 
-    /// Gets interval.
+    /// Changes each interval by up to `percent` percent either way.
+    pub fn with_jitter(mut self, percent: u32) -> Self {
+        self.jitter_percent = percent;
+        self
+    }
+
+    /// Seeds the random intervals and wiggle distances, to repeat them exactly.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Stops `run` once it has run for `limit`, or at the earlier of two limits.
+    pub fn with_time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(self.time_limit.map_or(limit, |current| current.min(limit)));
+        self
+    }
+
+    /// Only runs during `schedule`.
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Only runs during the matching events of `calendar`.
+    pub fn with_calendar(mut self, calendar: Calendar) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    /// Serves the HTTP API on `addr` while running. With a `token`, requests must
+    /// carry it as a bearer token; without one, `addr` must be a loopback address.
+    pub fn with_http_api(mut self, addr: SocketAddr, token: Option<String>) -> Self {
+        self.http_addr = Some(addr);
+        self.http_token = token;
+        self
+    }
+
+    /// Publishes the `io.github.guinetik.BusyCrab` D-Bus service on `bus` while running.
+    #[cfg(target_os = "linux")]
+    pub fn with_dbus_service(mut self, bus: control::dbus::Bus) -> Self {
+        self.dbus_bus = Some(bus);
+        self
+    }
+
+    /// Listens on the Unix domain socket at `path` while running, for
+    /// `busycrab status`, `pause`, `resume` and `stop`. Ignored on Windows.
+    pub fn with_control_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.control_socket = Some(path.into());
+        self
+    }
+
+    /// Takes the days in `holidays` off, pausing or shutting down according to `action`.
+    pub fn with_holidays(mut self, holidays: Holidays, action: HolidayAction) -> Self {
+        self.holidays = Some(holidays);
+        self.holiday_action = action;
+        self
+    }
+
+    /// Sets the clock the schedule is checked against.
+    /// Used for testing.
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets whether wiggles move by offsets or to exact positions.
+    pub fn with_positioning(mut self, positioning: Positioning) -> Self {
+        self.positioning = positioning;
+        self
+    }
+
+    /// Sets the keyboard controller used for key taps.
+    pub fn with_keyboard_controller(mut self, keyboard: Box<dyn KeyboardController>) -> Self {
+        self.keyboard = keyboard;
+        self
+    }
+
+    /// Sets which input each cycle simulates and the key to tap.
+    pub fn with_activity(mut self, activity: ActivityMode, key: ActivityKey) -> Self {
+        self.activity = activity;
+        self.activity_key = key;
+        self
+    }
+
+    /// Sets the sleep prevention backend, usually picked by `platform::select`.
+    pub fn with_platform(mut self, backend: Backend, platform: Box<dyn PlatformTrait>) -> Self {
+        self.backend = backend;
+        self.platform = platform;
+        self
+    }
+
+    /// Sets what happens when sleep prevention fails.
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Only simulates activity once `detector` reports the user idle for `threshold`.
+    pub fn with_idle_detector(mut self, detector: Box<dyn IdleDetector>, threshold: Duration) -> Self {
+        self.idle_detector = Some(detector);
+        self.idle_threshold = threshold;
+        self
+    }
+
+    /// Gets the interval drawn for the current cycle.
     pub fn get_interval(&self) -> Duration {
         self.interval
     }
 
-    /// Gets wiggle distance.
+    /// Gets the wiggle distance drawn for the current cycle.
     pub fn get_wiggle_distance(&self) -> i32 {
         self.wiggle_distance
     }
 
+    /// Gets the range intervals are drawn from, in seconds.
+    pub fn get_interval_range(&self) -> ValueRange<u64> {
+        self.interval_range
+    }
+
+    /// Gets the range wiggle distances are drawn from, in pixels.
+    pub fn get_wiggle_range(&self) -> ValueRange<i32> {
+        self.wiggle_range
+    }
+
+    /// Gets the positioning mode.
+    pub fn get_positioning(&self) -> Positioning {
+        self.positioning
+    }
+
+    /// Gets the activity mode.
+    pub fn get_activity(&self) -> ActivityMode {
+        self.activity
+    }
+
+    /// Gets the idle threshold, if an idle detector is set.
+    pub fn get_idle_threshold(&self) -> Option<Duration> {
+        self.idle_detector.as_ref().map(|_| self.idle_threshold)
+    }
+
+    /// Gets the sleep prevention backend.
+    pub fn get_backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Gets the current sleep prevention status.
+    pub fn sleep_prevention_status(&self) -> InhibitStatus {
+        self.platform.status()
+    }
+
+    /// Gets the schedule, if any.
+    pub fn get_schedule(&self) -> Option<&Schedule> {
+        self.schedule.as_ref()
+    }
+
+    /// Gets the time limit, if any.
+    pub fn get_time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    /// Gets the time left before `run` stops on its own.
+    /// `None` without a time limit or before `run` starts.
+    pub fn remaining_time(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Gets the calendar, if any.
+    pub fn get_calendar(&self) -> Option<&Calendar> {
+        self.calendar.as_ref()
+    }
+
+    /// Gets the holidays, if any.
+    pub fn get_holidays(&self) -> Option<&Holidays> {
+        self.holidays.as_ref()
+    }
+
+    /// Gets what happens on a holiday.
+    pub fn get_holiday_action(&self) -> HolidayAction {
+        self.holiday_action
+    }
+
+    /// Gets the failure policy.
+    pub fn get_failure_policy(&self) -> FailurePolicy {
+        self.failure_policy
+    }
+
+    /// Gets the sleep prevention failures seen so far.
+    pub fn failure_stats(&self) -> FailureStats {
+        self.failure_stats
+    }
+
+    /// Gets the flag that keeps the activity loop running.
+    /// Storing `false` stops `run` the same way Ctrl+C does.
+    pub fn shutdown_handle(&self) -> Arc<AtomicBool> {
+        self.running.clone()
+    }
+
+    /// Gets a handle to pause, resume, stop or check on BusyCrab from another thread.
+    pub fn control_handle(&self) -> ControlHandle {
+        ControlHandle::with_state(
+            self.running.clone(),
+            self.paused.clone(),
+            self.run_state.clone(),
+            self.metrics.clone(),
+        )
+    }
+
+    /// Checks if BusyCrab is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
     /// Checks if verbose mode is on.
     pub fn is_verbose(&self) -> bool {
         self.verbose
     }
 
+    /// Gets the name of the animation, `none` without one.
+    pub fn get_motion(&self) -> &str {
+        &self.motion_name
+    }
+
     /// Checks if motion is enabled.
     pub fn has_motion(&self) -> bool {
         self.motion.is_some()
@@ -0,0 +1,105 @@
+//! # Run Limits
+//!
+//! Stops BusyCrab after a while (`--duration 2h30m`) or at a time of day
+//! (`--until 17:45`), so it can't be left running by accident. Both turn into a time
+//! limit for `BusyCrab::run`, which then shuts down as if Ctrl+C had been pressed.
+
+use chrono::{NaiveDateTime, NaiveTime};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A length of time written with units, like `2h30m`, `90m` or `45s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunDuration(pub Duration);
+
+impl fmt::Display for RunDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+        let mut text = String::new();
+        if hours > 0 {
+            text.push_str(&format!("{}h", hours));
+        }
+        if minutes > 0 {
+            text.push_str(&format!("{}m", minutes));
+        }
+        if seconds > 0 || text.is_empty() {
+            text.push_str(&format!("{}s", seconds));
+        }
+        f.pad(&text)
+    }
+}
+
+impl FromStr for RunDuration {
+    type Err = String;
+
+    /// Parses numbers each followed by `h`, `m` or `s`, like `1h30m` or `2h 5s`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid duration '{}' (expected something like 2h30m)", s);
+        let mut total = 0u64;
+        let mut number = String::new();
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let unit = match c.to_ascii_lowercase() {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return Err(invalid()),
+            };
+            let value: u64 = number.parse().map_err(|_| invalid())?;
+            total = value
+                .checked_mul(unit)
+                .and_then(|secs| total.checked_add(secs))
+                .ok_or_else(invalid)?;
+            number.clear();
+        }
+        if !number.is_empty() {
+            return Err(format!(
+                "missing unit in duration '{}' (use h, m or s, like 1h30m)",
+                s
+            ));
+        }
+        if total == 0 {
+            return Err(invalid());
+        }
+        Ok(RunDuration(Duration::from_secs(total)))
+    }
+}
+
+/// A local time of day to stop at, like `17:45`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StopTime(pub NaiveTime);
+
+impl StopTime {
+    /// Gets the time from `now` until the next time the clock shows this time,
+    /// which is tomorrow if it has already passed today.
+    pub fn duration_from(self, now: NaiveDateTime) -> Duration {
+        let mut stop = now.date().and_time(self.0);
+        if stop <= now {
+            stop += chrono::Duration::days(1);
+        }
+        (stop - now).to_std().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for StopTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.0.format("%H:%M").to_string())
+    }
+}
+
+impl FromStr for StopTime {
+    type Err = String;
+
+    /// Parses `HH:MM` or `HH:MM:SS`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NaiveTime::parse_from_str(s.trim(), "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(s.trim(), "%H:%M:%S"))
+            .map(StopTime)
+            .map_err(|_| format!("invalid time '{}' (expected HH:MM)", s))
+    }
+}
@@ -1,24 +1,247 @@
+use std::error::Error as _;
 use std::process;
-use busycrab::cli::Args;
-use busycrab::BusyCrab;
-use clap::Parser;
+use std::time::Duration;
+use busycrab::cli::{Args, Command};
+#[cfg(unix)]
+use busycrab::control::socket;
+use busycrab::pattern::PatternOptions;
+use busycrab::platform;
+use busycrab::schedule::{Calendar, Holidays, Schedule};
+use busycrab::{BusyCrab, Error};
+use chrono::Local;
 
 pub fn main() {
-    let args = Args::parse();
+    let args = match Args::load() {
+        Ok(args) => args,
+        Err(err) => {
+            report_error(&err);
+            process::exit(1);
+        }
+    };
+
+    if let Some(Command::Backends) = args.command {
+        print_backends();
+        return;
+    }
+    if let Some(request) = args.command.as_ref().and_then(Command::control_request) {
+        send_control_request(&args, request);
+        return;
+    }
     
     if args.verbose {
         println!("Configuration:");
+        if let Some(profile) = &args.profile {
+            println!("  Profile: {}", profile);
+        }
         println!("  Interval: {} seconds", args.interval);
         println!("  Wiggle distance: {} pixels", args.wiggle);
+        if args.jitter > 0 {
+            println!("  Jitter: {}%", args.jitter);
+        }
         println!("  Motion type: {}", args.motion);
+        println!("  Pattern: {}", args.pattern);
+        println!("  Positioning: {}", args.positioning);
+        println!("  Activity: {}", args.activity);
+        if args.activity.uses_keyboard() {
+            println!("  Key: {}", args.key);
+        }
+        println!("  Input: {}", args.input);
+        println!("  On failure: {}", args.on_failure);
+        if let Some(duration) = args.duration {
+            println!("  Duration: {}", duration);
+        }
+        if let Some(until) = args.until {
+            println!("  Until: {}", until);
+        }
+        if args.idle_threshold > 0 {
+            println!("  Idle threshold: {} seconds ({})", args.idle_threshold, args.idle_source);
+        }
+        if let Some(addr) = args.http {
+            println!("  HTTP API: {}", addr);
+        }
+        if args.dbus {
+            println!("  D-Bus service: io.github.guinetik.BusyCrab");
+        }
     }
+
+    let schedule = match load_schedule(&args) {
+        Ok(schedule) => schedule,
+        Err(err) => {
+            report_error(&err);
+            process::exit(1);
+        }
+    };
+
+    let calendar = match load_calendar(&args) {
+        Ok(calendar) => calendar,
+        Err(err) => {
+            report_error(&err);
+            process::exit(1);
+        }
+    };
+
+    let holidays = match args.holidays.as_ref().map(Holidays::from_file).transpose() {
+        Ok(holidays) => holidays,
+        Err(err) => {
+            report_error(&err);
+            process::exit(1);
+        }
+    };
+
+    let (backend, platform) = match platform::select(args.backend) {
+        Ok(selected) => selected,
+        Err(err) => {
+            report_error(&err);
+            process::exit(1);
+        }
+    };
     
-    let mut crab = BusyCrab::new(args.interval, args.wiggle)
+    let mouse = match args.input.create() {
+        Ok(mouse) => mouse,
+        Err(err) => {
+            report_error(&err);
+            process::exit(1);
+        }
+    };
+
+    let idle_detector = if args.idle_threshold > 0 {
+        match args.idle_source.create() {
+            Ok(detector) => Some(detector),
+            Err(err) => {
+                report_error(&err);
+                process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut crab = BusyCrab::with_mouse_controller(args.interval.min, args.wiggle.min, mouse)
+        .with_interval_range(args.interval)
+        .with_wiggle_range(args.wiggle)
+        .with_jitter(args.jitter)
         .with_verbose(args.verbose)
-        .with_motion(&args.motion);
+        .with_motion(&args.motion)
+        .with_pattern(args.pattern.create_with(PatternOptions {
+            seed: args.seed,
+            duration: Duration::from_millis(args.pattern_duration),
+        }))
+        .with_positioning(args.positioning)
+        .with_activity(args.activity, args.key)
+        .with_platform(backend, platform)
+        .with_failure_policy(args.on_failure.policy(args.max_retries));
+    if let Some(seed) = args.seed {
+        crab = crab.with_seed(seed);
+    }
+    if let Some(schedule) = schedule {
+        crab = crab.with_schedule(schedule);
+    }
+    if let Some(calendar) = calendar {
+        crab = crab.with_calendar(calendar);
+    }
+    if let Some(holidays) = holidays {
+        crab = crab.with_holidays(holidays, args.on_holiday);
+    }
+    if let Some(duration) = args.duration {
+        crab = crab.with_time_limit(duration.0);
+    }
+    if let Some(until) = args.until {
+        crab = crab.with_time_limit(until.duration_from(Local::now().naive_local()));
+    }
+    if let Some(detector) = idle_detector {
+        crab = crab.with_idle_detector(detector, Duration::from_secs(args.idle_threshold));
+    }
+    if let Some(addr) = args.http {
+        crab = crab.with_http_api(addr, args.http_token.clone());
+    }
+    #[cfg(target_os = "linux")]
+    if args.dbus {
+        crab = crab.with_dbus_service(busycrab::control::dbus::Bus::Session);
+    }
+    #[cfg(unix)]
+    {
+        crab = crab.with_control_socket(control_socket_path(&args));
+    }
     
     if let Err(err) = crab.run() {
-        eprintln!("Error: {}", err);
+        report_error(&err);
         process::exit(1);
     }
 }
+
+/// Combines `--schedule` and `--schedule-file` into one schedule, if either is set.
+fn load_schedule(args: &Args) -> Result<Option<Schedule>, Error> {
+    let mut schedule = args.schedule.clone();
+    if let Some(path) = &args.schedule_file {
+        let from_file = Schedule::from_file(path)?;
+        match &mut schedule {
+            Some(schedule) => schedule.merge(from_file),
+            None => schedule = Some(from_file),
+        }
+    }
+    Ok(schedule)
+}
+
+/// Loads `--calendar` with `--calendar-filter`, if set.
+fn load_calendar(args: &Args) -> Result<Option<Calendar>, Error> {
+    let Some(path) = &args.calendar else {
+        return Ok(None);
+    };
+    let calendar = Calendar::load(path)?;
+    Ok(Some(match &args.calendar_filter {
+        Some(filter) => calendar.with_filter(filter),
+        None => calendar,
+    }))
+}
+
+/// Gets `--socket`, or the default control socket path.
+#[cfg(unix)]
+fn control_socket_path(args: &Args) -> std::path::PathBuf {
+    args.socket.clone().unwrap_or_else(socket::default_path)
+}
+
+/// Sends `request` to the running BusyCrab and prints the status it answers with.
+#[cfg(unix)]
+fn send_control_request(args: &Args, request: &str) {
+    match socket::send(control_socket_path(args), request) {
+        Ok(status) => println!("{}", status),
+        Err(err) => {
+            report_error(&err);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn send_control_request(_args: &Args, _request: &str) {
+    report_error(&Error::unsupported(
+        "Controlling a running BusyCrab is only available on Unix",
+    ));
+    process::exit(1);
+}
+
+/// Prints an error and the chain of errors that caused it.
+fn report_error(err: &Error) {
+    eprintln!("Error: {}", err);
+    let mut source = err.source();
+    while let Some(cause) = source {
+        eprintln!("  Caused by: {}", cause);
+        source = cause.source();
+    }
+}
+
+/// Prints every sleep prevention backend for this OS and whether it can be used.
+fn print_backends() {
+    println!("Sleep prevention backends, in order of preference:");
+    let mut selected = false;
+    for (backend, result) in platform::probe_all() {
+        match result {
+            Ok(()) if !selected => {
+                selected = true;
+                println!("  {:<12} available (selected)", backend);
+            }
+            Ok(()) => println!("  {:<12} available", backend),
+            Err(reason) => println!("  {:<12} unavailable: {}", backend, reason),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! # Metrics
+//!
+//! Counters a running BusyCrab keeps about what it did, for Prometheus and other
+//! scrapers. The HTTP API serves them at `GET /metrics` in the text exposition
+//! format:
+//!
+//! ```text
+//! # HELP busycrab_activity_cycles_total Activity cycles that simulated activity.
+//! # TYPE busycrab_activity_cycles_total counter
+//! busycrab_activity_cycles_total 12
+//! ```
+//!
+//! | Metric                               | Type    | Labels              |
+//! |--------------------------------------|---------|---------------------|
+//! | `busycrab_activity_cycles_total`     | counter |                     |
+//! | `busycrab_skipped_cycles_total`      | counter | `reason`            |
+//! | `busycrab_sleep_prevention_total`    | counter | `backend`, `result` |
+//! | `busycrab_animation_frames_total`    | counter |                     |
+//! | `busycrab_paused`                    | gauge   |                     |
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::control::CycleOutcome;
+use crate::platform::Backend;
+
+/// Content type of `Metrics::render` output.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Why a cycle didn't simulate activity, as `(outcome, reason label)`.
+const SKIP_REASONS: [(CycleOutcome, &str); 4] = [
+    (CycleOutcome::UserActive, "idle"),
+    (CycleOutcome::OffSchedule, "schedule"),
+    (CycleOutcome::Holiday, "holiday"),
+    (CycleOutcome::Paused, "paused"),
+];
+
+/// Sleep prevention refreshes through one backend.
+#[derive(Debug, Clone, Copy)]
+struct SleepPreventionCounts {
+    backend: Backend,
+    successes: u64,
+    failures: u64,
+}
+
+/// Counters shared by the activity loop, the animation thread and the HTTP API.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    activity_cycles: AtomicU64,
+    /// Skipped cycles, in `SKIP_REASONS` order
+    skipped_cycles: [AtomicU64; 4],
+    sleep_prevention: Mutex<Vec<SleepPreventionCounts>>,
+    animation_frames: AtomicU64,
+}
+
+impl Metrics {
+    /// Counts a finished activity cycle by what it did.
+    pub fn record_cycle(&self, outcome: CycleOutcome) {
+        let counter = match SKIP_REASONS.iter().position(|(skip, _)| *skip == outcome) {
+            Some(index) => &self.skipped_cycles[index],
+            None => &self.activity_cycles,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts one attempt to refresh sleep prevention through `backend`.
+    pub fn record_sleep_prevention(&self, backend: Backend, succeeded: bool) {
+        let mut counts = self
+            .sleep_prevention
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let index = match counts.iter().position(|count| count.backend == backend) {
+            Some(index) => index,
+            None => {
+                counts.push(SleepPreventionCounts {
+                    backend,
+                    successes: 0,
+                    failures: 0,
+                });
+                counts.len() - 1
+            }
+        };
+        if succeeded {
+            counts[index].successes += 1;
+        } else {
+            counts[index].failures += 1;
+        }
+    }
+
+    /// Counts one rendered animation frame.
+    pub fn record_frame(&self) {
+        self.animation_frames.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Gets the activity cycles that simulated activity.
+    pub fn activity_cycles(&self) -> u64 {
+        self.activity_cycles.load(Ordering::Relaxed)
+    }
+
+    /// Gets the animation frames rendered.
+    pub fn animation_frames(&self) -> u64 {
+        self.animation_frames.load(Ordering::Relaxed)
+    }
+
+    /// Writes the metrics in the Prometheus text exposition format.
+    ///
+    /// * `paused` - Whether BusyCrab is paused, for the `busycrab_paused` gauge
+    pub fn render(&self, paused: bool) -> String {
+        let mut out = String::new();
+
+        family(
+            &mut out,
+            "busycrab_activity_cycles_total",
+            "counter",
+            "Activity cycles that simulated activity.",
+        );
+        sample(
+            &mut out,
+            "busycrab_activity_cycles_total",
+            "",
+            self.activity_cycles(),
+        );
+
+        family(
+            &mut out,
+            "busycrab_skipped_cycles_total",
+            "counter",
+            "Activity cycles that didn't simulate activity, by reason.",
+        );
+        for ((_, reason), counter) in SKIP_REASONS.iter().zip(&self.skipped_cycles) {
+            sample(
+                &mut out,
+                "busycrab_skipped_cycles_total",
+                &format!("reason=\"{}\"", reason),
+                counter.load(Ordering::Relaxed),
+            );
+        }
+
+        family(
+            &mut out,
+            "busycrab_sleep_prevention_total",
+            "counter",
+            "Attempts to refresh sleep prevention, by backend and result.",
+        );
+        let counts = self
+            .sleep_prevention
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        for count in counts {
+            for (result, value) in [("success", count.successes), ("failure", count.failures)] {
+                sample(
+                    &mut out,
+                    "busycrab_sleep_prevention_total",
+                    &format!("backend=\"{}\",result=\"{}\"", count.backend, result),
+                    value,
+                );
+            }
+        }
+
+        family(
+            &mut out,
+            "busycrab_animation_frames_total",
+            "counter",
+            "Animation frames rendered.",
+        );
+        sample(
+            &mut out,
+            "busycrab_animation_frames_total",
+            "",
+            self.animation_frames(),
+        );
+
+        family(
+            &mut out,
+            "busycrab_paused",
+            "gauge",
+            "Whether BusyCrab is paused.",
+        );
+        sample(&mut out, "busycrab_paused", "", paused as u64);
+
+        out
+    }
+}
+
+/// Writes the `HELP` and `TYPE` lines of a metric.
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Writes one sample of a metric, with `labels` like `reason="idle"`.
+fn sample(out: &mut String, name: &str, labels: &str, value: u64) {
+    if labels.is_empty() {
+        let _ = writeln!(out, "{} {}", name, value);
+    } else {
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+    }
+}
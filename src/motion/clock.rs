@@ -1,5 +1,6 @@
 use super::Motion;
 use std::io::{self, Write};
+use std::time::{Duration, Instant};
 use chrono::{Local, Timelike};
 
 pub struct ClockMotion {
@@ -11,6 +12,10 @@ pub struct ClockMotion {
     // Clock parameters
     last_second: u64,
     cycling_chars: Vec<Vec<char>>,
+
+    // When BusyCrab stops, for the countdown under the clock
+    deadline: Option<Instant>,
+    paused: bool,
 }
 
 impl ClockMotion {
@@ -51,6 +56,8 @@ impl ClockMotion {
             frame_count: 0,
             last_second: 0,
             cycling_chars,
+            deadline: None,
+            paused: false,
         }
     }
 
@@ -191,6 +198,17 @@ impl ClockMotion {
     }
 }
 
+/// Formats the time left as `H:MM:SS`, or `MM:SS` under an hour.
+pub fn format_countdown(remaining: Duration) -> String {
+    let secs = remaining.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
 impl Motion for ClockMotion {
     fn update(&mut self) {
         // Get the latest terminal dimensions in case they changed
@@ -252,6 +270,18 @@ impl Motion for ClockMotion {
             clock_lines.push(combined_line);
         }
 
+        // Count down to the time limit, if there is one
+        if let Some(deadline) = self.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            clock_lines.push(String::new());
+            clock_lines.push(format!("{} left", format_countdown(remaining)));
+        }
+
+        if self.paused {
+            clock_lines.push(String::new());
+            clock_lines.push(super::PAUSED_LABEL.to_string());
+        }
+
         // Center the clock on screen
         let centered_lines = self.center_clock(&clock_lines);
 
@@ -266,6 +296,14 @@ impl Motion for ClockMotion {
 
         self.frame_count += 1;
     }
+
+    fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
 }
 
 impl Drop for ClockMotion {
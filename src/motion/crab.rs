@@ -6,6 +6,7 @@ pub struct CrabMotion {
     direction: i32,
     terminal_width: usize,
     first_update: bool,
+    paused: bool,
 }
 
 impl CrabMotion {
@@ -21,6 +22,7 @@ impl CrabMotion {
             direction: 1,
             terminal_width: width,
             first_update: true,
+            paused: false,
         }
     }
 }
@@ -32,11 +34,13 @@ impl Motion for CrabMotion {
             self.terminal_width = w.saturating_sub(5);
         }
         
-        // Update position
+        // Update position, staying put while paused
         let new_position = self.position as i32 + self.direction;
         
         // Check boundaries and reverse direction if needed
-        if new_position <= 0 {
+        if self.paused {
+            // Keep the crab where it is
+        } else if new_position <= 0 {
             self.position = 0;
             self.direction = 1;
         } else if new_position >= self.terminal_width as i32 {
@@ -59,10 +63,17 @@ impl Motion for CrabMotion {
         // Print the crab at its position without a newline
         let spaces = " ".repeat(self.position);
         print!("{}🦀", spaces);
+        if self.paused {
+            print!(" {}", super::PAUSED_LABEL);
+        }
         
         // Flush stdout to ensure the crab is displayed immediately
         let _ = std::io::stdout().flush();
     }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
 }
 
 impl Drop for CrabMotion {
@@ -6,6 +6,7 @@ pub struct MandelbrotMotion {
     terminal_height: usize,
     first_update: bool,
     frame_count: u32,
+    paused: bool,
     
     // Mandelbrot parameters
     center_x: f64,
@@ -58,6 +59,7 @@ impl MandelbrotMotion {
             terminal_height: height,
             first_update: true,
             frame_count: 0,
+            paused: false,
             
             // Start with first waypoint
             center_x: waypoints[0].0,
@@ -197,8 +199,10 @@ impl Motion for MandelbrotMotion {
             print!("\x1B[?25l\x1B[2J\x1B[H");
         }
 
-        // Update animation parameters
-        self.update_animation();
+        // Freeze while paused, otherwise update animation parameters
+        if !self.paused {
+            self.update_animation();
+        }
 
         // Clear screen and move cursor to top
         print!("\x1B[2J\x1B[H");
@@ -239,12 +243,20 @@ impl Motion for MandelbrotMotion {
             println!();
         }
 
+        if self.paused {
+            super::print_paused_banner();
+        }
+
         // Reset color and flush
         print!("\x1B[0m");
         let _ = std::io::stdout().flush();
 
         self.frame_count += 1;
     }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
 }
 
 impl Drop for MandelbrotMotion {
@@ -12,6 +12,7 @@ pub struct MatrixMotion {
     first_update: bool,
     frame_count: u32,
     symbols: Vec<char>,
+    paused: bool,
 }
 
 impl MatrixMotion {
@@ -61,6 +62,7 @@ impl MatrixMotion {
             first_update: true,
             frame_count: 0,
             symbols,
+            paused: false,
         }
     }
 
@@ -145,9 +147,11 @@ impl Motion for MatrixMotion {
             print!("\x1B[?25l\x1B[2J\x1B[H");
         }
 
-        // Update drops and grid
-        self.update_drops();
-        self.update_grid();
+        // Freeze while paused, otherwise update drops and grid
+        if !self.paused {
+            self.update_drops();
+            self.update_grid();
+        }
 
         // Clear screen and move cursor to top
         print!("\x1B[2J\x1B[H");
@@ -177,12 +181,20 @@ impl Motion for MatrixMotion {
             println!();
         }
 
+        if self.paused {
+            super::print_paused_banner();
+        }
+
         // Reset color and flush
         print!("\x1B[0m");
         let _ = std::io::stdout().flush();
 
         self.frame_count += 1;
     }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
 }
 
 impl Drop for MatrixMotion {
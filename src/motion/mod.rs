@@ -1,5 +1,42 @@
+use std::time::Instant;
+
 pub trait Motion: Send {
     fn update(&mut self);
+
+    /// Tells the motion when BusyCrab will stop, for motions that show a countdown.
+    /// Called once before the first update; `None` means no time limit.
+    fn set_deadline(&mut self, _deadline: Option<Instant>) {}
+
+    /// Tells the motion whether BusyCrab is paused, so it can freeze and say so.
+    /// Called before every update.
+    fn set_paused(&mut self, _paused: bool) {}
+}
+
+/// Names `from_name` knows, including `none` for no animation.
+pub const NAMES: [&str; 5] = ["crab", "matrix", "mandelbrot", "clock", "none"];
+
+/// Creates the motion called `name`, ignoring case.
+///
+/// ### Returns
+///
+/// - `Some` with the motion.
+/// - `None` for `none` and names that aren't in `NAMES`.
+pub fn from_name(name: &str) -> Option<Box<dyn Motion + Send>> {
+    match name.to_lowercase().as_str() {
+        "crab" => Some(Box::new(crab::CrabMotion::new())),
+        "matrix" => Some(Box::new(matrix::MatrixMotion::new())),
+        "mandelbrot" => Some(Box::new(mandelbrot::MandelbrotMotion::new())),
+        "clock" => Some(Box::new(clock::ClockMotion::new())),
+        _ => None,
+    }
+}
+
+/// Label motions show while BusyCrab is paused.
+pub const PAUSED_LABEL: &str = "⏸ paused";
+
+/// Draws `PAUSED_LABEL` over the top left corner of a full-screen motion.
+fn print_paused_banner() {
+    print!("\x1B[H\x1B[0m\x1B[7m {} \x1B[0m", PAUSED_LABEL);
 }
 
 pub mod crab;
@@ -0,0 +1,135 @@
+//! Bezier pattern: glides out and back along random curves, easing in and out.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::time::Duration;
+
+use super::{steps_through, Step, WigglePattern};
+
+/// Time between two movements, close to how often a real mouse reports.
+const STEP_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Fewest points on each curve, however short the duration.
+const MIN_POINTS: u32 = 4;
+
+/// Largest random offset, in pixels, added to points along the way.
+const JITTER: i32 = 1;
+
+/// A point on the plane, in pixels from the start.
+type Point = (f64, f64);
+
+/// Glides the cursor to a random point within the wiggle distance and back, each way
+/// along a cubic Bezier curve with random bends.
+///
+/// Movement speeds up and slows down (cubic ease-in/ease-out) and picks up a pixel of
+/// jitter, so the event cadence looks like a hand on a mouse rather than a jump.
+pub struct BezierPattern {
+    rng: ChaCha8Rng,
+    /// Time for the way out and back together
+    duration: Duration,
+}
+
+impl BezierPattern {
+    /// Creates a pattern that glides for `duration` per wiggle.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            rng: ChaCha8Rng::from_rng(&mut rand::rng()),
+            duration,
+        }
+    }
+
+    /// Creates a pattern that always takes the same steps for the same seed.
+    pub fn with_seed(seed: u64, duration: Duration) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            duration,
+        }
+    }
+
+    /// Picks control points for a curve from `from` to `to` that bends by up to `bend`
+    /// pixels on either side of the straight line.
+    fn control_points(&mut self, from: Point, to: Point, bend: f64) -> [Point; 4] {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length = dx.hypot(dy).max(1.0);
+        let normal = (-dy / length, dx / length);
+
+        let mut along = |fraction: f64| {
+            let offset = if bend > 0.0 {
+                self.rng.random_range(-bend..=bend)
+            } else {
+                0.0
+            };
+            (
+                from.0 + dx * fraction + normal.0 * offset,
+                from.1 + dy * fraction + normal.1 * offset,
+            )
+        };
+        let first = along(1.0 / 3.0);
+        let second = along(2.0 / 3.0);
+        [from, first, second, to]
+    }
+
+    /// Samples `count` points along a curve, spaced evenly in time and eased in distance,
+    /// jittering all but the last.
+    fn sample(&mut self, curve: [Point; 4], count: u32, points: &mut Vec<(i32, i32)>) {
+        for i in 1..=count {
+            let t = ease_in_out(f64::from(i) / f64::from(count));
+            let (x, y) = cubic_bezier(curve, t);
+            let (mut x, mut y) = (x.round() as i32, y.round() as i32);
+            if i < count {
+                x += self.rng.random_range(-JITTER..=JITTER);
+                y += self.rng.random_range(-JITTER..=JITTER);
+            }
+            points.push((x, y));
+        }
+    }
+}
+
+impl WigglePattern for BezierPattern {
+    fn name(&self) -> &'static str {
+        "bezier"
+    }
+
+    fn steps(&mut self, distance: i32) -> Vec<Step> {
+        let radius = f64::from(distance.abs());
+        let angle = self.rng.random_range(0.0..std::f64::consts::TAU);
+        let reach = radius * self.rng.random_range(0.5..=1.0);
+        let target = (
+            (reach * angle.cos()).round(),
+            (reach * angle.sin()).round(),
+        );
+
+        let per_curve = (self.duration.as_millis() / STEP_INTERVAL.as_millis() / 2) as u32;
+        let count = per_curve.max(MIN_POINTS);
+        let bend = radius / 2.0;
+
+        let mut points = Vec::with_capacity(2 * count as usize);
+        let out = self.control_points((0.0, 0.0), target, bend);
+        self.sample(out, count, &mut points);
+        let back = self.control_points(target, (0.0, 0.0), bend);
+        self.sample(back, count, &mut points);
+        // The back curve ends at the start, which steps_through adds itself
+        points.pop();
+
+        steps_through(&points, STEP_INTERVAL)
+    }
+}
+
+/// Cubic ease-in/ease-out: slow start, fast middle, slow finish.
+pub fn ease_in_out(t: f64) -> f64 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+/// Evaluates a cubic Bezier curve at `t` in `0..=1`.
+fn cubic_bezier([p0, p1, p2, p3]: [Point; 4], t: f64) -> Point {
+    let u = 1.0 - t;
+    let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+    (
+        a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+        a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+    )
+}
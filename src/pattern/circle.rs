@@ -0,0 +1,35 @@
+//! Circle pattern: one lap around a circle the size of the wiggle distance.
+
+use super::{sample_curve, steps_through, Step, WigglePattern, STEP_DELAY};
+
+/// Number of points around the circle.
+const POINTS: usize = 16;
+
+/// Traces a circle whose diameter is the wiggle distance, starting and ending
+/// on its leftmost point.
+pub struct CirclePattern;
+
+impl CirclePattern {
+    /// Creates a circle pattern.
+    pub fn new() -> Self {
+        CirclePattern
+    }
+}
+
+impl Default for CirclePattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WigglePattern for CirclePattern {
+    fn name(&self) -> &'static str {
+        "circle"
+    }
+
+    fn steps(&mut self, distance: i32) -> Vec<Step> {
+        let radius = f64::from(distance) / 2.0;
+        let points = sample_curve(POINTS, |t| (radius - radius * t.cos(), radius * t.sin()));
+        steps_through(&points, STEP_DELAY)
+    }
+}
@@ -0,0 +1,35 @@
+//! Figure-eight pattern: one pass along a figure eight centered on the cursor.
+
+use super::{sample_curve, steps_through, Step, WigglePattern, STEP_DELAY};
+
+/// Number of points along the figure eight.
+const POINTS: usize = 24;
+
+/// Traces a figure eight (a lemniscate of Gerono) that is twice the wiggle distance
+/// wide, centered on the starting point.
+pub struct FigureEightPattern;
+
+impl FigureEightPattern {
+    /// Creates a figure-eight pattern.
+    pub fn new() -> Self {
+        FigureEightPattern
+    }
+}
+
+impl Default for FigureEightPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WigglePattern for FigureEightPattern {
+    fn name(&self) -> &'static str {
+        "figure-eight"
+    }
+
+    fn steps(&mut self, distance: i32) -> Vec<Step> {
+        let size = f64::from(distance);
+        let points = sample_curve(POINTS, |t| (size * t.sin(), size * t.sin() * t.cos()));
+        steps_through(&points, STEP_DELAY)
+    }
+}
@@ -0,0 +1,33 @@
+//! Horizontal pattern: the classic move right and back again.
+
+use super::{Step, WigglePattern};
+use std::time::Duration;
+
+/// Moves right by the wiggle distance, waits 100 ms, then moves back.
+pub struct HorizontalPattern;
+
+impl HorizontalPattern {
+    /// Creates a horizontal pattern.
+    pub fn new() -> Self {
+        HorizontalPattern
+    }
+}
+
+impl Default for HorizontalPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WigglePattern for HorizontalPattern {
+    fn name(&self) -> &'static str {
+        "horizontal"
+    }
+
+    fn steps(&mut self, distance: i32) -> Vec<Step> {
+        vec![
+            Step::new(distance, 0, Duration::from_millis(100)),
+            Step::new(-distance, 0, Duration::ZERO),
+        ]
+    }
+}
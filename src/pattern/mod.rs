@@ -0,0 +1,208 @@
+//! # Wiggle Patterns
+//!
+//! Shapes the mouse traces on each activity cycle. A pattern turns the wiggle distance
+//! into a list of relative steps, each followed by a short pause, that always ends
+//! where it started so the cursor doesn't drift.
+//!
+//! - **horizontal**: Right and back again (the default)
+//! - **circle**: Around a circle
+//! - **square**: Around the corners of a square
+//! - **random-walk**: Random steps that stay within the wiggle distance
+//! - **figure-eight**: Along a figure eight
+//! - **bezier**: Glides out and back along random curves, easing in and out like a hand would
+//!
+//! The random patterns take a seed so the same seed always produces the same steps.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub mod bezier;
+pub mod circle;
+pub mod figure_eight;
+pub mod horizontal;
+pub mod random_walk;
+pub mod square;
+
+use bezier::BezierPattern;
+use circle::CirclePattern;
+use figure_eight::FigureEightPattern;
+use horizontal::HorizontalPattern;
+use random_walk::RandomWalkPattern;
+use square::SquarePattern;
+
+/// Pause after each step of the multi-step patterns.
+pub const STEP_DELAY: Duration = Duration::from_millis(20);
+
+/// Time the bezier pattern takes unless told otherwise.
+pub const DEFAULT_GLIDE_DURATION: Duration = Duration::from_millis(800);
+
+/// One relative mouse movement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// Horizontal movement in pixels
+    pub dx: i32,
+    /// Vertical movement in pixels
+    pub dy: i32,
+    /// Pause after the movement
+    pub delay: Duration,
+}
+
+impl Step {
+    /// Creates a step.
+    pub fn new(dx: i32, dy: i32, delay: Duration) -> Self {
+        Self { dx, dy, delay }
+    }
+}
+
+/// A shape for the mouse to trace.
+pub trait WigglePattern {
+    /// Name used on the command line and in logs.
+    fn name(&self) -> &'static str;
+
+    /// Produces the steps for one wiggle.
+    /// Called once per activity cycle.
+    ///
+    /// * `distance` - Size of the shape in pixels
+    ///
+    /// The steps must add up to no movement at all.
+    fn steps(&mut self, distance: i32) -> Vec<Step>;
+}
+
+/// Built-in patterns, as chosen with `--pattern`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PatternKind {
+    /// `HorizontalPattern`
+    #[default]
+    Horizontal,
+    /// `CirclePattern`
+    Circle,
+    /// `SquarePattern`
+    Square,
+    /// `RandomWalkPattern`
+    RandomWalk,
+    /// `FigureEightPattern`
+    FigureEight,
+    /// `BezierPattern`
+    Bezier,
+}
+
+/// Settings for `PatternKind::create_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternOptions {
+    /// Seed for the random patterns; `None` picks a random seed
+    pub seed: Option<u64>,
+    /// Time the bezier pattern takes for the whole wiggle
+    pub duration: Duration,
+}
+
+impl Default for PatternOptions {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            duration: DEFAULT_GLIDE_DURATION,
+        }
+    }
+}
+
+impl PatternKind {
+    /// All built-in patterns.
+    pub const ALL: [PatternKind; 6] = [
+        PatternKind::Horizontal,
+        PatternKind::Circle,
+        PatternKind::Square,
+        PatternKind::RandomWalk,
+        PatternKind::FigureEight,
+        PatternKind::Bezier,
+    ];
+
+    /// Name used on the command line and in logs.
+    pub fn name(self) -> &'static str {
+        match self {
+            PatternKind::Horizontal => "horizontal",
+            PatternKind::Circle => "circle",
+            PatternKind::Square => "square",
+            PatternKind::RandomWalk => "random-walk",
+            PatternKind::FigureEight => "figure-eight",
+            PatternKind::Bezier => "bezier",
+        }
+    }
+
+    /// Creates the pattern with default options.
+    pub fn create(self) -> Box<dyn WigglePattern> {
+        self.create_with(PatternOptions::default())
+    }
+
+    /// Creates the pattern.
+    pub fn create_with(self, options: PatternOptions) -> Box<dyn WigglePattern> {
+        let seed = options.seed.unwrap_or_else(rand::random);
+        match self {
+            PatternKind::Horizontal => Box::new(HorizontalPattern::new()),
+            PatternKind::Circle => Box::new(CirclePattern::new()),
+            PatternKind::Square => Box::new(SquarePattern::new()),
+            PatternKind::RandomWalk => Box::new(RandomWalkPattern::with_seed(seed)),
+            PatternKind::FigureEight => Box::new(FigureEightPattern::new()),
+            PatternKind::Bezier => Box::new(BezierPattern::with_seed(seed, options.duration)),
+        }
+    }
+}
+
+impl fmt::Display for PatternKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for PatternKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_lowercase().replace('_', "-");
+        PatternKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown pattern '{}' (expected horizontal, circle, square, random-walk, \
+                     figure-eight or bezier)",
+                    s
+                )
+            })
+    }
+}
+
+/// Turns positions relative to the starting point into steps, adding a final step
+/// back to the start.
+///
+/// A point equal to the previous one makes no move; its pause is added to a
+/// neighbouring step instead, so the pattern still takes as long.
+pub fn steps_through(points: &[(i32, i32)], delay: Duration) -> Vec<Step> {
+    let mut steps: Vec<Step> = Vec::with_capacity(points.len() + 1);
+    let mut current = (0, 0);
+    let mut pending = Duration::ZERO;
+    for &(x, y) in points.iter().chain(std::iter::once(&(0, 0))) {
+        if (x, y) != current {
+            steps.push(Step::new(x - current.0, y - current.1, delay + pending));
+            pending = Duration::ZERO;
+            current = (x, y);
+        } else if let Some(last) = steps.last_mut() {
+            last.delay += delay;
+        } else {
+            pending += delay;
+        }
+    }
+    steps
+}
+
+/// Samples `count` points of a closed curve, rounding to whole pixels.
+///
+/// * `curve` - Maps a parameter in `0..2π` to an offset from the start
+fn sample_curve(count: usize, curve: impl Fn(f64) -> (f64, f64)) -> Vec<(i32, i32)> {
+    (1..=count)
+        .map(|i| {
+            let t = std::f64::consts::TAU * i as f64 / count as f64;
+            let (x, y) = curve(t);
+            (x.round() as i32, y.round() as i32)
+        })
+        .collect()
+}
@@ -0,0 +1,66 @@
+//! Random-walk pattern: a few random steps near the cursor, then back to the start.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{steps_through, Step, WigglePattern, STEP_DELAY};
+
+/// Number of random steps before returning to the start.
+const WALK_STEPS: usize = 8;
+
+/// Takes random steps of half the wiggle distance, never straying further than the
+/// wiggle distance from the start, then returns.
+pub struct RandomWalkPattern {
+    rng: ChaCha8Rng,
+}
+
+impl RandomWalkPattern {
+    /// Creates a walk seeded from the thread RNG, so each one takes different steps.
+    pub fn new() -> Self {
+        Self {
+            rng: ChaCha8Rng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    /// Creates a walk that always takes the same steps for the same seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for RandomWalkPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WigglePattern for RandomWalkPattern {
+    fn name(&self) -> &'static str {
+        "random-walk"
+    }
+
+    fn steps(&mut self, distance: i32) -> Vec<Step> {
+        let radius = f64::from(distance.abs());
+        let stride = (radius / 2.0).max(1.0);
+        let mut position = (0.0, 0.0);
+        let mut points = Vec::with_capacity(WALK_STEPS);
+
+        for _ in 0..WALK_STEPS {
+            let angle = self.rng.random_range(0.0..std::f64::consts::TAU);
+            let mut x = position.0 + stride * angle.cos();
+            let mut y = position.1 + stride * angle.sin();
+
+            // Pull the point back onto the circle if the step left it
+            let reach = x.hypot(y);
+            if reach > radius {
+                x *= radius / reach;
+                y *= radius / reach;
+            }
+            position = (x, y);
+            points.push((x.trunc() as i32, y.trunc() as i32));
+        }
+        steps_through(&points, STEP_DELAY)
+    }
+}
@@ -0,0 +1,30 @@
+//! Square pattern: one lap around the corners of a square.
+
+use super::{steps_through, Step, WigglePattern, STEP_DELAY};
+
+/// Traces the corners of a square with sides of the wiggle distance.
+pub struct SquarePattern;
+
+impl SquarePattern {
+    /// Creates a square pattern.
+    pub fn new() -> Self {
+        SquarePattern
+    }
+}
+
+impl Default for SquarePattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WigglePattern for SquarePattern {
+    fn name(&self) -> &'static str {
+        "square"
+    }
+
+    fn steps(&mut self, distance: i32) -> Vec<Step> {
+        let corners = [(distance, 0), (distance, distance), (0, distance)];
+        steps_through(&corners, STEP_DELAY * 2)
+    }
+}
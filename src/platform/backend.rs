@@ -0,0 +1,182 @@
+//! # Backend Selection
+//!
+//! Some operating systems offer more than one way to keep the machine awake, and which
+//! ones work depends on the session (system bus, desktop environment, X11 or Wayland).
+//! This module probes them in order of preference at runtime and picks the first that
+//! is available, unless the user forces one with `--backend`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::{NoopPlatform, PlatformTrait};
+use crate::error::{Error, Result};
+
+/// A sleep-prevention backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// The OS API: `SetThreadExecutionState` on Windows, IOKit on macOS
+    Native,
+    /// systemd-logind inhibitor lock (Linux)
+    Logind,
+    /// `org.freedesktop.ScreenSaver` or `org.gnome.SessionManager` on the session bus (Linux)
+    ScreenSaver,
+    /// X server screensaver and DPMS (Linux)
+    X11,
+    /// Wayland idle inhibitor (Linux)
+    Wayland,
+    /// Does nothing; only activity is simulated
+    None,
+}
+
+impl Backend {
+    /// Backends tried on this OS, in order of preference.
+    pub fn chain() -> &'static [Backend] {
+        cfg_if::cfg_if! {
+            if #[cfg(any(target_os = "windows", target_os = "macos"))] {
+                &[Backend::Native, Backend::None]
+            } else {
+                &[
+                    Backend::Logind,
+                    Backend::ScreenSaver,
+                    Backend::X11,
+                    Backend::Wayland,
+                    Backend::None,
+                ]
+            }
+        }
+    }
+
+    /// Name used on the command line and in logs.
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Native => "native",
+            Backend::Logind => "logind",
+            Backend::ScreenSaver => "screensaver",
+            Backend::X11 => "x11",
+            Backend::Wayland => "wayland",
+            Backend::None => "none",
+        }
+    }
+
+    /// Checks if the backend can be used right now and creates it.
+    /// Probing doesn't prevent sleep yet; that happens on the first `acquire`.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with a ready platform if the backend is available.
+    /// - `Err` with the reason if it isn't; `Error::Unsupported` if it never is on this OS.
+    pub fn probe(self) -> Result<Box<dyn PlatformTrait>> {
+        match self {
+            Backend::None => Ok(Box::new(NoopPlatform::new())),
+            _ => probe_os(self),
+        }
+    }
+}
+
+/// The backend `Platform::new()` uses on this OS.
+impl Default for Backend {
+    fn default() -> Self {
+        Backend::chain()[0]
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "native" => Ok(Backend::Native),
+            "logind" => Ok(Backend::Logind),
+            "screensaver" => Ok(Backend::ScreenSaver),
+            "x11" => Ok(Backend::X11),
+            "wayland" => Ok(Backend::Wayland),
+            "none" => Ok(Backend::None),
+            _ => Err(format!(
+                "unknown backend '{}' (expected native, logind, screensaver, x11, wayland or none)",
+                s
+            )),
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(any(target_os = "windows", target_os = "macos"))] {
+        /// Creates the OS-specific backends.
+        fn probe_os(backend: Backend) -> Result<Box<dyn PlatformTrait>> {
+            match backend {
+                Backend::Native => Ok(Box::new(super::Platform::new())),
+                _ => Err(Error::unsupported(format!(
+                    "The {} backend is only available on Linux",
+                    backend
+                ))),
+            }
+        }
+    } else {
+        /// Creates the OS-specific backends.
+        fn probe_os(backend: Backend) -> Result<Box<dyn PlatformTrait>> {
+            use super::linux::{Platform, SessionPlatform, WaylandPlatform, X11Platform};
+
+            match backend {
+                Backend::Logind => {
+                    let platform = Platform::new();
+                    platform.probe()?;
+                    Ok(Box::new(platform))
+                }
+                Backend::ScreenSaver => SessionPlatform::detect()
+                    .map(|platform| Box::new(platform) as Box<dyn PlatformTrait>)
+                    .ok_or_else(|| {
+                        Error::backend(backend, "No screensaver service on the D-Bus session bus")
+                    }),
+                Backend::X11 => {
+                    let platform = X11Platform::new();
+                    platform.probe()?;
+                    Ok(Box::new(platform))
+                }
+                Backend::Wayland => {
+                    let platform = WaylandPlatform::new();
+                    platform.probe()?;
+                    Ok(Box::new(platform))
+                }
+                _ => Err(Error::unsupported(format!(
+                    "The {} backend is not available on Linux",
+                    backend
+                ))),
+            }
+        }
+    }
+}
+
+/// Probes every backend in the chain.
+/// Used by `busycrab backends` to show what works on this machine.
+pub fn probe_all() -> Vec<(Backend, Result<()>)> {
+    Backend::chain()
+        .iter()
+        .map(|backend| (*backend, backend.probe().map(|_| ())))
+        .collect()
+}
+
+/// Picks the backend to use.
+///
+/// * `requested` - Backend forced by the user, or `None` to use the first available one
+///
+/// ### Returns
+///
+/// - `Ok` with the chosen backend and its platform.
+/// - `Err` with the reason if a forced backend isn't available.
+pub fn select(requested: Option<Backend>) -> Result<(Backend, Box<dyn PlatformTrait>)> {
+    if let Some(backend) = requested {
+        return backend.probe().map(|platform| (backend, platform));
+    }
+    for backend in Backend::chain() {
+        if let Ok(platform) = backend.probe() {
+            return Ok((*backend, platform));
+        }
+    }
+    Ok((Backend::None, Box::new(NoopPlatform::new())))
+}
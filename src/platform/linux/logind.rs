@@ -0,0 +1,148 @@
+//! # logind Backend
+//!
+//! This module prevents sleep on Linux by taking a systemd-logind inhibitor lock
+//! through `org.freedesktop.login1.Manager.Inhibit` on the system D-Bus.
+//!
+//! logind hands back a file descriptor and keeps the lock for as long as that
+//! descriptor stays open, so the `Platform` holds on to it and closes it on drop.
+
+use std::os::fd::OwnedFd;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Mutex;
+
+use zbus::blocking::Connection;
+
+use super::super::{Backend, InhibitGuard, InhibitStatus, PlatformTrait};
+use crate::error::{Error, Result, ResultExt};
+
+const LOGIND_SERVICE: &str = "org.freedesktop.login1";
+const LOGIND_PATH: &str = "/org/freedesktop/login1";
+const LOGIND_MANAGER: &str = "org.freedesktop.login1.Manager";
+
+/// Operations we ask logind to block while BusyCrab runs.
+const INHIBIT_WHAT: &str = "idle:sleep";
+const INHIBIT_WHO: &str = "BusyCrab";
+const INHIBIT_WHY: &str = "Keeping the system awake";
+const INHIBIT_MODE: &str = "block";
+
+/// Linux platform implementation backed by systemd-logind.
+pub struct Platform {
+    /// Bus to talk to; `None` means the system bus
+    address: Option<String>,
+    /// Inhibitor lock file descriptor, once acquired
+    lock: Mutex<Option<OwnedFd>>,
+    /// Number of live `InhibitGuard`s, so only the last one releases
+    guards: AtomicUsize,
+}
+
+impl Platform {
+    /// Creates a new Platform instance that uses the system bus.
+    ///
+    /// No connection is made until the inhibit is acquired.
+    pub fn new() -> Self {
+        Self {
+            address: None,
+            lock: Mutex::new(None),
+            guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates a Platform that talks to the bus at `address` instead of the system bus.
+    /// Used for testing against a private `dbus-daemon`.
+    pub fn with_address(address: &str) -> Self {
+        Self {
+            address: Some(address.to_string()),
+            lock: Mutex::new(None),
+            guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Checks that the bus can be reached and logind is running on it.
+    /// Doesn't take a lock.
+    pub fn probe(&self) -> Result<()> {
+        let connection = super::connect(self.address.as_deref(), Connection::system)
+            .backend_context(Backend::Logind, "Failed to connect to the D-Bus system bus")?;
+        if super::has_owner(&connection, LOGIND_SERVICE) {
+            Ok(())
+        } else {
+            Err(Error::backend(
+                Backend::Logind,
+                "systemd-logind is not running on the system bus",
+            ))
+        }
+    }
+
+    /// Asks logind for an inhibitor lock and returns its file descriptor.
+    fn inhibit(&self) -> Result<OwnedFd> {
+        let connection = super::connect(self.address.as_deref(), Connection::system)
+            .backend_context(
+                Backend::Logind,
+                "Failed to connect to D-Bus to reach systemd-logind",
+            )?;
+        let reply = connection
+            .call_method(
+                Some(LOGIND_SERVICE),
+                LOGIND_PATH,
+                Some(LOGIND_MANAGER),
+                "Inhibit",
+                &(INHIBIT_WHAT, INHIBIT_WHO, INHIBIT_WHY, INHIBIT_MODE),
+            )
+            .backend_context(Backend::Logind, "systemd-logind refused the inhibitor lock")?;
+        let fd: zbus::zvariant::OwnedFd = reply.body().deserialize().backend_context(
+            Backend::Logind,
+            "systemd-logind returned an invalid inhibitor lock",
+        )?;
+        Ok(fd.into())
+    }
+}
+
+impl Default for Platform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlatformTrait for Platform {
+    /// Takes an `idle:sleep` inhibitor lock from logind.
+    ///
+    /// The lock is only requested once; later calls are no-ops while it is held.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with a guard if the lock is held.
+    /// - `Err` with an error message if the bus or logind could not be reached.
+    fn acquire(&self) -> Result<InhibitGuard<'_>> {
+        let mut lock = self
+            .lock
+            .lock()
+            .map_err(|_| Error::backend(Backend::Logind, "Inhibitor lock state was poisoned"))?;
+        if lock.is_none() {
+            *lock = Some(self.inhibit()?);
+        }
+        Ok(InhibitGuard::new(self, &self.guards))
+    }
+
+    /// Releases the inhibitor lock by closing its file descriptor.
+    fn release(&self) -> Result<()> {
+        let mut lock = self
+            .lock
+            .lock()
+            .map_err(|_| Error::backend(Backend::Logind, "Inhibitor lock state was poisoned"))?;
+        lock.take();
+        Ok(())
+    }
+
+    /// Reports whether the inhibitor lock is held.
+    fn status(&self) -> InhibitStatus {
+        match self.lock.lock() {
+            Ok(lock) if lock.is_some() => InhibitStatus::Held,
+            _ => InhibitStatus::Released,
+        }
+    }
+}
+
+impl Drop for Platform {
+    fn drop(&mut self) {
+        let _ = self.release();
+    }
+}
@@ -0,0 +1,45 @@
+//! # Linux Platform Implementations
+//!
+//! Linux has no single API for keeping a machine awake, so several backends live here:
+//!
+//! - **logind**: Takes a systemd-logind inhibitor lock on the system bus (the default `Platform`)
+//! - **session**: Inhibits the screensaver through `org.freedesktop.ScreenSaver` or
+//!   `org.gnome.SessionManager` on the session bus
+//! - **x11**: Suspends the X screensaver and DPMS directly through the X server
+//! - **wayland**: Holds a `zwp_idle_inhibitor_v1` from the compositor
+
+use zbus::blocking::{fdo::DBusProxy, Connection};
+use zbus::names::BusName;
+
+mod logind;
+mod session;
+mod wayland;
+mod x11;
+
+pub use logind::Platform;
+pub use session::{detect_session_services, SessionPlatform, SessionService};
+pub use wayland::WaylandPlatform;
+pub use x11::X11Platform;
+
+/// Opens a connection to `address`, or to the bus given by `fallback` when no address is set.
+/// Backends accept an explicit address so they can be tested against a private `dbus-daemon`.
+pub(crate) fn connect(
+    address: Option<&str>,
+    fallback: fn() -> zbus::Result<Connection>,
+) -> zbus::Result<Connection> {
+    match address {
+        Some(address) => zbus::blocking::connection::Builder::address(address)?.build(),
+        None => fallback(),
+    }
+}
+
+/// Checks if some client currently owns the well-known bus `name`.
+fn has_owner(connection: &Connection, name: &str) -> bool {
+    let Ok(dbus) = DBusProxy::new(connection) else {
+        return false;
+    };
+    BusName::try_from(name)
+        .ok()
+        .and_then(|name| dbus.name_has_owner(name).ok())
+        .unwrap_or(false)
+}
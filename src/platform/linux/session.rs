@@ -0,0 +1,247 @@
+//! # Session Bus Backend
+//!
+//! Desktop environments decide when to blank the screen on their own, regardless of
+//! what logind is told. This module asks them directly through the session bus:
+//!
+//! - `org.freedesktop.ScreenSaver.Inhibit` (KDE, XFCE and most other desktops)
+//! - `org.gnome.SessionManager.Inhibit` (GNOME)
+//!
+//! Both hand back a cookie that stays valid for as long as the calling connection is
+//! open, so the connection is kept alive until the cookie is returned on drop.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::Mutex;
+
+use zbus::blocking::Connection;
+
+use super::super::{Backend, InhibitGuard, InhibitStatus, PlatformTrait};
+use crate::error::{Error, Result, ResultExt};
+
+const APP_NAME: &str = "BusyCrab";
+const INHIBIT_REASON: &str = "Keeping the system awake";
+
+/// GNOME inhibit flags: suspending the session (4) and marking it idle (8).
+const GNOME_INHIBIT_FLAGS: u32 = 4 | 8;
+
+/// A session bus service that can inhibit the screensaver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionService {
+    /// `org.gnome.SessionManager`
+    GnomeSessionManager,
+    /// `org.freedesktop.ScreenSaver`
+    FreedesktopScreenSaver,
+}
+
+impl SessionService {
+    /// All services, in order of preference.
+    /// GNOME comes first because it also blocks suspend, not just blanking.
+    pub const ALL: [SessionService; 2] = [
+        SessionService::GnomeSessionManager,
+        SessionService::FreedesktopScreenSaver,
+    ];
+
+    /// Well-known bus name of the service.
+    pub fn bus_name(self) -> &'static str {
+        match self {
+            SessionService::GnomeSessionManager => "org.gnome.SessionManager",
+            SessionService::FreedesktopScreenSaver => "org.freedesktop.ScreenSaver",
+        }
+    }
+
+    /// Object path that implements the inhibit interface.
+    fn path(self) -> &'static str {
+        match self {
+            SessionService::GnomeSessionManager => "/org/gnome/SessionManager",
+            SessionService::FreedesktopScreenSaver => "/org/freedesktop/ScreenSaver",
+        }
+    }
+
+    /// Asks the service for an inhibit cookie.
+    fn inhibit(self, connection: &Connection) -> zbus::Result<u32> {
+        let reply = match self {
+            SessionService::GnomeSessionManager => connection.call_method(
+                Some(self.bus_name()),
+                self.path(),
+                Some(self.bus_name()),
+                "Inhibit",
+                &(APP_NAME, 0u32, INHIBIT_REASON, GNOME_INHIBIT_FLAGS),
+            )?,
+            SessionService::FreedesktopScreenSaver => connection.call_method(
+                Some(self.bus_name()),
+                self.path(),
+                Some(self.bus_name()),
+                "Inhibit",
+                &(APP_NAME, INHIBIT_REASON),
+            )?,
+        };
+        reply.body().deserialize()
+    }
+
+    /// Returns an inhibit cookie to the service.
+    fn uninhibit(self, connection: &Connection, cookie: u32) -> zbus::Result<()> {
+        let method = match self {
+            SessionService::GnomeSessionManager => "Uninhibit",
+            SessionService::FreedesktopScreenSaver => "UnInhibit",
+        };
+        connection.call_method(
+            Some(self.bus_name()),
+            self.path(),
+            Some(self.bus_name()),
+            method,
+            &(cookie,),
+        )?;
+        Ok(())
+    }
+}
+
+/// Lists the inhibit services currently present on the bus, in order of preference.
+pub fn detect_session_services(connection: &Connection) -> Vec<SessionService> {
+    SessionService::ALL
+        .into_iter()
+        .filter(|service| super::has_owner(connection, service.bus_name()))
+        .collect()
+}
+
+/// Connection and cookie for an active inhibit.
+struct Inhibit {
+    connection: Connection,
+    cookie: u32,
+}
+
+/// Linux platform implementation backed by a session bus screensaver service.
+pub struct SessionPlatform {
+    /// Service to talk to
+    service: SessionService,
+    /// Bus to talk to; `None` means the session bus
+    address: Option<String>,
+    /// Active inhibit, once acquired
+    inhibit: Mutex<Option<Inhibit>>,
+    /// Number of live `InhibitGuard`s, so only the last one releases
+    guards: AtomicUsize,
+}
+
+impl SessionPlatform {
+    /// Creates a new instance that uses `service` on the session bus.
+    ///
+    /// No connection is made until the inhibit is acquired.
+    pub fn new(service: SessionService) -> Self {
+        Self {
+            service,
+            address: None,
+            inhibit: Mutex::new(None),
+            guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates an instance that talks to the bus at `address` instead of the session bus.
+    /// Used for testing against a private `dbus-daemon`.
+    pub fn with_address(service: SessionService, address: &str) -> Self {
+        Self {
+            service,
+            address: Some(address.to_string()),
+            inhibit: Mutex::new(None),
+            guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates an instance for the first available service on the session bus.
+    ///
+    /// ### Returns
+    ///
+    /// - `Some` if a supported service is running.
+    /// - `None` if the bus can't be reached or no service is running.
+    pub fn detect() -> Option<Self> {
+        Self::detect_on(None)
+    }
+
+    /// Like `detect`, but looks at the bus at `address`.
+    pub fn detect_at(address: &str) -> Option<Self> {
+        Self::detect_on(Some(address))
+    }
+
+    fn detect_on(address: Option<&str>) -> Option<Self> {
+        let connection = super::connect(address, Connection::session).ok()?;
+        let service = detect_session_services(&connection).into_iter().next()?;
+        Some(Self {
+            service,
+            address: address.map(str::to_string),
+            inhibit: Mutex::new(None),
+            guards: AtomicUsize::new(0),
+        })
+    }
+
+    /// Gets the service this instance talks to.
+    pub fn service(&self) -> SessionService {
+        self.service
+    }
+
+    /// Gets the inhibit cookie, if one is held.
+    pub fn cookie(&self) -> Option<u32> {
+        self.inhibit
+            .lock()
+            .ok()
+            .and_then(|inhibit| inhibit.as_ref().map(|inhibit| inhibit.cookie))
+    }
+}
+
+impl PlatformTrait for SessionPlatform {
+    /// Obtains an inhibit cookie from the service.
+    ///
+    /// The cookie is only requested once; later calls are no-ops while it is held.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with a guard if a cookie is held.
+    /// - `Err` with an error message if the bus or service could not be reached.
+    fn acquire(&self) -> Result<InhibitGuard<'_>> {
+        let mut inhibit = self
+            .inhibit
+            .lock()
+            .map_err(|_| Error::backend(Backend::ScreenSaver, "Inhibit state was poisoned"))?;
+        if inhibit.is_none() {
+            let connection = super::connect(self.address.as_deref(), Connection::session)
+                .backend_context(
+                    Backend::ScreenSaver,
+                    "Failed to connect to the D-Bus session bus",
+                )?;
+            let cookie = self.service.inhibit(&connection).backend_context(
+                Backend::ScreenSaver,
+                "The screensaver service refused to inhibit",
+            )?;
+            *inhibit = Some(Inhibit { connection, cookie });
+        }
+        Ok(InhibitGuard::new(self, &self.guards))
+    }
+
+    /// Returns the cookie so the desktop can blank the screen again.
+    fn release(&self) -> Result<()> {
+        let mut inhibit = self
+            .inhibit
+            .lock()
+            .map_err(|_| Error::backend(Backend::ScreenSaver, "Inhibit state was poisoned"))?;
+        match inhibit.take() {
+            Some(inhibit) => self
+                .service
+                .uninhibit(&inhibit.connection, inhibit.cookie)
+                .backend_context(
+                    Backend::ScreenSaver,
+                    "The screensaver service refused to uninhibit",
+                ),
+            None => Ok(()),
+        }
+    }
+
+    /// Reports whether a cookie is held.
+    fn status(&self) -> InhibitStatus {
+        match self.cookie() {
+            Some(_) => InhibitStatus::Held,
+            None => InhibitStatus::Released,
+        }
+    }
+}
+
+impl Drop for SessionPlatform {
+    fn drop(&mut self) {
+        let _ = self.release();
+    }
+}
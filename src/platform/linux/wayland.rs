@@ -0,0 +1,386 @@
+//! # Wayland Backend
+//!
+//! Synthetic pointer motion is ignored by many Wayland compositors, so this module uses
+//! the `idle-inhibit-unstable-v1` protocol instead: it attaches a `zwp_idle_inhibitor_v1`
+//! to a surface. Compositors only honour inhibitors on surfaces that are mapped, so the
+//! surface is made an `xdg_toplevel` window with a transparent 1x1 buffer. The compositor
+//! won't go idle while the inhibitor exists, so it is held for as long as the platform
+//! lives and destroyed on drop.
+
+use std::os::fd::{AsFd, FromRawFd, OwnedFd};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Mutex;
+
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{
+    wl_buffer::WlBuffer, wl_compositor::WlCompositor, wl_registry, wl_shm, wl_shm::WlShm,
+    wl_shm_pool::WlShmPool, wl_surface::WlSurface,
+};
+use wayland_client::{delegate_noop, Connection, Dispatch, EventQueue, QueueHandle};
+use wayland_protocols::wp::idle_inhibit::zv1::client::{
+    zwp_idle_inhibit_manager_v1::ZwpIdleInhibitManagerV1, zwp_idle_inhibitor_v1::ZwpIdleInhibitorV1,
+};
+use wayland_protocols::xdg::shell::client::{
+    xdg_surface::{self, XdgSurface},
+    xdg_toplevel::XdgToplevel,
+    xdg_wm_base::{self, XdgWmBase},
+};
+
+use super::super::{Backend, InhibitGuard, InhibitStatus, PlatformTrait};
+use crate::error::{Error, Result, ResultExt};
+
+/// Event dispatch state. Only the shell needs answering: pings get a pong and the
+/// window's configure events get acknowledged.
+#[derive(Default)]
+struct WaylandState {
+    /// Whether the window got its first configure event, after which it can be mapped
+    configured: bool,
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgWmBase, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        wm_base: &XdgWmBase,
+        event: xdg_wm_base::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let xdg_wm_base::Event::Ping { serial } = event {
+            wm_base.pong(serial);
+        }
+    }
+}
+
+impl Dispatch<XdgSurface, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        xdg_surface: &XdgSurface,
+        event: xdg_surface::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let xdg_surface::Event::Configure { serial } = event {
+            xdg_surface.ack_configure(serial);
+            state.configured = true;
+        }
+    }
+}
+
+delegate_noop!(WaylandState: WlCompositor);
+delegate_noop!(WaylandState: ignore WlSurface);
+delegate_noop!(WaylandState: ignore WlShm);
+delegate_noop!(WaylandState: WlShmPool);
+delegate_noop!(WaylandState: ignore WlBuffer);
+delegate_noop!(WaylandState: ignore XdgToplevel);
+delegate_noop!(WaylandState: ZwpIdleInhibitManagerV1);
+delegate_noop!(WaylandState: ZwpIdleInhibitorV1);
+
+/// Compositor connection and the objects backing the inhibitor.
+struct WaylandInhibit {
+    connection: Connection,
+    queue: EventQueue<WaylandState>,
+    state: WaylandState,
+    surface: WlSurface,
+    xdg_surface: XdgSurface,
+    toplevel: XdgToplevel,
+    buffer: WlBuffer,
+    inhibitor: ZwpIdleInhibitorV1,
+}
+
+impl WaylandInhibit {
+    /// Binds the idle inhibit manager, maps a 1x1 window and creates an inhibitor on it.
+    fn create(connection: Connection) -> Result<Self> {
+        let (globals, mut queue) = registry_queue_init::<WaylandState>(&connection)
+            .backend_context(Backend::Wayland, "Failed to read the Wayland registry")?;
+        let handle = queue.handle();
+
+        let compositor: WlCompositor = globals.bind(&handle, 1..=4, ()).backend_context(
+            Backend::Wayland,
+            "The Wayland compositor does not offer wl_compositor",
+        )?;
+        let shm: WlShm = globals.bind(&handle, 1..=1, ()).backend_context(
+            Backend::Wayland,
+            "The Wayland compositor does not offer wl_shm",
+        )?;
+        let wm_base: XdgWmBase = globals.bind(&handle, 1..=1, ()).backend_context(
+            Backend::Wayland,
+            "The Wayland compositor does not offer xdg_wm_base",
+        )?;
+        let manager: ZwpIdleInhibitManagerV1 = globals.bind(&handle, 1..=1, ()).backend_context(
+            Backend::Wayland,
+            "The Wayland compositor does not support idle-inhibit-unstable-v1",
+        )?;
+
+        // The first commit has no buffer; the compositor answers with a configure
+        let surface = compositor.create_surface(&handle, ());
+        let xdg_surface = wm_base.get_xdg_surface(&surface, &handle, ());
+        let toplevel = xdg_surface.get_toplevel(&handle, ());
+        toplevel.set_title("BusyCrab".to_string());
+        toplevel.set_app_id("io.github.guinetik.BusyCrab".to_string());
+        surface.commit();
+
+        let mut state = WaylandState::default();
+        queue.roundtrip(&mut state).backend_context(
+            Backend::Wayland,
+            "The Wayland compositor rejected the window",
+        )?;
+        if !state.configured {
+            return Err(Error::backend(
+                Backend::Wayland,
+                "The Wayland compositor did not configure the window",
+            ));
+        }
+
+        // Attaching a buffer after the configure maps the window
+        let buffer = transparent_pixel(&shm, &handle)?;
+        let inhibitor = manager.create_inhibitor(&surface, &handle, ());
+        surface.attach(Some(&buffer), 0, 0);
+        surface.damage(0, 0, 1, 1);
+        surface.commit();
+        queue.roundtrip(&mut state).backend_context(
+            Backend::Wayland,
+            "The Wayland compositor rejected the idle inhibitor",
+        )?;
+
+        Ok(Self {
+            connection,
+            queue,
+            state,
+            surface,
+            xdg_surface,
+            toplevel,
+            buffer,
+            inhibitor,
+        })
+    }
+
+    /// Handles events that arrived, like pings, so the compositor doesn't consider
+    /// the window unresponsive. Doesn't block.
+    fn refresh(&mut self) -> Result<()> {
+        self.connection.flush().backend_context(
+            Backend::Wayland,
+            "Lost connection to the Wayland compositor",
+        )?;
+        if let Some(guard) = self.queue.prepare_read() {
+            match guard.read() {
+                Ok(_) => {}
+                Err(wayland_client::backend::WaylandError::Io(err))
+                    if err.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(err) => {
+                    return Err(Error::backend_source(
+                        Backend::Wayland,
+                        "Lost connection to the Wayland compositor",
+                        err,
+                    ))
+                }
+            }
+        }
+        self.queue
+            .dispatch_pending(&mut self.state)
+            .backend_context(
+                Backend::Wayland,
+                "Lost connection to the Wayland compositor",
+            )?;
+        self.connection.flush().backend_context(
+            Backend::Wayland,
+            "Lost connection to the Wayland compositor",
+        )
+    }
+}
+
+impl Drop for WaylandInhibit {
+    fn drop(&mut self) {
+        self.inhibitor.destroy();
+        self.toplevel.destroy();
+        self.xdg_surface.destroy();
+        self.surface.destroy();
+        self.buffer.destroy();
+        let _ = self.connection.flush();
+    }
+}
+
+/// Creates a 1x1 ARGB buffer with a fully transparent pixel.
+fn transparent_pixel(shm: &WlShm, handle: &QueueHandle<WaylandState>) -> Result<WlBuffer> {
+    // SAFETY: the name is a C string literal; the result is checked before use.
+    let fd = unsafe { libc::memfd_create(c"busycrab-pixel".as_ptr(), libc::MFD_CLOEXEC) };
+    if fd < 0 {
+        return Err(Error::backend_source(
+            Backend::Wayland,
+            "Failed to create a buffer for the window",
+            std::io::Error::last_os_error(),
+        ));
+    }
+    // SAFETY: memfd_create returned a new descriptor nothing else owns.
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+    std::fs::File::from(
+        fd.try_clone()
+            .backend_context(Backend::Wayland, "Failed to create a buffer for the window")?,
+    )
+    .set_len(4)
+    .backend_context(Backend::Wayland, "Failed to create a buffer for the window")?;
+
+    let pool = shm.create_pool(fd.as_fd(), 4, handle, ());
+    let buffer = pool.create_buffer(0, 1, 1, 4, wl_shm::Format::Argb8888, handle, ());
+    pool.destroy();
+    Ok(buffer)
+}
+
+/// Linux platform implementation using the Wayland idle inhibit protocol.
+pub struct WaylandPlatform {
+    /// Compositor socket, usually taken from `$WAYLAND_DISPLAY`
+    display: Option<String>,
+    /// Active inhibitor, once created
+    inhibit: Mutex<Option<WaylandInhibit>>,
+    /// Number of live `InhibitGuard`s, so only the last one releases
+    guards: AtomicUsize,
+}
+
+impl WaylandPlatform {
+    /// Creates a new instance for the compositor in `$WAYLAND_DISPLAY`.
+    ///
+    /// No connection is made until the inhibit is acquired.
+    pub fn new() -> Self {
+        Self {
+            display: std::env::var("WAYLAND_DISPLAY")
+                .ok()
+                .filter(|display| !display.is_empty()),
+            inhibit: Mutex::new(None),
+            guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates an instance for an explicit socket name (relative to `$XDG_RUNTIME_DIR`) or path.
+    pub fn with_display(display: &str) -> Self {
+        Self {
+            display: Some(display.to_string()),
+            inhibit: Mutex::new(None),
+            guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Checks that the compositor can be reached and supports idle inhibition.
+    /// Doesn't create an inhibitor.
+    pub fn probe(&self) -> Result<()> {
+        let connection = Self::connect(self.display()?)?;
+        let (globals, _) = registry_queue_init::<WaylandState>(&connection)
+            .backend_context(Backend::Wayland, "Failed to read the Wayland registry")?;
+        let supported = globals.contents().with_list(|list| {
+            list.iter()
+                .any(|global| global.interface == "zwp_idle_inhibit_manager_v1")
+        });
+        if supported {
+            Ok(())
+        } else {
+            Err(Error::backend(
+                Backend::Wayland,
+                "The Wayland compositor does not support idle-inhibit-unstable-v1",
+            ))
+        }
+    }
+
+    /// Gets the compositor socket to connect to.
+    fn display(&self) -> Result<&str> {
+        self.display.as_deref().ok_or_else(|| {
+            Error::backend(
+                Backend::Wayland,
+                "WAYLAND_DISPLAY is not set; the Wayland backend needs a running compositor",
+            )
+        })
+    }
+
+    /// Opens a connection to the compositor socket.
+    fn connect(display: &str) -> Result<Connection> {
+        let mut path = PathBuf::from(display);
+        if path.is_relative() {
+            let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR").ok_or_else(|| {
+                Error::backend(
+                    Backend::Wayland,
+                    "XDG_RUNTIME_DIR is not set; can't locate the Wayland socket",
+                )
+            })?;
+            path = PathBuf::from(runtime_dir).join(path);
+        }
+        let stream = UnixStream::connect(path).backend_context(
+            Backend::Wayland,
+            "Failed to connect to the Wayland compositor",
+        )?;
+        Connection::from_socket(stream).backend_context(
+            Backend::Wayland,
+            "Failed to connect to the Wayland compositor",
+        )
+    }
+}
+
+impl Default for WaylandPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlatformTrait for WaylandPlatform {
+    /// Creates the idle inhibitor.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with a guard if the inhibitor is held.
+    /// - `Err` with an error message if `WAYLAND_DISPLAY` is unset, the compositor can't be
+    ///   reached, or it doesn't support idle inhibition.
+    fn acquire(&self) -> Result<InhibitGuard<'_>> {
+        let display = self.display()?;
+        let mut inhibit = self
+            .inhibit
+            .lock()
+            .map_err(|_| Error::backend(Backend::Wayland, "Wayland state was poisoned"))?;
+        if inhibit.is_none() {
+            *inhibit = Some(WaylandInhibit::create(Self::connect(display)?)?);
+        }
+        Ok(InhibitGuard::new(self, &self.guards))
+    }
+
+    /// Acquires if needed, then services the compositor connection.
+    fn refresh(&self) -> Result<()> {
+        self.acquire()?.keep();
+        let mut inhibit = self
+            .inhibit
+            .lock()
+            .map_err(|_| Error::backend(Backend::Wayland, "Wayland state was poisoned"))?;
+        match inhibit.as_mut() {
+            Some(inhibit) => inhibit.refresh(),
+            None => Ok(()),
+        }
+    }
+
+    /// Destroys the idle inhibitor and disconnects.
+    fn release(&self) -> Result<()> {
+        let mut inhibit = self
+            .inhibit
+            .lock()
+            .map_err(|_| Error::backend(Backend::Wayland, "Wayland state was poisoned"))?;
+        inhibit.take();
+        Ok(())
+    }
+
+    /// Reports whether the idle inhibitor is held.
+    fn status(&self) -> InhibitStatus {
+        match self.inhibit.lock() {
+            Ok(inhibit) if inhibit.is_some() => InhibitStatus::Held,
+            _ => InhibitStatus::Released,
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! # X11 Backend
+//!
+//! For X11 sessions without a D-Bus screensaver service, this module talks to the
+//! X server directly:
+//!
+//! - `XScreenSaverSuspend` (MIT-SCREEN-SAVER extension) stops the built-in screensaver
+//! - `DPMSDisable` keeps the monitor from powering down
+//! - `XResetScreenSaver` restarts the idle timer on every cycle, for servers without the extensions
+//!
+//! Everything is undone on drop, restoring DPMS only if it was enabled before.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::Mutex;
+
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xproto::{ConnectionExt as _, ScreenSaver};
+use x11rb::protocol::{dpms, screensaver};
+use x11rb::rust_connection::RustConnection;
+
+use super::super::{Backend, InhibitGuard, InhibitStatus, PlatformTrait};
+use crate::error::{Error, Result, ResultExt};
+
+/// Connection and the changes made to the server.
+struct X11Session {
+    connection: RustConnection,
+    /// Whether the screensaver was suspended through the extension
+    suspended: bool,
+    /// Whether DPMS was enabled before we disabled it
+    dpms_was_enabled: bool,
+}
+
+impl X11Session {
+    /// Connects to `display` and suspends the screensaver and DPMS where supported.
+    fn open(display: &str) -> Result<Self> {
+        let (connection, _) = x11rb::connect(Some(display))
+            .backend_context(Backend::X11, "Failed to connect to the X server")?;
+
+        let suspended = has_extension(&connection, screensaver::X11_EXTENSION_NAME)
+            && screensaver::suspend(&connection, 1)
+                .ok()
+                .and_then(|cookie| cookie.check().ok())
+                .is_some();
+
+        let dpms_was_enabled = has_extension(&connection, dpms::X11_EXTENSION_NAME)
+            && dpms::info(&connection)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .is_some_and(|info| info.state)
+            && dpms::disable(&connection)
+                .ok()
+                .and_then(|cookie| cookie.check().ok())
+                .is_some();
+
+        Ok(Self {
+            connection,
+            suspended,
+            dpms_was_enabled,
+        })
+    }
+
+    /// Restarts the server's idle timer, like `XResetScreenSaver`.
+    fn reset(&self) -> Result<()> {
+        self.connection
+            .force_screen_saver(ScreenSaver::RESET)
+            .backend_context(Backend::X11, "Failed to reset the X screensaver")?;
+        self.connection
+            .flush()
+            .backend_context(Backend::X11, "Lost connection to the X server")
+    }
+}
+
+impl Drop for X11Session {
+    fn drop(&mut self) {
+        if self.suspended {
+            let _ = screensaver::suspend(&self.connection, 0);
+        }
+        if self.dpms_was_enabled {
+            let _ = dpms::enable(&self.connection);
+        }
+        let _ = self.connection.flush();
+    }
+}
+
+/// Checks if the X server supports the named extension.
+fn has_extension(connection: &RustConnection, name: &'static str) -> bool {
+    matches!(connection.extension_information(name), Ok(Some(_)))
+}
+
+/// Linux platform implementation that talks to the X server.
+pub struct X11Platform {
+    /// Display to connect to, usually taken from `$DISPLAY`
+    display: Option<String>,
+    /// Open session, once connected
+    session: Mutex<Option<X11Session>>,
+    /// Number of live `InhibitGuard`s, so only the last one releases
+    guards: AtomicUsize,
+}
+
+impl X11Platform {
+    /// Creates a new instance for the display in `$DISPLAY`.
+    ///
+    /// No connection is made until the inhibit is acquired.
+    pub fn new() -> Self {
+        Self {
+            display: std::env::var("DISPLAY")
+                .ok()
+                .filter(|display| !display.is_empty()),
+            session: Mutex::new(None),
+            guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates an instance for an explicit display such as `":99"`.
+    pub fn with_display(display: &str) -> Self {
+        Self {
+            display: Some(display.to_string()),
+            session: Mutex::new(None),
+            guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Checks that `DISPLAY` is set and the X server accepts connections.
+    /// Doesn't change any server settings.
+    pub fn probe(&self) -> Result<()> {
+        let display = self.display()?;
+        x11rb::connect(Some(display))
+            .map(|_| ())
+            .backend_context(Backend::X11, "Failed to connect to the X server")
+    }
+
+    /// Gets the display to connect to.
+    fn display(&self) -> Result<&str> {
+        self.display.as_deref().ok_or_else(|| {
+            Error::backend(
+                Backend::X11,
+                "DISPLAY is not set; the X11 backend needs a running X server",
+            )
+        })
+    }
+
+    /// Checks if the screensaver was suspended through the MIT-SCREEN-SAVER extension.
+    pub fn is_suspended(&self) -> bool {
+        self.session
+            .lock()
+            .ok()
+            .is_some_and(|session| session.as_ref().is_some_and(|session| session.suspended))
+    }
+}
+
+impl Default for X11Platform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlatformTrait for X11Platform {
+    /// Connects to the X server and suspends the screensaver and DPMS.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with a guard if the X server accepted the requests.
+    /// - `Err` with an error message if `DISPLAY` is unset or the server can't be reached.
+    fn acquire(&self) -> Result<InhibitGuard<'_>> {
+        let display = self.display()?;
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| Error::backend(Backend::X11, "X11 session state was poisoned"))?;
+        if session.is_none() {
+            *session = Some(X11Session::open(display)?);
+        }
+        Ok(InhibitGuard::new(self, &self.guards))
+    }
+
+    /// Acquires if needed, then resets the server's idle timer.
+    fn refresh(&self) -> Result<()> {
+        self.acquire()?.keep();
+        let session = self
+            .session
+            .lock()
+            .map_err(|_| Error::backend(Backend::X11, "X11 session state was poisoned"))?;
+        match session.as_ref() {
+            Some(session) => session.reset(),
+            None => Ok(()),
+        }
+    }
+
+    /// Restores the screensaver and DPMS and disconnects.
+    fn release(&self) -> Result<()> {
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| Error::backend(Backend::X11, "X11 session state was poisoned"))?;
+        session.take();
+        Ok(())
+    }
+
+    /// Reports whether the X session is open.
+    fn status(&self) -> InhibitStatus {
+        match self.session.lock() {
+            Ok(session) if session.is_some() => InhibitStatus::Held,
+            _ => InhibitStatus::Released,
+        }
+    }
+}
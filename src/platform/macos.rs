@@ -5,15 +5,32 @@
 //! This module provides a stub implementation for macOS.
 //! The actual implementation would use IOKit framework's power management functions.
 
-use super::PlatformTrait;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use super::{InhibitGuard, InhibitStatus, PlatformTrait};
+use crate::error::Result;
 
 /// macOS-specific platform stub implementation.
-pub struct Platform {}
+pub struct Platform {
+    /// Whether the stub assertion is "held"
+    held: AtomicBool,
+    /// Number of live `InhibitGuard`s, so only the last one releases
+    guards: AtomicUsize,
+}
 
 impl Platform {
     /// Creates a new Platform instance.
     pub fn new() -> Self {
-        Platform {}
+        Platform {
+            held: AtomicBool::new(false),
+            guards: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Default for Platform {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -22,15 +39,35 @@ impl PlatformTrait for Platform {
     /// 
     /// ### Returns
     /// 
-    /// - Always returns `Ok(())`.
-    fn prevent_sleep(&self) -> Result<(), &'static str> {
-        println!("[STUB] macOS prevent_sleep called - not implemented");
+    /// - Always returns `Ok` with a guard.
+    fn acquire(&self) -> Result<InhibitGuard<'_>> {
+        if !self.held.swap(true, Ordering::SeqCst) {
+            println!("[STUB] macOS acquire called - not implemented");
+        }
+        Ok(InhibitGuard::new(self, &self.guards))
+    }
+
+    /// Stub implementation that just logs the call.
+    fn release(&self) -> Result<()> {
+        if self.held.swap(false, Ordering::SeqCst) {
+            println!("[STUB] macOS release called - not implemented");
+        }
         Ok(())
     }
+
+    /// Reports whether the stub assertion is held.
+    fn status(&self) -> InhibitStatus {
+        if self.held.load(Ordering::SeqCst) {
+            InhibitStatus::Held
+        } else {
+            InhibitStatus::Released
+        }
+    }
 }
 
 impl Drop for Platform {
     fn drop(&mut self) {
+        let _ = self.release();
         println!("[STUB] macOS platform dropped");
     }
-}
\ No newline at end of file
+}
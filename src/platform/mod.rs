@@ -7,26 +7,129 @@
 //! 
 //! - **Windows**: Uses `SetThreadExecutionState` from the Windows API
 //! - **macOS**: Uses `IOPMAssertionCreateWithName` from the IOKit framework
-//! - **Linux**: Currently not implemented (returns an error)
+//! - **Linux**: Takes a systemd-logind inhibitor lock over D-Bus, with session bus,
+//!   X11 and Wayland backends available in `platform::linux`
 //! 
 //! The module exposes a consistent `Platform` type with the same interface
 //! regardless of the underlying platform, making the rest of the application
-//! platform-agnostic.
+//! platform-agnostic. Where an OS has several backends, `select` probes
+//! them at runtime and falls back to `NoopPlatform` when none is available.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::Result;
+
+/// Whether a platform is currently keeping the system awake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InhibitStatus {
+    /// Sleep is being prevented
+    Held,
+    /// Nothing is held; the system may sleep
+    Released,
+}
 
 /// Trait defining platform-specific operations.
 /// 
 /// This trait abstracts platform-specific functionality, allowing for
 /// different implementations including real platform APIs and test mocks.
+///
+/// Sleep prevention has a lifecycle: `acquire` takes the inhibit, `refresh` keeps it
+/// alive on every activity cycle, and `release` gives it back. Implementations must
+/// make all three idempotent.
 pub trait PlatformTrait {
+    /// Starts preventing the system from sleeping or turning off the display.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with a guard that releases the inhibit when dropped.
+    /// - `Err` if the backend failed.
+    fn acquire(&self) -> Result<InhibitGuard<'_>>;
+
+    /// Keeps the inhibit alive, acquiring it first if needed.
+    /// Called on every activity cycle.
+    fn refresh(&self) -> Result<()> {
+        self.acquire().map(InhibitGuard::keep)
+    }
+
+    /// Stops preventing sleep. Does nothing if nothing is held.
+    fn release(&self) -> Result<()>;
+
+    /// Reports whether the inhibit is currently held.
+    fn status(&self) -> InhibitStatus;
+
     /// Prevents the system from sleeping or turning off the display.
+    /// Same as `refresh`.
     /// 
     /// ### Returns
     /// 
     /// - `Ok(())` if the operation was successful.
-    /// - `Err` with an error message if the operation failed.
-    fn prevent_sleep(&self) -> Result<(), &'static str>;
+    /// - `Err` if the backend failed.
+    fn prevent_sleep(&self) -> Result<()> {
+        self.refresh()
+    }
 }
 
+/// Releases an acquired inhibit when dropped, unless other guards for the same
+/// platform are still alive.
+#[must_use = "the inhibit is released as soon as the guard is dropped"]
+pub struct InhibitGuard<'a> {
+    /// Platform to release, until the guard is consumed
+    platform: Option<&'a dyn PlatformTrait>,
+    /// The platform's count of live guards. `acquire` is idempotent, so several
+    /// guards can share one inhibit and only the last one out releases it
+    live: &'a AtomicUsize,
+}
+
+impl<'a> InhibitGuard<'a> {
+    /// Creates a guard for an inhibit that `platform` has just acquired. `live` is
+    /// the platform's own count of live guards.
+    pub fn new(platform: &'a dyn PlatformTrait, live: &'a AtomicUsize) -> Self {
+        live.fetch_add(1, Ordering::SeqCst);
+        Self {
+            platform: Some(platform),
+            live,
+        }
+    }
+
+    /// Releases the inhibit now, reporting any error. Does nothing while other
+    /// guards for the platform are alive.
+    pub fn release(mut self) -> Result<()> {
+        match self.platform.take() {
+            Some(platform) if self.forget() => platform.release(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Drops the guard without releasing; the caller becomes responsible for
+    /// calling `PlatformTrait::release`.
+    pub fn keep(mut self) {
+        if self.platform.take().is_some() {
+            self.forget();
+        }
+    }
+
+    /// Takes this guard off the live count, returning whether it was the last one.
+    fn forget(&self) -> bool {
+        self.live.fetch_sub(1, Ordering::SeqCst) == 1
+    }
+}
+
+impl Drop for InhibitGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(platform) = self.platform.take() {
+            if self.forget() {
+                let _ = platform.release();
+            }
+        }
+    }
+}
+
+mod backend;
+mod noop;
+
+pub use backend::{probe_all, select, Backend};
+pub use noop::NoopPlatform;
+
 // Use cfg_if to select the appropriate implementation
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
@@ -36,7 +139,7 @@ cfg_if::cfg_if! {
         mod macos;
         pub use macos::Platform;
     } else {
-        mod linux;
+        pub mod linux;
         pub use linux::Platform;
     }
 } 
\ No newline at end of file
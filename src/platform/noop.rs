@@ -0,0 +1,47 @@
+//! # No-op Platform Implementation
+//!
+//! Used when no sleep-prevention backend is available. BusyCrab keeps simulating
+//! activity, but nothing stops the system from sleeping on its own schedule.
+
+use std::sync::atomic::AtomicUsize;
+
+use super::{InhibitGuard, InhibitStatus, PlatformTrait};
+use crate::error::Result;
+
+/// Platform implementation that does nothing.
+pub struct NoopPlatform {
+    /// Number of live `InhibitGuard`s, so only the last one releases
+    guards: AtomicUsize,
+}
+
+impl NoopPlatform {
+    /// Creates a new instance.
+    pub fn new() -> Self {
+        NoopPlatform {
+            guards: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Default for NoopPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlatformTrait for NoopPlatform {
+    /// Always succeeds without doing anything.
+    fn acquire(&self) -> Result<InhibitGuard<'_>> {
+        Ok(InhibitGuard::new(self, &self.guards))
+    }
+
+    /// Always succeeds without doing anything.
+    fn release(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Always `Released`, since nothing is ever held.
+    fn status(&self) -> InhibitStatus {
+        InhibitStatus::Released
+    }
+}
@@ -3,23 +3,39 @@
 //! This module provides a Windows-specific implementation for preventing system sleep
 //! using the Windows API `SetThreadExecutionState` function.
 
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
 use winapi::um::winbase::SetThreadExecutionState;
 use winapi::um::winnt::{ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED};
 
-use super::PlatformTrait;
+use super::{Backend, InhibitGuard, InhibitStatus, PlatformTrait};
+use crate::error::{Error, Result};
 
 /// Windows-specific platform implementation.
 /// 
 /// Uses the `SetThreadExecutionState` Windows API function to prevent the system
 /// from entering sleep mode or turning off the display.
-pub struct Platform;
+pub struct Platform {
+    /// Whether the execution state is currently set
+    held: AtomicBool,
+    /// Number of live `InhibitGuard`s, so only the last one releases
+    guards: AtomicUsize,
+}
 
 impl Platform {
     /// Creates a new Platform instance.
-    /// 
-    /// This is a zero-sized struct for the Windows platform as no state needs to be maintained.
     pub fn new() -> Self {
-        Platform
+        Platform {
+            held: AtomicBool::new(false),
+            guards: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Default for Platform {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -33,21 +49,58 @@ impl PlatformTrait for Platform {
     /// 
     /// ### Returns
     /// 
-    /// - `Ok(())` if the call was successful.
-    /// - `Err` with an error message if the call failed.
+    /// - `Ok` with a guard if the call was successful.
+    /// - `Err` with the OS error if the call failed.
     /// 
     /// ### Safety
     /// 
     /// This function makes an unsafe call to the Windows API. The `unsafe` block is contained
     /// within this function and doesn't leak to the rest of the application.
-    fn prevent_sleep(&self) -> Result<(), &'static str> {
+    fn acquire(&self) -> Result<InhibitGuard<'_>> {
         let result = unsafe {
             SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED)
         };
         if result == 0 {
-            Err("Failed to set execution state")
+            Err(Error::backend_source(
+                Backend::Native,
+                "Failed to set execution state",
+                io::Error::last_os_error(),
+            ))
+        } else {
+            self.held.store(true, Ordering::SeqCst);
+            Ok(InhibitGuard::new(self, &self.guards))
+        }
+    }
+
+    /// Lets the system sleep again by resetting the execution state to just `ES_CONTINUOUS`.
+    fn release(&self) -> Result<()> {
+        if !self.held.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let result = unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+        if result == 0 {
+            Err(Error::backend_source(
+                Backend::Native,
+                "Failed to reset execution state",
+                io::Error::last_os_error(),
+            ))
         } else {
             Ok(())
         }
     }
-} 
\ No newline at end of file
+
+    /// Reports whether the execution state is set.
+    fn status(&self) -> InhibitStatus {
+        if self.held.load(Ordering::SeqCst) {
+            InhibitStatus::Held
+        } else {
+            InhibitStatus::Released
+        }
+    }
+}
+
+impl Drop for Platform {
+    fn drop(&mut self) {
+        let _ = self.release();
+    }
+}
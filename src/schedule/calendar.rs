@@ -0,0 +1,536 @@
+//! Calendar-aware scheduling from iCalendar (`.ics`) exports.
+//!
+//! BusyCrab reads the `VEVENT`s of a calendar, expands their `RRULE` recurrences and
+//! only runs while one of the events whose summary matches a filter is going on,
+//! like a block called "Focus time".
+//!
+//! Times written with a `Z` are UTC; all other times, including those with a `TZID`,
+//! are taken as local time, which is right for exports made in the local time zone.
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::recurrence::{Recurrence, RuleError};
+use crate::error::{Error, Result};
+
+/// A date-time from an iCalendar file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IcsTime {
+    /// The date and time as written
+    pub value: NaiveDateTime,
+    /// Whether `value` is UTC rather than local time
+    pub utc: bool,
+    /// Whether only a date was written, like for all-day events
+    pub date_only: bool,
+}
+
+impl IcsTime {
+    /// Parses `YYYYMMDD`, `YYYYMMDDTHHMMSS` or `YYYYMMDDTHHMMSSZ`.
+    /// A bare date means the start of that day.
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        let s = s.trim();
+        let invalid = || format!("invalid date-time '{}'", s);
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y%m%d") {
+            return Ok(Self {
+                value: date.and_time(NaiveTime::MIN),
+                utc: false,
+                date_only: true,
+            });
+        }
+        let (text, utc) = match s.strip_suffix(['Z', 'z']) {
+            Some(text) => (text, true),
+            None => (s, false),
+        };
+        let value = NaiveDateTime::parse_from_str(text, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        Ok(Self {
+            value,
+            utc,
+            date_only: false,
+        })
+    }
+
+    /// Converts to UTC (`utc` set) or local time.
+    pub fn to_base(self, utc: bool) -> Self {
+        let value = match (self.utc, utc) {
+            (false, true) => Local
+                .from_local_datetime(&self.value)
+                .earliest()
+                .map(|time| time.naive_utc())
+                .unwrap_or(self.value),
+            (true, false) => Utc
+                .from_utc_datetime(&self.value)
+                .with_timezone(&Local)
+                .naive_local(),
+            _ => self.value,
+        };
+        Self { value, utc, ..self }
+    }
+}
+
+/// One event from a calendar, possibly recurring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// The event's title
+    pub summary: String,
+    /// Start of the first occurrence
+    start: IcsTime,
+    /// Length of each occurrence
+    duration: Duration,
+    /// How the event repeats, if it does
+    rule: Option<Recurrence>,
+    /// Starts of occurrences that were removed or moved, in the same base as `start`
+    exdates: Vec<NaiveDateTime>,
+}
+
+impl Event {
+    /// Checks if the event repeats.
+    pub fn is_recurring(&self) -> bool {
+        self.rule.is_some()
+    }
+
+    /// Checks if an occurrence of the event covers the local time `at`.
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        let at = IcsTime {
+            value: at,
+            utc: false,
+            date_only: false,
+        }
+        .to_base(self.start.utc)
+        .value;
+
+        let starts: Box<dyn Iterator<Item = NaiveDateTime>> = match &self.rule {
+            Some(rule) => Box::new(rule.occurrences(self.start.value)),
+            None => Box::new(std::iter::once(self.start.value)),
+        };
+        starts
+            .take_while(|start| *start <= at)
+            .any(|start| at < start + self.duration && !self.exdates.contains(&start))
+    }
+}
+
+/// Events loaded from one or more `.ics` files, with the filter that picks the ones
+/// BusyCrab runs during.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Calendar {
+    events: Vec<Event>,
+    /// Text the summary has to contain, ignoring case; `None` matches every event
+    filter: Option<String>,
+    /// Why events whose recurrence isn't supported were left out
+    skipped: Vec<String>,
+}
+
+impl Calendar {
+    /// Loads a `.ics` file, or every `.ics` file in a directory.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the events of all files. Events with a recurrence rule that isn't
+    ///   supported are left out and listed in `skipped`.
+    /// - `Err(Error::Config)` naming the file and line if something can't be read
+    ///   or parsed, or if a directory holds no `.ics` files.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.is_dir() {
+            return Self::load_file(path);
+        }
+
+        let entries = std::fs::read_dir(path).map_err(|err| {
+            Error::config(format!(
+                "can't read calendar directory {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        let mut files: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| {
+                file.extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("ics"))
+            })
+            .collect();
+        if files.is_empty() {
+            return Err(Error::config(format!(
+                "no .ics files in calendar directory {}",
+                path.display()
+            )));
+        }
+        files.sort();
+
+        let mut calendar = Self::default();
+        for file in files {
+            let loaded = Self::load_file(&file)?;
+            calendar.events.extend(loaded.events);
+            calendar.skipped.extend(loaded.skipped);
+        }
+        Ok(calendar)
+    }
+
+    /// Loads a single `.ics` file.
+    fn load_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            Error::config(format!("can't read calendar {}: {}", path.display(), err))
+        })?;
+        let mut calendar: Self = text
+            .parse()
+            .map_err(|err| Error::config(format!("{}: {}", path.display(), err)))?;
+        for reason in &mut calendar.skipped {
+            *reason = format!("{}: {}", path.display(), reason);
+        }
+        Ok(calendar)
+    }
+
+    /// Only runs during events whose summary contains `filter`, ignoring case.
+    pub fn with_filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /// Gets the summary filter, if any.
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    /// Lists the events that were left out because their recurrence rule isn't
+    /// supported, with the line they start on and why.
+    pub fn skipped(&self) -> &[String] {
+        &self.skipped
+    }
+
+    /// Gets all events, matching the filter or not.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Lists the events that match the filter.
+    pub fn matching_events(&self) -> impl Iterator<Item = &Event> {
+        let filter = self.filter.as_ref().map(|filter| filter.to_lowercase());
+        self.events.iter().filter(move |event| {
+            filter
+                .as_ref()
+                .is_none_or(|filter| event.summary.to_lowercase().contains(filter))
+        })
+    }
+
+    /// Gets the matching event going on at the local time `at`, if any.
+    pub fn active_event(&self, at: NaiveDateTime) -> Option<&Event> {
+        self.matching_events().find(|event| event.contains(at))
+    }
+
+    /// Checks if a matching event is going on at the local time `at`.
+    pub fn is_active(&self, at: NaiveDateTime) -> bool {
+        self.active_event(at).is_some()
+    }
+}
+
+impl fmt::Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match &self.filter {
+            Some(filter) => format!(
+                "{} of {} events matching \"{}\"",
+                self.matching_events().count(),
+                self.events.len(),
+                filter
+            ),
+            None => format!("{} events", self.events.len()),
+        };
+        f.pad(&text)
+    }
+}
+
+impl FromStr for Calendar {
+    type Err = String;
+
+    /// Parses the text of an `.ics` file.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut events = Vec::new();
+        let mut overrides = Vec::new();
+        let mut skipped = Vec::new();
+        let mut current: Option<EventBuilder> = None;
+        // Line the current event starts on
+        let mut event_line = 0;
+        // Components nested in the current event, like VALARM, whose properties are skipped
+        let mut nested = 0;
+
+        for (number, line) in unfold(s) {
+            let at_line = |err: String| format!("line {}: {}", number, err);
+            let Some((name, params, value)) = split_property(&line) else {
+                return Err(at_line(format!("invalid line '{}'", line)));
+            };
+
+            match (name.as_str(), current.as_mut()) {
+                ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                    current = Some(EventBuilder::default());
+                    event_line = number;
+                }
+                ("BEGIN", Some(_)) => nested += 1,
+                ("END", Some(_)) if nested > 0 => nested -= 1,
+                ("END", Some(_)) => {
+                    let built = current.take().unwrap().build().map_err(at_line)?;
+                    if let Some(reason) = built.skipped {
+                        skipped.push(format!("line {}: skipped {}", event_line, reason));
+                        continue;
+                    }
+                    if let Some(replaced) = built.replaces {
+                        overrides.push(replaced);
+                    }
+                    if let Some(event) = built.event {
+                        events.push((built.uid, event));
+                    }
+                }
+                (_, Some(builder)) if nested == 0 => {
+                    builder.set(&name, &params, &value).map_err(at_line)?
+                }
+                _ => {}
+            }
+        }
+        if current.is_some() {
+            return Err("VEVENT without END".to_string());
+        }
+
+        // Moved or cancelled occurrences replace the ones their rule would make
+        for (uid, start) in overrides {
+            for (event_uid, event) in &mut events {
+                if event_uid.as_deref() == Some(uid.as_str()) {
+                    event.exdates.push(start.to_base(event.start.utc).value);
+                }
+            }
+        }
+
+        Ok(Calendar {
+            events: events.into_iter().map(|(_, event)| event).collect(),
+            filter: None,
+            skipped,
+        })
+    }
+}
+
+/// Parameters of a property, like `VALUE=DATE`, with upper-cased names.
+type Params = Vec<(String, String)>;
+
+/// Properties of a `VEVENT` collected while parsing.
+#[derive(Default)]
+struct EventBuilder {
+    uid: Option<String>,
+    summary: String,
+    start: Option<IcsTime>,
+    end: Option<IcsTime>,
+    duration: Option<Duration>,
+    rule: Option<Recurrence>,
+    exdates: Vec<IcsTime>,
+    recurrence_id: Option<IcsTime>,
+    cancelled: bool,
+    /// Why the recurrence rule can't be expanded, if it can't
+    unsupported_rule: Option<String>,
+}
+
+/// A parsed `VEVENT`.
+struct Built {
+    /// UID that changed occurrences refer to; `None` for changed occurrences themselves
+    uid: Option<String>,
+    /// The event, unless it was cancelled
+    event: Option<Event>,
+    /// For a changed occurrence of a recurring event, the event's UID and the
+    /// original start of the occurrence
+    replaces: Option<(String, IcsTime)>,
+    /// Why the event is left out, when its recurrence rule isn't supported
+    skipped: Option<String>,
+}
+
+impl EventBuilder {
+    /// Records one property.
+    fn set(
+        &mut self,
+        name: &str,
+        params: &[(String, String)],
+        value: &str,
+    ) -> std::result::Result<(), String> {
+        let date_only = params
+            .iter()
+            .any(|(param, value)| param == "VALUE" && value.eq_ignore_ascii_case("DATE"));
+        let time = |value: &str| {
+            let time = IcsTime::parse(value)?;
+            match date_only && !time.date_only {
+                true => Err(format!("expected a date, got '{}'", value)),
+                false => Ok(time),
+            }
+        };
+
+        match name {
+            "UID" => self.uid = Some(value.to_string()),
+            "SUMMARY" => self.summary = unescape(value),
+            "DTSTART" => self.start = Some(time(value)?),
+            "DTEND" => self.end = Some(time(value)?),
+            "DURATION" => self.duration = Some(parse_duration(value)?),
+            "RRULE" => match value.parse() {
+                Ok(rule) => self.rule = Some(rule),
+                Err(RuleError::Unsupported(reason)) => self.unsupported_rule = Some(reason),
+                Err(RuleError::Invalid(reason)) => return Err(reason),
+            },
+            "EXDATE" => {
+                for value in value.split(',') {
+                    self.exdates.push(time(value)?);
+                }
+            }
+            "RECURRENCE-ID" => self.recurrence_id = Some(time(value)?),
+            "STATUS" => self.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Turns the properties into an event.
+    fn build(self) -> std::result::Result<Built, String> {
+        let start = self.start.ok_or("VEVENT without DTSTART")?;
+        if let Some(reason) = self.unsupported_rule {
+            return Ok(Built {
+                uid: None,
+                event: None,
+                replaces: None,
+                skipped: Some(format!("\"{}\": {}", self.summary, reason)),
+            });
+        }
+        let duration = match (self.end, self.duration) {
+            (Some(end), _) => end.to_base(start.utc).value - start.value,
+            (None, Some(duration)) => duration,
+            (None, None) if start.date_only => Duration::days(1),
+            (None, None) => Duration::zero(),
+        };
+        if duration < Duration::zero() {
+            return Err("VEVENT ends before it starts".to_string());
+        }
+
+        let replaces = match (self.uid.clone(), self.recurrence_id) {
+            (Some(uid), Some(original)) => Some((uid, original)),
+            _ => None,
+        };
+        let uid = if replaces.is_some() { None } else { self.uid };
+
+        let mut rule = self.rule;
+        if let Some(until) = rule.as_mut().and_then(|rule| rule.until.as_mut()) {
+            if until.date_only {
+                until.value = until.value.date().and_hms_opt(23, 59, 59).unwrap();
+            }
+            *until = until.to_base(start.utc);
+        }
+        let exdates = self
+            .exdates
+            .into_iter()
+            .map(|time| time.to_base(start.utc).value)
+            .collect();
+
+        let event = Event {
+            summary: self.summary,
+            start,
+            duration,
+            rule,
+            exdates,
+        };
+        Ok(Built {
+            uid,
+            event: (!self.cancelled).then_some(event),
+            replaces,
+            skipped: None,
+        })
+    }
+}
+
+/// Joins folded lines (continued with a leading space or tab) and drops empty ones,
+/// keeping the number of each line's first physical line.
+fn unfold(text: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, previous))) => previous.push_str(rest),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push((index + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+/// Splits `NAME;PARAM=VALUE:value` into the upper-cased name, the parameters and
+/// the value. Colons inside quoted parameter values don't end the name.
+fn split_property(line: &str) -> Option<(String, Params, String)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(index, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(index),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(param, value)| {
+            (
+                param.to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    Some((name, params, value.to_string()))
+}
+
+/// Undoes the escaping of text values.
+fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// Parses a `DURATION` value like `PT1H30M`, `P1D` or `P2W`.
+fn parse_duration(value: &str) -> std::result::Result<Duration, String> {
+    let invalid = || format!("invalid duration '{}'", value);
+    let rest = value
+        .trim()
+        .trim_start_matches('+')
+        .strip_prefix(['P', 'p'])
+        .ok_or_else(invalid)?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c.to_ascii_uppercase() {
+            digit @ '0'..='9' => number.push(digit),
+            'T' => in_time = true,
+            unit => {
+                let amount: i64 = number.parse().map_err(|_| invalid())?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(amount),
+                    ('D', false) => Duration::days(amount),
+                    ('H', true) => Duration::hours(amount),
+                    ('M', true) => Duration::minutes(amount),
+                    ('S', true) => Duration::seconds(amount),
+                    _ => return Err(invalid()),
+                };
+            }
+        }
+    }
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
@@ -0,0 +1,275 @@
+//! Holidays and days out of office, when BusyCrab shouldn't run at all.
+//!
+//! A holidays file lists one entry per line, optionally followed by a name:
+//!
+//! ```text
+//! # Public holidays
+//! 12-25                    Christmas
+//! 12-31..01-01             New Year
+//! # Time off
+//! 2024-08-05..2024-08-16   Summer vacation
+//! 2024-10-04
+//! ```
+//!
+//! `YYYY-MM-DD` is a single date and `MM-DD` the same day every year. Either kind
+//! can be a range with `..`, which includes both ends; a yearly range ending before
+//! it starts runs over New Year.
+
+use chrono::{Datelike, NaiveDate};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// A day of the year, written `MM-DD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MonthDay {
+    /// Month, 1 to 12
+    pub month: u32,
+    /// Day of the month, 1 to 31
+    pub day: u32,
+}
+
+impl MonthDay {
+    /// Gets the day of the year of `date`.
+    pub fn of(date: NaiveDate) -> Self {
+        Self {
+            month: date.month(),
+            day: date.day(),
+        }
+    }
+}
+
+impl fmt::Display for MonthDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{:02}-{:02}", self.month, self.day))
+    }
+}
+
+impl FromStr for MonthDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || format!("invalid date '{}' (expected YYYY-MM-DD or MM-DD)", s);
+        let (month, day) = s.split_once('-').ok_or_else(invalid)?;
+        let (month, day) = (
+            month.parse().map_err(|_| invalid())?,
+            day.parse().map_err(|_| invalid())?,
+        );
+        // 2024 is a leap year, so Feb 29 is allowed
+        NaiveDate::from_ymd_opt(2024, month, day).ok_or_else(invalid)?;
+        Ok(Self { month, day })
+    }
+}
+
+/// Dates a holiday entry covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HolidayDates {
+    /// From the first date to the second, inclusive
+    Dates(NaiveDate, NaiveDate),
+    /// From the first day to the second every year, inclusive
+    Yearly(MonthDay, MonthDay),
+}
+
+impl HolidayDates {
+    /// Checks if `date` falls on the holiday.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        match *self {
+            HolidayDates::Dates(first, last) => (first..=last).contains(&date),
+            HolidayDates::Yearly(first, last) => {
+                let day = MonthDay::of(date);
+                if first <= last {
+                    (first..=last).contains(&day)
+                } else {
+                    day >= first || day <= last
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for HolidayDates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            HolidayDates::Dates(first, last) if first == last => first.to_string(),
+            HolidayDates::Dates(first, last) => format!("{}..{}", first, last),
+            HolidayDates::Yearly(first, last) if first == last => first.to_string(),
+            HolidayDates::Yearly(first, last) => format!("{}..{}", first, last),
+        };
+        f.pad(&text)
+    }
+}
+
+impl FromStr for HolidayDates {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (first, last) = s.split_once("..").unwrap_or((s, s));
+        let last = last.trim_start_matches('=');
+
+        let date = |text: &str| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok();
+        if let (Some(first), Some(last)) = (date(first), date(last)) {
+            return match first <= last {
+                true => Ok(HolidayDates::Dates(first, last)),
+                false => Err(format!("range '{}' ends before it starts", s)),
+            };
+        }
+        if date(first).is_some() || date(last).is_some() {
+            return Err(format!("range '{}' mixes dates with and without a year", s));
+        }
+        Ok(HolidayDates::Yearly(first.parse()?, last.parse()?))
+    }
+}
+
+/// One entry of a holidays file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holiday {
+    /// Dates the entry covers
+    pub dates: HolidayDates,
+    /// What the day off is, if the file says
+    pub name: Option<String>,
+}
+
+impl fmt::Display for Holiday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => f.pad(&format!("{} {}", self.dates, name)),
+            None => f.pad(&self.dates.to_string()),
+        }
+    }
+}
+
+impl FromStr for Holiday {
+    type Err = String;
+
+    /// Parses `DATES [NAME]`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let (dates, name) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        let name = name.trim();
+        Ok(Self {
+            dates: dates.parse()?,
+            name: (!name.is_empty()).then(|| name.to_string()),
+        })
+    }
+}
+
+/// Days BusyCrab takes off.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Holidays {
+    entries: Vec<Holiday>,
+}
+
+impl Holidays {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a holidays file with one entry per line. Empty lines and everything
+    /// after `#` are ignored.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the holidays.
+    /// - `Err(Error::Config)` naming the line if the file can't be read or parsed.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            Error::config(format!(
+                "can't read holidays file {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        text.parse()
+            .map_err(|err| Error::config(format!("{} {}", path.display(), err)))
+    }
+
+    /// Adds an entry.
+    pub fn add(&mut self, holiday: Holiday) {
+        self.entries.push(holiday);
+    }
+
+    /// Gets all entries.
+    pub fn entries(&self) -> &[Holiday] {
+        &self.entries
+    }
+
+    /// Gets the first entry covering `date`, if any.
+    pub fn on(&self, date: NaiveDate) -> Option<&Holiday> {
+        self.entries
+            .iter()
+            .find(|holiday| holiday.dates.contains(date))
+    }
+
+    /// Checks if `date` is a holiday.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.on(date).is_some()
+    }
+}
+
+impl fmt::Display for Holidays {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.entries.len() {
+            1 => f.pad("1 entry"),
+            count => f.pad(&format!("{} entries", count)),
+        }
+    }
+}
+
+impl FromStr for Holidays {
+    type Err = String;
+
+    /// Parses the text of a holidays file. Errors start with the line they're on,
+    /// like `line 3: ...`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut holidays = Self::new();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let holiday = line
+                .parse()
+                .map_err(|err| format!("line {}: {}", number + 1, err))?;
+            holidays.add(holiday);
+        }
+        Ok(holidays)
+    }
+}
+
+/// What BusyCrab does on a holiday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HolidayAction {
+    /// Release sleep prevention and wait for the next working day
+    #[default]
+    Pause,
+    /// Shut down
+    Exit,
+}
+
+impl fmt::Display for HolidayAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            HolidayAction::Pause => "pause",
+            HolidayAction::Exit => "exit",
+        })
+    }
+}
+
+impl FromStr for HolidayAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pause" => Ok(HolidayAction::Pause),
+            "exit" => Ok(HolidayAction::Exit),
+            _ => Err(format!(
+                "unknown holiday action '{}' (expected pause or exit)",
+                s
+            )),
+        }
+    }
+}
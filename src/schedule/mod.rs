@@ -0,0 +1,180 @@
+//! # Schedule
+//!
+//! Limits BusyCrab to working hours. A schedule is a set of weekly time windows, like
+//! `Mon-Fri 09:00-18:00`, minus breaks, like a lunch hour. Outside the schedule
+//! BusyCrab releases sleep prevention and stops simulating activity, and it picks up
+//! again on its own once the next window starts.
+//!
+//! Schedules come from `--schedule`, with entries separated by `;`, or from a file
+//! with one entry per line:
+//!
+//! ```text
+//! # Office hours
+//! Mon-Fri 09:00-18:00
+//! Sat 10:00-12:00
+//! break Mon-Fri 12:00-13:00
+//! ```
+//!
+//! A calendar (`.ics` export) can limit BusyCrab further, to events like focus
+//! blocks; see `calendar`. Holidays and time off listed in a holidays file stop it
+//! for whole days; see `holiday`.
+//!
+//! Times are local. The time of day is read through a `Clock` so tests can set it.
+
+use chrono::{Local, NaiveDateTime};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+pub mod calendar;
+pub mod holiday;
+pub mod recurrence;
+pub mod window;
+
+pub use calendar::{Calendar, Event};
+pub use holiday::{Holiday, HolidayAction, Holidays};
+pub use recurrence::{Recurrence, RuleError};
+pub use window::{Days, TimeWindow};
+
+/// Tells the local date and time.
+pub trait Clock {
+    /// Gets the current local date and time.
+    fn now(&self) -> NaiveDateTime;
+}
+
+/// Clock that reads the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+}
+
+/// When BusyCrab should be active.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Schedule {
+    /// Windows BusyCrab runs in; none means any time outside a break
+    windows: Vec<TimeWindow>,
+    /// Windows BusyCrab pauses in, even inside a window above
+    breaks: Vec<TimeWindow>,
+}
+
+impl Schedule {
+    /// Creates an empty schedule, which is always active.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a schedule file with one entry per line. Empty lines and everything
+    /// after `#` are ignored.
+    ///
+    /// ### Returns
+    ///
+    /// - `Ok` with the schedule.
+    /// - `Err(Error::Config)` naming the line if the file can't be read or parsed.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            Error::config(format!(
+                "can't read schedule file {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+
+        let mut schedule = Self::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            schedule.add_entry(line).map_err(|err| {
+                Error::config(format!("{} line {}: {}", path.display(), number + 1, err))
+            })?;
+        }
+        Ok(schedule)
+    }
+
+    /// Adds a window BusyCrab runs in.
+    pub fn add_window(&mut self, window: TimeWindow) {
+        self.windows.push(window);
+    }
+
+    /// Adds a break BusyCrab pauses in.
+    pub fn add_break(&mut self, window: TimeWindow) {
+        self.breaks.push(window);
+    }
+
+    /// Adds the windows and breaks of `other`.
+    pub fn merge(&mut self, other: Schedule) {
+        self.windows.extend(other.windows);
+        self.breaks.extend(other.breaks);
+    }
+
+    /// Parses one entry, `[break] [DAYS] HH:MM-HH:MM`, and adds it.
+    /// Blank entries are skipped.
+    fn add_entry(&mut self, entry: &str) -> std::result::Result<(), String> {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return Ok(());
+        }
+        match entry.split_once(char::is_whitespace) {
+            Some((keyword, rest)) if keyword.eq_ignore_ascii_case("break") => {
+                self.add_break(rest.parse()?)
+            }
+            _ => self.add_window(entry.parse()?),
+        }
+        Ok(())
+    }
+
+    /// Gets the windows BusyCrab runs in.
+    pub fn windows(&self) -> &[TimeWindow] {
+        &self.windows
+    }
+
+    /// Gets the windows BusyCrab pauses in.
+    pub fn breaks(&self) -> &[TimeWindow] {
+        &self.breaks
+    }
+
+    /// Checks if BusyCrab should be active at `at`: inside a window (or there are no
+    /// windows) and not inside a break.
+    pub fn is_active(&self, at: NaiveDateTime) -> bool {
+        let in_window =
+            self.windows.is_empty() || self.windows.iter().any(|window| window.contains(at));
+        in_window && !self.breaks.iter().any(|window| window.contains(at))
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: Vec<String> = self
+            .windows
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.breaks.iter().map(|window| format!("break {}", window)))
+            .collect();
+        if entries.is_empty() {
+            f.pad("always")
+        } else {
+            f.pad(&entries.join("; "))
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    /// Parses entries separated by `;` or newlines.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut schedule = Self::new();
+        for entry in s.split([';', '\n']) {
+            schedule.add_entry(entry)?;
+        }
+        if schedule.windows.is_empty() && schedule.breaks.is_empty() {
+            return Err("empty schedule".to_string());
+        }
+        Ok(schedule)
+    }
+}
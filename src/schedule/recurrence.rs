@@ -0,0 +1,387 @@
+//! `RRULE` recurrence rules from iCalendar (RFC 5545), limited to what calendar
+//! exports use for meetings and focus blocks: `FREQ` of `DAILY`, `WEEKLY`, `MONTHLY`
+//! or `YEARLY` with `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY` and `BYMONTHDAY`.
+//! Other valid rules are reported as `RuleError::Unsupported`.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Weekday};
+use std::fmt;
+use std::str::FromStr;
+
+use super::calendar::IcsTime;
+
+/// Periods to look through for a matching date before giving up on a rule that
+/// can never match, like `BYDAY=5MO;BYMONTHDAY=1` (a fifth Monday on the 1st).
+const MAX_EMPTY_PERIODS: u32 = 1000;
+
+/// How often a recurrence repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A `BYDAY` entry: a weekday, optionally the nth (or nth from last, when negative)
+/// of its kind in the month, or in the year for yearly rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByDay {
+    /// Which occurrence in the month or year; `None` means every one
+    pub ordinal: Option<i32>,
+    /// Day of the week
+    pub weekday: Weekday,
+}
+
+/// Why an `RRULE` couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    /// The rule may be valid, but uses a frequency or part that isn't supported,
+    /// like `BYMONTH`
+    Unsupported(String),
+    /// The rule is malformed
+    Invalid(String),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Unsupported(message) | RuleError::Invalid(message) => f.pad(message),
+        }
+    }
+}
+
+impl From<String> for RuleError {
+    fn from(message: String) -> Self {
+        RuleError::Invalid(message)
+    }
+}
+
+/// When a recurring event repeats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    /// Base period
+    pub frequency: Frequency,
+    /// Number of periods between repeats
+    pub interval: u32,
+    /// Most occurrences, counting the first
+    pub count: Option<u32>,
+    /// Last time an occurrence may start
+    pub until: Option<IcsTime>,
+    /// Weekdays the event falls on
+    pub by_day: Vec<ByDay>,
+    /// Days of the month the event falls on; negative counts from the end
+    pub by_month_day: Vec<i32>,
+}
+
+impl Recurrence {
+    /// Lists occurrence starts in order, beginning with `start`.
+    pub fn occurrences(&self, start: NaiveDateTime) -> Occurrences<'_> {
+        Occurrences {
+            rule: self,
+            start,
+            period: 0,
+            pending: Vec::new(),
+            produced: 0,
+            empty_periods: 0,
+            first: true,
+        }
+    }
+
+    /// Lists the dates in the `period`th period after the one holding `start`,
+    /// sorted, including dates before `start` that the caller skips.
+    fn period_dates(&self, start: NaiveDate, period: u32) -> Vec<NaiveDate> {
+        let step = i64::from(period) * i64::from(self.interval);
+        let weekdays: Vec<Weekday> = self.by_day.iter().map(|day| day.weekday).collect();
+
+        let mut dates = match self.frequency {
+            Frequency::Daily => {
+                let date = start + Duration::days(step);
+                if (weekdays.is_empty() || weekdays.contains(&date.weekday()))
+                    && self.matches_month_day(date)
+                {
+                    vec![date]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let monday = start - Duration::days(start.weekday().num_days_from_monday().into());
+                let monday = monday + Duration::weeks(step);
+                if weekdays.is_empty() {
+                    vec![monday + Duration::days(start.weekday().num_days_from_monday().into())]
+                } else {
+                    weekdays
+                        .iter()
+                        .map(|day| monday + Duration::days(day.num_days_from_monday().into()))
+                        .collect()
+                }
+            }
+            Frequency::Monthly => {
+                let months = i64::from(start.year()) * 12 + i64::from(start.month0()) + step;
+                let Some(first) = month_start(months) else {
+                    return Vec::new();
+                };
+                self.month_dates(first, start.day())
+            }
+            // Without BYDAY or BYMONTHDAY, yearly rules repeat on the start's month
+            // and day; with them, they pick days from the whole year
+            Frequency::Yearly if self.by_day.is_empty() && self.by_month_day.is_empty() => {
+                let months = (i64::from(start.year()) + step) * 12 + i64::from(start.month0());
+                let Some(first) = month_start(months) else {
+                    return Vec::new();
+                };
+                self.month_dates(first, start.day())
+            }
+            Frequency::Yearly => {
+                let Some(first) = month_start((i64::from(start.year()) + step) * 12) else {
+                    return Vec::new();
+                };
+                let length = if first.leap_year() { 366 } else { 365 };
+                self.span_dates(first, length)
+            }
+        };
+        dates.sort();
+        dates.dedup();
+        dates
+    }
+
+    /// Lists the dates the rule picks in the month starting at `first`.
+    /// Without `BYDAY` or `BYMONTHDAY`, that's `day`, if the month has it.
+    fn month_dates(&self, first: NaiveDate, day: u32) -> Vec<NaiveDate> {
+        if self.by_day.is_empty() && self.by_month_day.is_empty() {
+            return first.with_day(day).into_iter().collect();
+        }
+        self.span_dates(first, days_in_month(first))
+    }
+
+    /// Lists the `BYDAY` and `BYMONTHDAY` dates in the `length` days from `first`,
+    /// a month or a year, counting `BYDAY` ordinals within it. With both, only the
+    /// `BYDAY` dates that are also `BYMONTHDAY` dates count.
+    fn span_dates(&self, first: NaiveDate, length: u32) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+
+        for by_day in &self.by_day {
+            let matching: Vec<NaiveDate> = first
+                .iter_days()
+                .take(length as usize)
+                .filter(|date| date.weekday() == by_day.weekday)
+                .collect();
+            match by_day.ordinal {
+                None => dates.extend(matching),
+                Some(n) if n > 0 => dates.extend(matching.get(n as usize - 1)),
+                Some(n) => dates.extend(
+                    matching
+                        .len()
+                        .checked_sub(n.unsigned_abs() as usize)
+                        .map(|i| matching[i]),
+                ),
+            }
+        }
+
+        if self.by_day.is_empty() {
+            dates.extend(
+                first
+                    .iter_days()
+                    .take(length as usize)
+                    .filter(|date| self.matches_month_day(*date)),
+            );
+        } else {
+            dates.retain(|date| self.matches_month_day(*date));
+        }
+        dates
+    }
+
+    /// Checks `date` is one of the `BYMONTHDAY` days, or that there are none.
+    fn matches_month_day(&self, date: NaiveDate) -> bool {
+        if self.by_month_day.is_empty() {
+            return true;
+        }
+        let length = days_in_month(date.with_day(1).unwrap()) as i32;
+        self.by_month_day.iter().any(|&day| {
+            let day = if day < 0 { length + day + 1 } else { day };
+            day == date.day() as i32
+        })
+    }
+}
+
+/// Occurrence starts of a recurrence, from `Recurrence::occurrences`.
+pub struct Occurrences<'a> {
+    rule: &'a Recurrence,
+    start: NaiveDateTime,
+    period: u32,
+    /// Dates of the current period not returned yet, last first
+    pending: Vec<NaiveDate>,
+    produced: u32,
+    empty_periods: u32,
+    /// Whether `start` itself still has to be returned
+    first: bool,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<NaiveDateTime> {
+        if self.rule.count.is_some_and(|count| self.produced >= count) {
+            return None;
+        }
+
+        // The start always counts as the first occurrence, matching the rule or not
+        let candidate = if self.first {
+            self.first = false;
+            self.start
+        } else {
+            while self.pending.is_empty() {
+                if self.empty_periods >= MAX_EMPTY_PERIODS {
+                    return None;
+                }
+                let mut dates = self.rule.period_dates(self.start.date(), self.period);
+                self.period += 1;
+                dates.retain(|date| *date > self.start.date());
+                self.empty_periods = if dates.is_empty() {
+                    self.empty_periods + 1
+                } else {
+                    0
+                };
+                dates.reverse();
+                self.pending = dates;
+            }
+            self.pending.pop()?.and_time(self.start.time())
+        };
+
+        if self.rule.until.is_some_and(|until| candidate > until.value) {
+            return None;
+        }
+        self.produced += 1;
+        Some(candidate)
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = RuleError;
+
+    /// Parses the value of an `RRULE` property. `UNTIL` has to be converted to the
+    /// event's time base before listing occurrences.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut frequency = None;
+        let mut rule = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+        };
+
+        for part in s.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("invalid RRULE part '{}'", part))?;
+            let number = |value: &str| {
+                value
+                    .parse::<i32>()
+                    .map_err(|_| format!("invalid number in RRULE part '{}'", part))
+            };
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        "HOURLY" | "MINUTELY" | "SECONDLY" => {
+                            return Err(RuleError::Unsupported(format!(
+                                "unsupported RRULE frequency '{}'",
+                                value
+                            )))
+                        }
+                        _ => return Err(format!("invalid RRULE frequency '{}'", value).into()),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = u32::try_from(number(value)?)
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| format!("invalid RRULE interval '{}'", value))?
+                }
+                "COUNT" => {
+                    rule.count = Some(
+                        u32::try_from(number(value)?)
+                            .map_err(|_| format!("invalid RRULE count '{}'", value))?,
+                    )
+                }
+                "UNTIL" => rule.until = Some(IcsTime::parse(value)?),
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        rule.by_day.push(parse_by_day(day)?);
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for day in value.split(',') {
+                        rule.by_month_day.push(number(day)?);
+                    }
+                }
+                // Only changes which week BYDAY counts from, which doesn't matter for
+                // the weekly rules supported here unless INTERVAL > 1 and WKST isn't MO
+                "WKST" => {}
+                _ => {
+                    return Err(RuleError::Unsupported(format!(
+                        "unsupported RRULE part '{}'",
+                        name
+                    )))
+                }
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(|| "RRULE without FREQ".to_string())?;
+        // RFC 5545 doesn't allow BYMONTHDAY on weekly rules
+        if rule.frequency == Frequency::Weekly && !rule.by_month_day.is_empty() {
+            return Err(RuleError::Invalid(
+                "BYMONTHDAY can't be used with FREQ=WEEKLY".to_string(),
+            ));
+        }
+        Ok(rule)
+    }
+}
+
+/// Parses a `BYDAY` entry like `MO`, `2TU` or `-1FR`.
+fn parse_by_day(s: &str) -> Result<ByDay, String> {
+    let s = s.trim();
+    let split = s.len().saturating_sub(2);
+    let (ordinal, weekday) = s.split_at(split);
+    let weekday = match weekday.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(format!("invalid BYDAY '{}'", s)),
+    };
+    let ordinal = match ordinal {
+        "" => None,
+        ordinal => Some(
+            ordinal
+                .trim_start_matches('+')
+                .parse::<i32>()
+                .ok()
+                .filter(|n| *n != 0)
+                .ok_or_else(|| format!("invalid BYDAY '{}'", s))?,
+        ),
+    };
+    Ok(ByDay { ordinal, weekday })
+}
+
+/// Gets the first day of the month `months` months after year 0.
+fn month_start(months: i64) -> Option<NaiveDate> {
+    let year = i32::try_from(months.div_euclid(12)).ok()?;
+    NaiveDate::from_ymd_opt(year, months.rem_euclid(12) as u32 + 1, 1)
+}
+
+/// Gets the number of days in the month starting at `first`.
+fn days_in_month(first: NaiveDate) -> u32 {
+    let next = first
+        .checked_add_months(chrono::Months::new(1))
+        .unwrap_or(NaiveDate::MAX);
+    (next - first).num_days() as u32
+}
@@ -0,0 +1,180 @@
+//! Weekly time windows such as `Mon-Fri 09:00-18:00`.
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use std::fmt;
+use std::str::FromStr;
+
+/// Days of the week, Monday first.
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// A set of weekdays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Days([bool; 7]);
+
+impl Days {
+    /// Every day of the week.
+    pub const ALL: Days = Days([true; 7]);
+
+    /// Monday to Friday.
+    pub const WEEKDAYS: Days = Days([true, true, true, true, true, false, false]);
+
+    /// Saturday and Sunday.
+    pub const WEEKEND: Days = Days([false, false, false, false, false, true, true]);
+
+    /// Checks if `day` is in the set.
+    pub fn contains(self, day: Weekday) -> bool {
+        self.0[day.num_days_from_monday() as usize]
+    }
+
+    /// Adds `day` to the set.
+    fn insert(&mut self, day: Weekday) {
+        self.0[day.num_days_from_monday() as usize] = true;
+    }
+}
+
+impl fmt::Display for Days {
+    /// Writes the days as ranges, like `Mon-Fri` or `Mon,Wed-Thu`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Days::ALL {
+            return f.pad("daily");
+        }
+        let mut parts = Vec::new();
+        let mut i = 0;
+        while i < WEEK.len() {
+            if !self.0[i] {
+                i += 1;
+                continue;
+            }
+            let first = i;
+            while i + 1 < WEEK.len() && self.0[i + 1] {
+                i += 1;
+            }
+            parts.push(if first == i {
+                WEEK[first].to_string()
+            } else {
+                format!("{}-{}", WEEK[first], WEEK[i])
+            });
+            i += 1;
+        }
+        f.pad(&parts.join(","))
+    }
+}
+
+impl FromStr for Days {
+    type Err = String;
+
+    /// Parses `daily`, `weekdays`, `weekends`, or a comma-separated list of days
+    /// and ranges like `Mon-Wed,Fri`. Ranges may wrap around the week, like `Fri-Mon`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" => return Ok(Days::ALL),
+            "weekdays" => return Ok(Days::WEEKDAYS),
+            "weekends" => return Ok(Days::WEEKEND),
+            _ => {}
+        }
+
+        let day = |name: &str| {
+            name.trim()
+                .parse::<Weekday>()
+                .map_err(|_| format!("unknown day '{}'", name.trim()))
+        };
+        let mut days = Days([false; 7]);
+        for part in s.split(',') {
+            match part.split_once('-') {
+                Some((first, last)) => {
+                    let (mut current, last) = (day(first)?, day(last)?);
+                    days.insert(current);
+                    while current != last {
+                        current = current.succ();
+                        days.insert(current);
+                    }
+                }
+                None => days.insert(day(part)?),
+            }
+        }
+        Ok(days)
+    }
+}
+
+/// A time of day range on some days of the week.
+///
+/// A window whose end isn't after its start runs past midnight into the next day,
+/// so `22:00-06:00` on Friday covers Friday night until Saturday morning and
+/// `00:00-24:00` covers whole days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    /// Days the window starts on
+    pub days: Days,
+    /// Time the window opens
+    pub start: NaiveTime,
+    /// Time the window closes
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    /// Creates a window from `start` to `end` on `days`.
+    pub fn new(days: Days, start: NaiveTime, end: NaiveTime) -> Self {
+        Self { days, start, end }
+    }
+
+    /// Checks if `at` falls in the window.
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        let (day, time) = (at.weekday(), at.time());
+        if self.start < self.end {
+            self.days.contains(day) && self.start <= time && time < self.end
+        } else {
+            (self.days.contains(day) && time >= self.start)
+                || (self.days.contains(day.pred()) && time < self.end)
+        }
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let end = if self.end == NaiveTime::MIN {
+            "24:00".to_string()
+        } else {
+            self.end.format("%H:%M").to_string()
+        };
+        f.pad(&format!(
+            "{} {}-{}",
+            self.days,
+            self.start.format("%H:%M"),
+            end
+        ))
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = String;
+
+    /// Parses `[DAYS] HH:MM-HH:MM`. Without days, the window applies daily.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (days, times) = match s.rsplit_once(char::is_whitespace) {
+            Some((days, times)) => (days.trim().replace(' ', "").parse()?, times),
+            None => (Days::ALL, s),
+        };
+        let (start, end) = times
+            .split_once('-')
+            .ok_or_else(|| format!("expected HH:MM-HH:MM in '{}'", s))?;
+        Ok(Self::new(days, time(start)?, time(end)?))
+    }
+}
+
+/// Parses `HH:MM`, accepting `24:00` as midnight at the end of a day.
+fn time(s: &str) -> Result<NaiveTime, String> {
+    let s = s.trim();
+    if s == "24:00" {
+        return Ok(NaiveTime::MIN);
+    }
+    NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| format!("invalid time '{}'", s))
+}
@@ -0,0 +1,131 @@
+//! # Variation
+//!
+//! Keeps activity from ticking like a metronome. The interval and wiggle distance can
+//! be ranges (`--interval 45..90`) that each cycle draws a fresh value from, and the
+//! interval can get a random jitter of a few percent on top.
+
+use rand::distr::uniform::SampleUniform;
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// An inclusive range of values, or a single value when `min` and `max` are equal.
+/// Written as `60` or `45..90` on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueRange<T> {
+    /// Smallest value that can be drawn
+    pub min: T,
+    /// Largest value that can be drawn
+    pub max: T,
+}
+
+impl<T: Copy + PartialOrd> ValueRange<T> {
+    /// Creates a range that always draws `value`.
+    pub fn fixed(value: T) -> Self {
+        Self {
+            min: value,
+            max: value,
+        }
+    }
+
+    /// Creates a range from `min` to `max`, inclusive.
+    ///
+    /// ### Returns
+    ///
+    /// - `Some` with the range.
+    /// - `None` if `min` is larger than `max`.
+    pub fn new(min: T, max: T) -> Option<Self> {
+        (min <= max).then_some(Self { min, max })
+    }
+
+    /// Checks if the range only holds one value.
+    pub fn is_fixed(&self) -> bool {
+        self.min == self.max
+    }
+}
+
+impl<T: Copy + PartialOrd + SampleUniform> ValueRange<T> {
+    /// Draws a value from the range.
+    pub fn sample(&self, rng: &mut impl Rng) -> T {
+        if self.is_fixed() {
+            self.min
+        } else {
+            rng.random_range(self.min..=self.max)
+        }
+    }
+}
+
+impl<T: fmt::Display + PartialEq> fmt::Display for ValueRange<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            f.pad(&self.min.to_string())
+        } else {
+            f.pad(&format!("{}..{}", self.min, self.max))
+        }
+    }
+}
+
+impl<T> FromStr for ValueRange<T>
+where
+    T: Copy + PartialOrd + FromStr + fmt::Display,
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<T>()
+                .map_err(|_| format!("invalid value '{}' (expected a number or MIN..MAX)", s))
+        };
+
+        match s.split_once("..") {
+            None => Ok(Self::fixed(parse(s)?)),
+            Some((min, max)) => {
+                let (min, max) = (parse(min)?, parse(max.trim_start_matches('='))?);
+                Self::new(min, max)
+                    .ok_or_else(|| format!("range '{}' starts above its end ({} > {})", s, min, max))
+            }
+        }
+    }
+}
+
+/// Serializes as written on the command line, like `"60"` or `"45..90"`.
+impl<T: fmt::Display + PartialEq> Serialize for ValueRange<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes from a number, or a string as written on the command line.
+impl<'de, T> Deserialize<'de> for ValueRange<T>
+where
+    T: Copy + PartialOrd + FromStr + fmt::Display,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Written {
+            Number(i64),
+            Text(String),
+        }
+
+        let text = match Written::deserialize(deserializer)? {
+            Written::Number(number) => number.to_string(),
+            Written::Text(text) => text,
+        };
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Adds up to `percent` percent of `secs`, in either direction, rounded to whole seconds.
+/// Never jitters below 1 second, so a large percentage can't leave no pause at all.
+pub fn jitter_secs(secs: u64, percent: u32, rng: &mut impl Rng) -> u64 {
+    let spread = secs.saturating_mul(u64::from(percent)) / 100;
+    if spread == 0 {
+        return secs;
+    }
+    let min = secs.saturating_sub(spread).max(1);
+    rng.random_range(min..=secs.saturating_add(spread))
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use busycrab::platform::{self, Backend};
+use busycrab::BusyCrab;
+
+#[test]
+fn test_backend_names_round_trip() {
+    for backend in Backend::chain() {
+        let parsed: Backend = backend.name().parse().unwrap();
+        assert_eq!(parsed, *backend);
+    }
+    assert_eq!("X11".parse::<Backend>(), Ok(Backend::X11));
+    assert!("bogus".parse::<Backend>().is_err());
+}
+
+#[test]
+fn test_backend_chain_ends_with_none() {
+    let chain = Backend::chain();
+    assert_eq!(chain.last(), Some(&Backend::None));
+    assert_eq!(Backend::default(), chain[0]);
+}
+
+#[test]
+fn test_select_forced_none() {
+    let (backend, platform) = platform::select(Some(Backend::None)).unwrap();
+    assert_eq!(backend, Backend::None);
+    assert!(platform.prevent_sleep().is_ok());
+}
+
+#[test]
+fn test_select_auto_always_finds_a_backend() {
+    // The no-op backend is always available as a last resort
+    let (backend, _platform) = platform::select(None).unwrap();
+    assert!(Backend::chain().contains(&backend));
+}
+
+#[test]
+fn test_probe_all_covers_chain() {
+    let results = platform::probe_all();
+    let backends: Vec<Backend> = results.iter().map(|(backend, _)| *backend).collect();
+    assert_eq!(backends, Backend::chain());
+    assert!(results.last().unwrap().1.is_ok());
+}
+
+#[test]
+fn test_busycrab_with_platform() {
+    let (backend, platform) = platform::select(Some(Backend::None)).unwrap();
+    let busycrab = BusyCrab::new(60, 3).with_platform(backend, platform);
+    assert_eq!(busycrab.get_backend(), Backend::None);
+}
@@ -0,0 +1,306 @@
+use busycrab::schedule::{Calendar, Recurrence};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use std::path::Path;
+
+/// Builds a local time in January 2024, which starts on a Monday.
+fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, day)
+        .unwrap()
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+}
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Lists the first `n` occurrences of a rule starting at `start`.
+fn occurrences(rule: &str, start: NaiveDateTime, n: usize) -> Vec<NaiveDateTime> {
+    let rule: Recurrence = rule.parse().unwrap();
+    rule.occurrences(start).take(n).collect()
+}
+
+#[test]
+fn test_recurrence_daily_and_weekly() {
+    assert_eq!(
+        occurrences("FREQ=DAILY;INTERVAL=2", at(1, 9, 0), 3),
+        vec![at(1, 9, 0), at(3, 9, 0), at(5, 9, 0)]
+    );
+    assert_eq!(
+        occurrences("FREQ=WEEKLY;BYDAY=MO,TH", at(1, 9, 0), 4),
+        vec![at(1, 9, 0), at(4, 9, 0), at(8, 9, 0), at(11, 9, 0)]
+    );
+    // Weekdays only, from a Friday
+    assert_eq!(
+        occurrences("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR", at(5, 8, 0), 2),
+        vec![at(5, 8, 0), at(8, 8, 0)]
+    );
+}
+
+#[test]
+fn test_recurrence_monthly() {
+    let date = |month, day| {
+        NaiveDate::from_ymd_opt(2024, month, day)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap()
+    };
+    // Months without a 31st are skipped
+    assert_eq!(
+        occurrences("FREQ=MONTHLY", date(1, 31), 3),
+        vec![date(1, 31), date(3, 31), date(5, 31)]
+    );
+    assert_eq!(
+        occurrences("FREQ=MONTHLY;BYDAY=2TU", date(1, 9), 3),
+        vec![date(1, 9), date(2, 13), date(3, 12)]
+    );
+    assert_eq!(
+        occurrences("FREQ=MONTHLY;BYMONTHDAY=-1", date(1, 31), 3),
+        vec![date(1, 31), date(2, 29), date(3, 31)]
+    );
+    // BYDAY and BYMONTHDAY together pick the days matching both: Friday the 13th
+    let friday_13th = |year, month| {
+        NaiveDate::from_ymd_opt(year, month, 13)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap()
+    };
+    assert_eq!(
+        occurrences("FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13", date(9, 13), 3),
+        vec![date(9, 13), date(12, 13), friday_13th(2025, 6)]
+    );
+    assert_eq!(
+        occurrences("FREQ=DAILY;BYMONTHDAY=1,15", date(1, 1), 3),
+        vec![date(1, 1), date(1, 15), date(2, 1)]
+    );
+}
+
+#[test]
+fn test_recurrence_count_and_until() {
+    assert_eq!(occurrences("FREQ=DAILY;COUNT=3", at(1, 9, 0), 10).len(), 3);
+    assert_eq!(
+        occurrences("FREQ=DAILY;UNTIL=20240103T090000", at(1, 9, 0), 10),
+        vec![at(1, 9, 0), at(2, 9, 0), at(3, 9, 0)]
+    );
+    // A rule that can never match again ends instead of searching forever: the
+    // fifth Monday of a month is never the 1st
+    assert_eq!(
+        occurrences("FREQ=MONTHLY;BYDAY=5MO;BYMONTHDAY=1", at(1, 9, 0), 10),
+        vec![at(1, 9, 0)]
+    );
+}
+
+#[test]
+fn test_recurrence_yearly() {
+    let date = |year, month, day| {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+    };
+    assert_eq!(
+        occurrences("FREQ=YEARLY", date(2024, 3, 15), 3),
+        vec![date(2024, 3, 15), date(2025, 3, 15), date(2026, 3, 15)]
+    );
+    // BYDAY covers the whole year, not just the start's month
+    assert_eq!(
+        occurrences("FREQ=YEARLY;BYDAY=MO", at(22, 9, 0), 3),
+        vec![at(22, 9, 0), at(29, 9, 0), date(2024, 2, 5)]
+    );
+    assert_eq!(
+        occurrences("FREQ=YEARLY;BYDAY=MO", date(2024, 12, 30), 2),
+        vec![date(2024, 12, 30), date(2025, 1, 6)]
+    );
+    // Ordinals count within the year: the 10th and last Mondays of it
+    assert_eq!(
+        occurrences("FREQ=YEARLY;BYDAY=10MO,-1MO", at(1, 9, 0), 4),
+        vec![
+            at(1, 9, 0),
+            date(2024, 3, 4),
+            date(2024, 12, 30),
+            date(2025, 3, 10)
+        ]
+    );
+    // BYMONTHDAY picks that day of every month, skipping months without it
+    assert_eq!(
+        occurrences("FREQ=YEARLY;BYMONTHDAY=30", date(2024, 1, 30), 3),
+        vec![date(2024, 1, 30), date(2024, 3, 30), date(2024, 4, 30)]
+    );
+    // And with BYDAY, only the days matching both: Friday the 13th
+    assert_eq!(
+        occurrences("FREQ=YEARLY;BYDAY=FR;BYMONTHDAY=13", date(2024, 9, 13), 3),
+        vec![date(2024, 9, 13), date(2024, 12, 13), date(2025, 6, 13)]
+    );
+}
+
+#[test]
+fn test_recurrence_parse_errors() {
+    assert!("INTERVAL=2".parse::<Recurrence>().is_err());
+    assert!("FREQ=HOURLY".parse::<Recurrence>().is_err());
+    assert!("FREQ=DAILY;INTERVAL=0".parse::<Recurrence>().is_err());
+    assert!("FREQ=WEEKLY;BYDAY=XX".parse::<Recurrence>().is_err());
+    assert!("FREQ=DAILY;BYSETPOS=1".parse::<Recurrence>().is_err());
+    assert!("FREQ=WEEKLY;BYMONTHDAY=13".parse::<Recurrence>().is_err());
+}
+
+#[test]
+fn test_calendar_fixture_events() {
+    let calendar = Calendar::load(fixture("calendar/work.ics")).unwrap();
+    // The moved occurrence is its own event; the cancelled one and the alarm aren't
+    assert_eq!(calendar.events().len(), 5);
+    assert_eq!(calendar.to_string(), "5 events");
+
+    // Folded and escaped summary
+    assert!(calendar
+        .events()
+        .iter()
+        .any(|event| event.summary == "Deep work, no meetings (focus)"));
+    // A yearly rule with BYMONTH isn't supported, so that event is left out
+    // instead of failing the whole calendar
+    assert_eq!(calendar.skipped().len(), 1);
+    let skipped = &calendar.skipped()[0];
+    assert!(skipped.contains("work.ics: line 52"), "{}", skipped);
+    assert!(skipped.contains("\"Focus on cake\""), "{}", skipped);
+    assert!(
+        skipped.contains("unsupported RRULE part 'BYMONTH'"),
+        "{}",
+        skipped
+    );
+    assert!(!calendar.with_filter("cake").is_active(at(10, 12, 0)));
+}
+
+#[test]
+fn test_calendar_recurring_focus() {
+    let calendar = Calendar::load(fixture("calendar/work.ics"))
+        .unwrap()
+        .with_filter("focus time");
+    assert_eq!(calendar.filter(), Some("focus time"));
+
+    assert!(calendar.is_active(at(1, 9, 0)));
+    assert!(calendar.is_active(at(1, 10, 59)));
+    assert!(!calendar.is_active(at(1, 11, 0)));
+    // Standup doesn't match the filter
+    assert!(!calendar.is_active(at(2, 9, 40)));
+    // Excluded date
+    assert!(!calendar.is_active(at(3, 10, 0)));
+    // Moved to the afternoon
+    assert!(!calendar.is_active(at(8, 10, 0)));
+    assert!(calendar.is_active(at(8, 15, 0)));
+    assert!(calendar.is_active(at(10, 10, 0)));
+    // Cancelled
+    assert!(!calendar.is_active(at(2, 15, 0)));
+    // Sixth and last occurrence is on the 17th
+    assert!(calendar.is_active(at(17, 10, 0)));
+    assert!(!calendar.is_active(at(22, 10, 0)));
+}
+
+#[test]
+fn test_calendar_until_all_day_and_monthly() {
+    let calendar = Calendar::load(fixture("calendar/work.ics")).unwrap();
+    let event = |summary: &str| {
+        calendar
+            .events()
+            .iter()
+            .find(|event| event.summary.starts_with(summary))
+            .unwrap()
+    };
+
+    // A date-only UNTIL includes that whole day
+    let standup = event("Standup");
+    assert!(standup.is_recurring());
+    assert!(standup.contains(at(5, 9, 35)));
+    assert!(!standup.contains(at(5, 9, 45)));
+    assert!(!standup.contains(at(6, 9, 35)));
+
+    let focus_day = event("Focus day");
+    assert!(!focus_day.is_recurring());
+    assert!(focus_day.contains(at(12, 0, 0)));
+    assert!(focus_day.contains(at(12, 23, 59)));
+    assert!(!focus_day.contains(at(13, 0, 0)));
+
+    // Last Friday of the month
+    let deep_work = event("Deep work");
+    let feb_23 = NaiveDate::from_ymd_opt(2024, 2, 23)
+        .unwrap()
+        .and_hms_opt(14, 0, 0)
+        .unwrap();
+    assert!(deep_work.contains(at(26, 14, 0)));
+    assert!(deep_work.contains(feb_23));
+    assert!(!deep_work.contains(feb_23 - chrono::Duration::days(7)));
+}
+
+#[test]
+fn test_calendar_utc_event() {
+    let calendar: Calendar = "BEGIN:VCALENDAR\n\
+        BEGIN:VEVENT\n\
+        SUMMARY:Focus\n\
+        DTSTART:20240104T120000Z\n\
+        DTEND:20240104T130000Z\n\
+        END:VEVENT\n\
+        END:VCALENDAR\n"
+        .parse()
+        .unwrap();
+
+    let start = Utc
+        .from_utc_datetime(&at(4, 12, 0))
+        .with_timezone(&Local)
+        .naive_local();
+    assert!(calendar.is_active(start));
+    assert!(calendar.is_active(start + chrono::Duration::minutes(59)));
+    assert!(!calendar.is_active(start + chrono::Duration::minutes(60)));
+    assert!(!calendar.is_active(start - chrono::Duration::minutes(1)));
+}
+
+#[test]
+fn test_calendar_directory() {
+    let calendar = Calendar::load(fixture("calendar")).unwrap();
+    assert_eq!(calendar.events().len(), 6);
+
+    let calendar = calendar.with_filter("GYM");
+    assert_eq!(calendar.to_string(), "1 of 6 events matching \"GYM\"");
+    // Every other Tuesday
+    assert!(calendar.is_active(at(2, 18, 30)));
+    assert!(!calendar.is_active(at(9, 18, 30)));
+    assert!(calendar.is_active(at(16, 18, 30)));
+    assert_eq!(
+        calendar.active_event(at(16, 18, 30)).unwrap().summary,
+        "Gym"
+    );
+}
+
+#[test]
+fn test_calendar_errors() {
+    let err = Calendar::load(fixture("missing.ics")).unwrap_err();
+    assert!(err.to_string().contains("missing.ics"));
+
+    // A directory without calendars
+    let err = Calendar::load(fixture("")).unwrap_err();
+    assert!(err.to_string().contains("no .ics files"));
+
+    let err = "BEGIN:VEVENT\nSUMMARY:Focus\nEND:VEVENT\n"
+        .parse::<Calendar>()
+        .unwrap_err();
+    assert!(err.contains("line 3"));
+    assert!(err.contains("DTSTART"));
+
+    let err = "BEGIN:VEVENT\nDTSTART:20240101T090000\nRRULE:FREQ=SOMETIMES\nEND:VEVENT\n"
+        .parse::<Calendar>()
+        .unwrap_err();
+    assert!(err.contains("line 3"));
+
+    // Unsupported rules only skip their event
+    let calendar = "BEGIN:VEVENT\nDTSTART:20240101T090000\nRRULE:FREQ=HOURLY\nEND:VEVENT\n"
+        .parse::<Calendar>()
+        .unwrap();
+    assert!(calendar.events().is_empty());
+    assert_eq!(
+        calendar.skipped(),
+        ["line 1: skipped \"\": unsupported RRULE frequency 'HOURLY'"]
+    );
+
+    assert!("BEGIN:VEVENT\nDTSTART:20240101T090000\n"
+        .parse::<Calendar>()
+        .is_err());
+}
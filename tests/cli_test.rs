@@ -0,0 +1,287 @@
+mod common;
+
+use busycrab::cli::{Args, Command};
+use busycrab::failure::FailureMode;
+use busycrab::idle::IdleSource;
+use busycrab::input::{ActivityKey, ActivityMode, InputBackend, Positioning};
+use busycrab::limit::{RunDuration, StopTime};
+use busycrab::pattern::PatternKind;
+use busycrab::schedule::HolidayAction;
+use busycrab::{Backend, ValueRange};
+use clap::Parser;
+use std::time::Duration;
+
+#[test]
+fn test_default_args() {
+    let args = Args::try_parse_from(["busycrab"]).unwrap();
+    assert_eq!(args.interval, ValueRange::fixed(60));
+    assert_eq!(args.wiggle, ValueRange::fixed(3));
+    assert_eq!(args.jitter, 0);
+    assert_eq!(args.motion, "crab");
+    assert_eq!(args.backend, None);
+    assert_eq!(args.pattern, PatternKind::Horizontal);
+    assert_eq!(args.pattern_duration, 800);
+    assert_eq!(args.seed, None);
+    assert_eq!(args.activity, ActivityMode::Mouse);
+    assert_eq!(args.key, ActivityKey::F15);
+    assert_eq!(args.input, InputBackend::Enigo);
+    assert_eq!(args.on_failure, FailureMode::Fail);
+    assert_eq!(args.max_retries, 3);
+    assert!(args.command.is_none());
+}
+
+#[test]
+fn test_backend_flag() {
+    let args = Args::try_parse_from(["busycrab", "--backend", "wayland"]).unwrap();
+    assert_eq!(args.backend, Some(Backend::Wayland));
+
+    assert!(Args::try_parse_from(["busycrab", "--backend", "bogus"]).is_err());
+}
+
+#[test]
+fn test_backends_subcommand() {
+    let args = Args::try_parse_from(["busycrab", "backends"]).unwrap();
+    assert!(matches!(args.command, Some(Command::Backends)));
+}
+
+#[test]
+fn test_failure_flags() {
+    let args =
+        Args::try_parse_from(["busycrab", "--on-failure", "retry", "--max-retries", "5"]).unwrap();
+    assert_eq!(args.on_failure, FailureMode::Retry);
+    assert_eq!(args.max_retries, 5);
+
+    assert!(Args::try_parse_from(["busycrab", "--on-failure", "panic"]).is_err());
+}
+
+#[test]
+fn test_input_flag() {
+    let args = Args::try_parse_from(["busycrab", "--input", "uinput"]).unwrap();
+    assert_eq!(args.input, InputBackend::Uinput);
+
+    assert!(Args::try_parse_from(["busycrab", "--input", "joystick"]).is_err());
+}
+
+#[test]
+fn test_activity_flags() {
+    #[cfg(not(target_os = "macos"))]
+    {
+        let args = Args::try_parse_from(["busycrab", "--activity", "both", "--key", "scrolllock"])
+            .unwrap();
+        assert_eq!(args.activity, ActivityMode::Both);
+        assert_eq!(args.key, ActivityKey::ScrollLock);
+    }
+    // Mac keyboards have no Scroll Lock to tap
+    #[cfg(target_os = "macos")]
+    assert!(Args::try_parse_from(["busycrab", "--key", "scroll-lock"]).is_err());
+
+    let args = Args::try_parse_from(["busycrab", "-a", "keyboard", "-k", "Shift"]).unwrap();
+    assert_eq!(args.activity, ActivityMode::Keyboard);
+    assert_eq!(args.key, ActivityKey::Shift);
+
+    assert!(Args::try_parse_from(["busycrab", "--activity", "telepathy"]).is_err());
+    assert!(Args::try_parse_from(["busycrab", "--key", "enter"]).is_err());
+}
+
+#[test]
+fn test_pattern_flag() {
+    let args = Args::try_parse_from(["busycrab", "--pattern", "figure-eight"]).unwrap();
+    assert_eq!(args.pattern, PatternKind::FigureEight);
+
+    let args = Args::try_parse_from(["busycrab", "-p", "circle"]).unwrap();
+    assert_eq!(args.pattern, PatternKind::Circle);
+
+    let args = Args::try_parse_from([
+        "busycrab",
+        "--pattern",
+        "bezier",
+        "--pattern-duration",
+        "1500",
+        "--seed",
+        "42",
+    ])
+    .unwrap();
+    assert_eq!(args.pattern, PatternKind::Bezier);
+    assert_eq!(args.pattern_duration, 1500);
+    assert_eq!(args.seed, Some(42));
+}
+
+#[test]
+fn test_idle_flags() {
+    let args = Args::try_parse_from(["busycrab"]).unwrap();
+    assert_eq!(args.idle_threshold, 0);
+    assert_eq!(args.idle_source, IdleSource::Auto);
+
+    let args =
+        Args::try_parse_from(["busycrab", "--idle-threshold", "120", "--idle-source", "input"])
+            .unwrap();
+    assert_eq!(args.idle_threshold, 120);
+    assert_eq!(args.idle_source, IdleSource::Input);
+
+    assert!(Args::try_parse_from(["busycrab", "--idle-source", "webcam"]).is_err());
+}
+
+#[test]
+fn test_positioning_flag() {
+    let args = Args::try_parse_from(["busycrab"]).unwrap();
+    assert_eq!(args.positioning, Positioning::Relative);
+
+    let args = Args::try_parse_from(["busycrab", "--positioning", "Absolute"]).unwrap();
+    assert_eq!(args.positioning, Positioning::Absolute);
+
+    assert!(Args::try_parse_from(["busycrab", "--positioning", "teleport"]).is_err());
+}
+
+#[test]
+fn test_range_flags() {
+    let args =
+        Args::try_parse_from(["busycrab", "-i", "45..90", "--wiggle", "1..4", "--jitter", "20"])
+            .unwrap();
+    assert_eq!(args.interval, ValueRange::new(45, 90).unwrap());
+    assert_eq!(args.wiggle, ValueRange::new(1, 4).unwrap());
+    assert_eq!(args.jitter, 20);
+
+    assert!(Args::try_parse_from(["busycrab", "--interval", "90..45"]).is_err());
+    assert!(Args::try_parse_from(["busycrab", "--interval", "soon"]).is_err());
+    assert!(Args::try_parse_from(["busycrab", "--jitter", "150"]).is_err());
+}
+
+#[test]
+fn test_schedule_flags() {
+    let args = Args::try_parse_from(["busycrab"]).unwrap();
+    assert!(args.schedule.is_none());
+    assert!(args.schedule_file.is_none());
+
+    let args = Args::try_parse_from([
+        "busycrab",
+        "--schedule",
+        "Mon-Fri 09:00-18:00; break 12:00-13:00",
+        "--schedule-file",
+        "hours.txt",
+    ])
+    .unwrap();
+    let schedule = args.schedule.unwrap();
+    assert_eq!(schedule.windows().len(), 1);
+    assert_eq!(schedule.breaks().len(), 1);
+    assert_eq!(args.schedule_file.unwrap().to_str(), Some("hours.txt"));
+
+    assert!(Args::try_parse_from(["busycrab", "--schedule", "whenever"]).is_err());
+}
+
+#[test]
+fn test_time_limit_flags() {
+    let args = Args::try_parse_from(["busycrab"]).unwrap();
+    assert!(args.duration.is_none());
+    assert!(args.until.is_none());
+
+    let args = Args::try_parse_from(["busycrab", "--duration", "2h30m", "--until", "17:45"]).unwrap();
+    assert_eq!(args.duration, Some(RunDuration(Duration::from_secs(9000))));
+    assert_eq!(args.until, Some("17:45".parse::<StopTime>().unwrap()));
+
+    assert!(Args::try_parse_from(["busycrab", "--duration", "forever"]).is_err());
+    assert!(Args::try_parse_from(["busycrab", "--until", "teatime"]).is_err());
+}
+
+#[test]
+fn test_calendar_flags() {
+    let args = Args::try_parse_from(["busycrab"]).unwrap();
+    assert!(args.calendar.is_none());
+    assert!(args.calendar_filter.is_none());
+
+    let args = Args::try_parse_from([
+        "busycrab",
+        "--calendar",
+        "work.ics",
+        "--calendar-filter",
+        "Focus",
+    ])
+    .unwrap();
+    assert_eq!(args.calendar.unwrap().to_str(), Some("work.ics"));
+    assert_eq!(args.calendar_filter.as_deref(), Some("Focus"));
+
+    // The filter needs a calendar
+    assert!(Args::try_parse_from(["busycrab", "--calendar-filter", "Focus"]).is_err());
+}
+
+#[test]
+fn test_holiday_flags() {
+    let args = Args::try_parse_from(["busycrab"]).unwrap();
+    assert!(args.holidays.is_none());
+    assert_eq!(args.on_holiday, HolidayAction::Pause);
+
+    let args = Args::try_parse_from([
+        "busycrab",
+        "--holidays",
+        "days-off.txt",
+        "--on-holiday",
+        "exit",
+    ])
+    .unwrap();
+    assert_eq!(args.holidays.unwrap().to_str(), Some("days-off.txt"));
+    assert_eq!(args.on_holiday, HolidayAction::Exit);
+
+    assert!(Args::try_parse_from(["busycrab", "--on-holiday", "exit"]).is_err());
+    assert!(Args::try_parse_from(["busycrab", "--holidays", "x", "--on-holiday", "party"]).is_err());
+}
+
+#[test]
+fn test_control_commands() {
+    for (name, request) in [
+        ("status", "status"),
+        ("pause", "pause"),
+        ("resume", "resume"),
+        ("stop", "stop"),
+    ] {
+        let args = Args::try_parse_from(["busycrab", name]).unwrap();
+        assert_eq!(args.command.unwrap().control_request(), Some(request));
+    }
+    assert_eq!(Command::Backends.control_request(), None);
+
+    // --socket works before or after the command
+    let args = Args::try_parse_from(["busycrab", "status", "--socket", "/tmp/crab.sock"]).unwrap();
+    assert_eq!(args.socket.unwrap().to_str(), Some("/tmp/crab.sock"));
+    let args = Args::try_parse_from(["busycrab", "--socket", "/tmp/crab.sock", "stop"]).unwrap();
+    assert!(matches!(args.command, Some(Command::Stop)));
+    assert!(Args::try_parse_from(["busycrab"]).unwrap().socket.is_none());
+}
+
+#[test]
+fn test_http_flags() {
+    let args = Args::try_parse_from(["busycrab"]).unwrap();
+    assert!(args.http.is_none());
+    assert!(args.http_token.is_none());
+
+    let args = Args::try_parse_from(["busycrab", "--http"]).unwrap();
+    assert_eq!(args.http, Some("127.0.0.1:7878".parse().unwrap()));
+
+    let args =
+        Args::try_parse_from(["busycrab", "--http", "0.0.0.0:9000", "--http-token", "s3cret"])
+            .unwrap();
+    assert_eq!(args.http, Some("0.0.0.0:9000".parse().unwrap()));
+    assert_eq!(args.http_token.as_deref(), Some("s3cret"));
+
+    assert!(Args::try_parse_from(["busycrab", "--http", "localhost"]).is_err());
+    assert!(Args::try_parse_from(["busycrab", "--http-token", "s3cret"]).is_err());
+}
+
+#[test]
+fn test_dbus_flag() {
+    assert!(!Args::try_parse_from(["busycrab"]).unwrap().dbus);
+    assert!(Args::try_parse_from(["busycrab", "--dbus"]).unwrap().dbus);
+}
+
+#[test]
+fn test_config_flags() {
+    let args = Args::try_parse_from(["busycrab"]).unwrap();
+    assert_eq!(args.config, None);
+    assert_eq!(args.profile, None);
+
+    let args =
+        Args::try_parse_from(["busycrab", "--config", "crab.toml", "--profile", "meeting"]).unwrap();
+    assert_eq!(args.config, Some("crab.toml".into()));
+    assert_eq!(args.profile.as_deref(), Some("meeting"));
+
+    // They work with the control commands too
+    let args = Args::try_parse_from(["busycrab", "status", "--profile", "meeting"]).unwrap();
+    assert_eq!(args.profile.as_deref(), Some("meeting"));
+}
@@ -0,0 +1,69 @@
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+
+/// Minimal bus configuration: listen on a private socket and allow everything.
+const BUS_CONFIG: &str = r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+<busconfig>
+  <type>session</type>
+  <listen>unix:tmpdir=/tmp</listen>
+  <auth>EXTERNAL</auth>
+  <policy context="default">
+    <allow send_destination="*" eavesdrop="true"/>
+    <allow eavesdrop="true"/>
+    <allow own="*"/>
+  </policy>
+</busconfig>
+"#;
+
+/// A private `dbus-daemon` for tests that need a bus.
+/// The daemon is killed when this value is dropped.
+pub struct TestBus {
+    child: Child,
+    config: PathBuf,
+    /// Address clients should connect to
+    pub address: String,
+}
+
+impl TestBus {
+    /// Starts a private bus.
+    /// Returns `None` when `dbus-daemon` isn't installed so tests can skip.
+    pub fn start() -> Option<Self> {
+        let config = std::env::temp_dir().join(format!(
+            "busycrab-test-bus-{}-{:?}.conf",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::write(&config, BUS_CONFIG).ok()?;
+
+        let mut child = Command::new("dbus-daemon")
+            .arg(format!("--config-file={}", config.display()))
+            .arg("--nofork")
+            .arg("--print-address")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+
+        let mut address = String::new();
+        let stdout = child.stdout.take()?;
+        BufReader::new(stdout).read_line(&mut address).ok()?;
+
+        Some(Self {
+            child,
+            config,
+            address: address.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for TestBus {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_file(&self.config);
+    }
+}
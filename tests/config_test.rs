@@ -0,0 +1,325 @@
+use busycrab::cli::config::{default_path, env_name, load_args, ConfigFile};
+use busycrab::input::ActivityMode;
+use busycrab::{Error, ValueRange};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const CONFIG: &str = r#"
+interval = "45..90"
+motion = "clock"
+idle_threshold = 30
+
+[profile.meeting]
+activity = "keyboard"
+idle-threshold = 120
+verbose = true
+
+[profile.overnight-build]
+motion = "none"
+until = 07:00:00
+http = true
+"#;
+
+/// Writes a config file for one test, removed when dropped.
+struct TempConfig(PathBuf);
+
+impl TempConfig {
+    fn new(name: &str, text: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("busycrab-{}-{}.toml", name, std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for TempConfig {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Creates an environment lookup over `vars`.
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    move |name| vars.get(name).cloned()
+}
+
+/// Gets the error of parsing `text` as a config file.
+fn parse_error(text: &str) -> String {
+    text.parse::<ConfigFile>().unwrap_err()
+}
+
+#[test]
+fn test_config_file_values() {
+    let file: ConfigFile = CONFIG.parse().unwrap();
+    assert_eq!(
+        file.profiles().collect::<Vec<_>>(),
+        ["meeting", "overnight-build"]
+    );
+
+    let defaults = file.values(None).unwrap();
+    assert_eq!(defaults.len(), 3);
+    assert_eq!(defaults["interval"], "45..90");
+    assert_eq!(defaults["idle-threshold"], "30");
+
+    let meeting = file.values(Some("meeting")).unwrap();
+    assert_eq!(meeting["interval"], "45..90");
+    assert_eq!(meeting["idle-threshold"], "120");
+    assert_eq!(meeting["activity"], "keyboard");
+    assert_eq!(meeting["verbose"], "true");
+
+    let overnight = file.values(Some("overnight-build")).unwrap();
+    assert_eq!(overnight["motion"], "none");
+    assert_eq!(overnight["until"], "07:00:00");
+
+    assert!(file.values(Some("lunch")).is_none());
+    assert_eq!("".parse::<ConfigFile>().unwrap(), ConfigFile::default());
+}
+
+#[test]
+fn test_config_file_errors() {
+    for (text, message) in [
+        ("interval = 60\nspeed = 3\n", "line 2: unknown key 'speed'"),
+        (
+            "motion = \"none\"\n\n[profile.meeting]\ninterval = \"fast\"\n",
+            "line 4: interval: invalid value 'fast'",
+        ),
+        (
+            "jitter = 200\n",
+            "line 1: jitter: invalid value '200': 200 is not in 0..=100",
+        ),
+        (
+            "wiggle = [1, 2]\n",
+            "line 1: wiggle: expected a string, number or boolean",
+        ),
+        (
+            "dbus = \"sometimes\"\n",
+            "line 1: dbus: invalid value 'sometimes' (expected true or false)",
+        ),
+        (
+            "on-failure = \"warn\"\non_failure = \"retry\"\n",
+            "line 2: on-failure is already set on line 1",
+        ),
+        ("profile = \"meeting\"\n", "line 1:"),
+        (
+            "[profile.meeting]\nconfig = \"other.toml\"\n",
+            "line 2: config can't be set in the config file",
+        ),
+        ("interval = 60\nmotion = \n", "line 2:"),
+    ] {
+        let err = parse_error(text);
+        assert!(err.starts_with(message), "{:?}: {}", text, err);
+    }
+}
+
+#[test]
+fn test_config_file_from_file() {
+    let config = TempConfig::new("bad-config", "interval = 60\n\nkey = \"f15\"\nmouse = 1\n");
+    let err = ConfigFile::from_file(&config.0).unwrap_err();
+    assert!(matches!(err, Error::Config(_)));
+    assert!(
+        err.to_string().contains("line 4: unknown key 'mouse'"),
+        "{}",
+        err
+    );
+    assert!(err.to_string().contains(&config.0.display().to_string()));
+
+    let err = ConfigFile::from_file("/nonexistent/busycrab.toml").unwrap_err();
+    assert!(
+        err.to_string().contains("can't read config file"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_precedence() {
+    let config = TempConfig::new("precedence", CONFIG);
+    let path = config.0.to_str().unwrap();
+
+    // Defaults from the file
+    let args = load_args(["busycrab", "--config", path], env(&[])).unwrap();
+    assert_eq!(args.interval, ValueRange::new(45, 90).unwrap());
+    assert_eq!(args.motion, "clock");
+    assert_eq!(args.idle_threshold, 30);
+    assert_eq!(args.activity, ActivityMode::Mouse);
+    assert!(!args.verbose);
+
+    // The profile wins over the defaults
+    let args = load_args(
+        ["busycrab", "--config", path, "--profile", "meeting"],
+        env(&[]),
+    )
+    .unwrap();
+    assert_eq!(args.profile.as_deref(), Some("meeting"));
+    assert_eq!(args.idle_threshold, 120);
+    assert_eq!(args.activity, ActivityMode::Keyboard);
+    assert!(args.verbose);
+
+    // The environment wins over the profile
+    let vars = env(&[
+        ("BUSYCRAB_CONFIG", path),
+        ("BUSYCRAB_PROFILE", "meeting"),
+        ("BUSYCRAB_IDLE_THRESHOLD", "300"),
+        ("BUSYCRAB_MOTION", "matrix"),
+        ("BUSYCRAB_VERBOSE", "false"),
+    ]);
+    let args = load_args(["busycrab"], &vars).unwrap();
+    assert_eq!(args.idle_threshold, 300);
+    assert_eq!(args.motion, "matrix");
+    assert_eq!(args.activity, ActivityMode::Keyboard);
+    assert!(!args.verbose);
+
+    // The command line wins over everything
+    let args = load_args(
+        [
+            "busycrab",
+            "--idle-threshold",
+            "5",
+            "-i",
+            "10",
+            "--profile",
+            "overnight-build",
+        ],
+        &vars,
+    )
+    .unwrap();
+    assert_eq!(args.idle_threshold, 5);
+    assert_eq!(args.interval, ValueRange::fixed(10));
+    assert_eq!(args.motion, "matrix");
+    assert_eq!(args.until.unwrap().to_string(), "07:00");
+    assert_eq!(args.http, Some("127.0.0.1:7878".parse().unwrap()));
+}
+
+#[test]
+fn test_load_errors() {
+    let config = TempConfig::new("load-errors", CONFIG);
+    let path = config.0.to_str().unwrap();
+
+    let err = load_args(
+        ["busycrab", "--config", path, "--profile", "lunch"],
+        env(&[]),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("no profile 'lunch' in")
+            && err.to_string().contains("meeting, overnight-build"),
+        "{}",
+        err
+    );
+
+    let err = load_args(
+        ["busycrab", "--config", "/nonexistent/busycrab.toml"],
+        env(&[]),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("can't read config file"),
+        "{}",
+        err
+    );
+
+    let err = load_args(
+        ["busycrab", "--config", path],
+        env(&[("BUSYCRAB_JITTER", "lots")]),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("BUSYCRAB_JITTER: invalid value 'lots'"),
+        "{}",
+        err
+    );
+
+    // Flags from the file still have to go together
+    let other = TempConfig::new("requires", "calendar-filter = \"Focus\"\n");
+    let err = load_args(
+        ["busycrab", "--config", other.0.to_str().unwrap()],
+        env(&[]),
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::Config(_)));
+    assert!(err.to_string().contains("--calendar"), "{}", err);
+    assert!(
+        err.to_string().contains(&format!(
+            "calendar-filter (from {} line 1)",
+            other.0.display()
+        )),
+        "{}",
+        err
+    );
+
+    // The same for profiles and the environment
+    let profiled = TempConfig::new(
+        "requires-profile",
+        "[profile.focus]\ncalendar-filter = \"Focus\"\n",
+    );
+    let err = load_args(
+        [
+            "busycrab",
+            "--config",
+            profiled.0.to_str().unwrap(),
+            "--profile",
+            "focus",
+        ],
+        env(&[]),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("line 2, profile focus)"),
+        "{}",
+        err
+    );
+    let err = load_args(["busycrab"], env(&[("BUSYCRAB_HTTP_TOKEN", "s3cret")])).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("http-token (from BUSYCRAB_HTTP_TOKEN): "),
+        "{}",
+        err
+    );
+
+    // Subcommands leave out flags that don't fit
+    let args = load_args(
+        [
+            "busycrab",
+            "--config",
+            other.0.to_str().unwrap(),
+            "backends",
+        ],
+        env(&[]),
+    )
+    .unwrap();
+    assert!(args.command.is_some());
+    assert_eq!(args.calendar_filter, None);
+}
+
+#[test]
+fn test_default_config_file() {
+    let dir = std::env::temp_dir().join(format!("busycrab-xdg-{}", std::process::id()));
+    let xdg = env(&[("XDG_CONFIG_HOME", dir.to_str().unwrap())]);
+    assert_eq!(default_path(&xdg), Some(dir.join("busycrab/config.toml")));
+
+    // A missing default file is fine, but a profile needs one
+    let args = load_args(["busycrab"], &xdg).unwrap();
+    assert_eq!(args.interval, ValueRange::fixed(60));
+    let err = load_args(["busycrab", "--profile", "meeting"], &xdg).unwrap_err();
+    assert!(err.to_string().contains("without a config file"), "{}", err);
+
+    std::fs::create_dir_all(dir.join("busycrab")).unwrap();
+    std::fs::write(dir.join("busycrab/config.toml"), CONFIG).unwrap();
+    let args = load_args(["busycrab", "--profile", "meeting"], &xdg).unwrap();
+    assert_eq!(args.idle_threshold, 120);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    if !cfg!(windows) {
+        let home = env(&[("HOME", "/home/crab"), ("XDG_CONFIG_HOME", "relative")]);
+        assert_eq!(
+            default_path(home),
+            Some(Path::new("/home/crab/.config/busycrab/config.toml").to_path_buf())
+        );
+    }
+    assert_eq!(env_name("idle-threshold"), "BUSYCRAB_IDLE_THRESHOLD");
+}
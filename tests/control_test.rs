@@ -0,0 +1,248 @@
+use busycrab::control::keys::is_toggle_key;
+use busycrab::control::{ControlCommand, ControlHandle};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn test_control_command_parsing() {
+    assert_eq!("pause".parse(), Ok(ControlCommand::Pause));
+    assert_eq!("Resume".parse(), Ok(ControlCommand::Resume));
+    assert_eq!("toggle".parse(), Ok(ControlCommand::TogglePause));
+    assert_eq!("STOP".parse(), Ok(ControlCommand::Stop));
+    assert_eq!(ControlCommand::TogglePause.to_string(), "toggle");
+    assert!("nap".parse::<ControlCommand>().is_err());
+}
+
+#[test]
+fn test_control_handle() {
+    let running = Arc::new(AtomicBool::new(true));
+    let paused = Arc::new(AtomicBool::new(false));
+    let handle = ControlHandle::new(running.clone(), paused.clone());
+
+    handle.pause();
+    assert!(handle.is_paused());
+    assert!(paused.load(Ordering::SeqCst));
+
+    // Clones share the state
+    let clone = handle.clone();
+    clone.apply(ControlCommand::TogglePause);
+    assert!(!handle.is_paused());
+    clone.apply(ControlCommand::TogglePause);
+    assert!(handle.is_paused());
+    handle.resume();
+    assert!(!clone.is_paused());
+
+    assert!(handle.is_running());
+    handle.stop();
+    assert!(!running.load(Ordering::SeqCst));
+    assert!(!clone.is_running());
+}
+
+#[test]
+fn test_toggle_keys() {
+    assert!(is_toggle_key(b'p'));
+    assert!(is_toggle_key(b'P'));
+    assert!(is_toggle_key(b' '));
+    assert!(!is_toggle_key(b'q'));
+    assert!(!is_toggle_key(b'\n'));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pause_signals() {
+    use busycrab::control::signals;
+
+    signals::install().unwrap();
+    assert_eq!(signals::take_pending(), None);
+
+    unsafe { libc::raise(libc::SIGUSR1) };
+    assert_eq!(signals::take_pending(), Some(ControlCommand::Pause));
+    assert_eq!(signals::take_pending(), None);
+
+    // Only the last signal counts
+    unsafe {
+        libc::raise(libc::SIGUSR1);
+        libc::raise(libc::SIGUSR2);
+    }
+    assert_eq!(signals::take_pending(), Some(ControlCommand::Resume));
+}
+
+/// Makes a socket path that no other test uses.
+#[cfg(unix)]
+fn socket_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("busycrab-{}-{}.sock", name, std::process::id()))
+}
+
+#[test]
+fn test_status_display() {
+    use busycrab::control::Status;
+
+    let mut status = Status {
+        pid: 4242,
+        uptime_secs: 3725,
+        state: "active".to_string(),
+        paused: false,
+        cycles: 12,
+        backend: "logind".to_string(),
+        interval_secs: 60,
+        next_activity_secs: Some(42),
+    };
+    assert_eq!(
+        status.to_string(),
+        "BusyCrab (pid 4242): active\n  Uptime: 1h2m5s\n  Activity cycles: 12\n  \
+         Backend: logind\n  Interval: 60 seconds\n  Next activity in: 42 seconds"
+    );
+
+    status.next_activity_secs = None;
+    assert!(status.to_string().ends_with("Interval: 60 seconds"));
+
+    // Status is reported before the first cycle too
+    let handle = ControlHandle::new(
+        Arc::new(AtomicBool::new(true)),
+        Arc::new(AtomicBool::new(true)),
+    );
+    let status = handle.status();
+    assert_eq!(status.pid, std::process::id());
+    assert_eq!(status.state, "paused");
+    assert_eq!(status.cycles, 0);
+    assert_eq!(status.next_activity_secs, None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_socket_commands() {
+    use busycrab::control::socket::{self, ControlServer};
+
+    let path = socket_path("commands");
+    let running = Arc::new(AtomicBool::new(true));
+    let handle = ControlHandle::new(running.clone(), Arc::new(AtomicBool::new(false)));
+    let server = ControlServer::start(&path, handle.clone()).unwrap();
+    assert_eq!(server.path(), path);
+
+    let status = socket::send(&path, "status").unwrap();
+    assert_eq!(status.state, "active");
+    assert!(!status.paused);
+
+    let status = socket::send(&path, "pause").unwrap();
+    assert!(status.paused);
+    assert_eq!(status.state, "paused");
+    assert!(handle.is_paused());
+
+    assert!(!socket::send(&path, "resume").unwrap().paused);
+    assert!(socket::send(&path, "toggle").unwrap().paused);
+
+    let err = socket::send(&path, "dance").unwrap_err();
+    assert!(
+        err.to_string().contains("unknown command 'dance'"),
+        "{}",
+        err
+    );
+
+    assert_eq!(socket::send(&path, "stop").unwrap().state, "stopping");
+    assert!(!running.load(Ordering::SeqCst));
+
+    // The socket goes away with the server
+    drop(server);
+    assert!(!path.exists());
+    let err = socket::send(&path, "status").unwrap_err();
+    assert!(
+        err.to_string().contains("no BusyCrab is running"),
+        "{}",
+        err
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_socket_protocol() {
+    use busycrab::control::socket::{ControlServer, Response};
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let path = socket_path("protocol");
+    let handle = ControlHandle::new(
+        Arc::new(AtomicBool::new(true)),
+        Arc::new(AtomicBool::new(false)),
+    );
+    let _server = ControlServer::start(&path, handle).unwrap();
+
+    // Several requests on one connection, one response line each
+    let mut stream = UnixStream::connect(&path).unwrap();
+    stream
+        .write_all(b"{\"command\":\"status\"}\n\nnot json\n{\"command\":\"PAUSE\"}\n")
+        .unwrap();
+    let mut lines = BufReader::new(stream).lines();
+    let mut response =
+        || -> Response { serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap() };
+
+    let status = response();
+    assert!(status.ok);
+    assert!(status.error.is_none());
+    let invalid = response();
+    assert!(!invalid.ok);
+    assert!(invalid.status.is_none());
+    assert!(invalid.error.unwrap().starts_with("invalid request"));
+    assert!(response().status.unwrap().paused);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_socket_silent_client() {
+    use busycrab::control::socket::{self, ControlServer};
+    use std::os::unix::net::UnixStream;
+    use std::time::{Duration, Instant};
+
+    let path = socket_path("silent");
+    let running = Arc::new(AtomicBool::new(true));
+    let handle = ControlHandle::new(running.clone(), Arc::new(AtomicBool::new(false)));
+    let _server = ControlServer::start(&path, handle).unwrap();
+
+    // A client that connects and sends nothing doesn't hold up the others
+    let _silent = UnixStream::connect(&path).unwrap();
+    let started = Instant::now();
+    assert_eq!(socket::send(&path, "stop").unwrap().state, "stopping");
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert!(!running.load(Ordering::SeqCst));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_socket_in_use() {
+    use busycrab::control::socket::ControlServer;
+    use std::os::unix::net::UnixListener;
+
+    let handle = ControlHandle::new(
+        Arc::new(AtomicBool::new(true)),
+        Arc::new(AtomicBool::new(false)),
+    );
+
+    let path = socket_path("in-use");
+    let _server = ControlServer::start(&path, handle.clone()).unwrap();
+    let err = ControlServer::start(&path, handle.clone()).err().unwrap();
+    assert!(err.to_string().contains("already listening"), "{}", err);
+
+    // A socket nobody listens on is left over from a crash and gets replaced
+    let stale = socket_path("stale");
+    drop(UnixListener::bind(&stale).unwrap());
+    assert!(stale.exists());
+    let _server = ControlServer::start(&stale, handle).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_socket_path_not_a_socket() {
+    use busycrab::control::socket::ControlServer;
+
+    let handle = ControlHandle::new(
+        Arc::new(AtomicBool::new(true)),
+        Arc::new(AtomicBool::new(false)),
+    );
+
+    // A regular file at the socket path is never removed
+    let path = socket_path("regular-file");
+    std::fs::write(&path, "notes").unwrap();
+    let err = ControlServer::start(&path, handle).err().unwrap();
+    assert!(err.to_string().contains("is not a socket"), "{}", err);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "notes");
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,56 @@
+use busycrab::{Backend, Error};
+use std::error::Error as _;
+use std::io;
+
+#[test]
+fn test_error_display() {
+    assert_eq!(
+        Error::backend(Backend::Logind, "lock refused").to_string(),
+        "logind backend: lock refused"
+    );
+    assert_eq!(
+        Error::unsupported("The x11 backend is only available on Linux").to_string(),
+        "The x11 backend is only available on Linux"
+    );
+    assert_eq!(
+        Error::input("no display").to_string(),
+        "input simulation failed: no display"
+    );
+    assert_eq!(
+        Error::idle("DISPLAY is not set").to_string(),
+        "idle detection failed: DISPLAY is not set"
+    );
+    assert_eq!(
+        Error::config("interval must be positive").to_string(),
+        "invalid configuration: interval must be positive"
+    );
+    assert_eq!(
+        Error::control("no BusyCrab running").to_string(),
+        "control failed: no BusyCrab running"
+    );
+}
+
+#[test]
+fn test_error_source() {
+    let os_error = io::Error::from_raw_os_error(5);
+    let err = Error::backend_source(Backend::Native, "Failed to set execution state", os_error);
+
+    assert_eq!(err.failed_backend(), Some(Backend::Native));
+    let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.raw_os_error(), Some(5));
+
+    assert!(Error::backend(Backend::X11, "gone").source().is_none());
+    assert!(Error::config("bad").failed_backend().is_none());
+}
+
+#[test]
+fn test_unsupported_backend() {
+    // Every OS has at least one backend that belongs to another OS
+    let foreign = [Backend::Native, Backend::Logind]
+        .into_iter()
+        .find(|backend| !Backend::chain().contains(backend))
+        .unwrap();
+
+    let err = foreign.probe().err().unwrap();
+    assert!(matches!(err, Error::Unsupported(_)));
+}
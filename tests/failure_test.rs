@@ -0,0 +1,38 @@
+use busycrab::failure::{FailureMode, FailurePolicy, DEFAULT_RETRY_DELAY, MAX_RETRY_DELAY};
+use std::time::Duration;
+
+#[test]
+fn test_retry_delay_doubles() {
+    let policy = FailurePolicy::Retry {
+        max_retries: 3,
+        initial_delay: Duration::from_millis(100),
+    };
+    assert_eq!(policy.retry_delay(0), Some(Duration::from_millis(100)));
+    assert_eq!(policy.retry_delay(1), Some(Duration::from_millis(200)));
+    assert_eq!(policy.retry_delay(2), Some(Duration::from_millis(400)));
+    assert_eq!(policy.retry_delay(3), None);
+}
+
+#[test]
+fn test_retry_delay_is_capped() {
+    let policy = FailurePolicy::retry(100);
+    assert_eq!(policy.retry_delay(0), Some(DEFAULT_RETRY_DELAY));
+    assert_eq!(policy.retry_delay(40), Some(MAX_RETRY_DELAY));
+    assert_eq!(policy.retry_delay(99), Some(MAX_RETRY_DELAY));
+}
+
+#[test]
+fn test_other_policies_never_retry() {
+    assert_eq!(FailurePolicy::FailFast.retry_delay(0), None);
+    assert_eq!(FailurePolicy::Warn.retry_delay(0), None);
+    assert_eq!(FailurePolicy::default(), FailurePolicy::FailFast);
+}
+
+#[test]
+fn test_failure_mode_policy() {
+    assert_eq!("warn".parse::<FailureMode>(), Ok(FailureMode::Warn));
+    assert!("sometimes".parse::<FailureMode>().is_err());
+
+    assert_eq!(FailureMode::Fail.policy(5), FailurePolicy::FailFast);
+    assert_eq!(FailureMode::Retry.policy(5), FailurePolicy::retry(5));
+}
@@ -0,0 +1,100 @@
+use busycrab::schedule::holiday::{HolidayDates, MonthDay};
+use busycrab::schedule::{Holiday, HolidayAction, Holidays};
+use chrono::NaiveDate;
+use std::path::Path;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn test_holiday_dates_parsing() {
+    assert_eq!(
+        "2024-10-04".parse(),
+        Ok(HolidayDates::Dates(date(2024, 10, 4), date(2024, 10, 4)))
+    );
+    assert_eq!(
+        "2024-08-05..2024-08-16".parse(),
+        Ok(HolidayDates::Dates(date(2024, 8, 5), date(2024, 8, 16)))
+    );
+    assert_eq!(
+        "12-25".parse(),
+        Ok(HolidayDates::Yearly(
+            MonthDay { month: 12, day: 25 },
+            MonthDay { month: 12, day: 25 }
+        ))
+    );
+    assert_eq!(
+        "12-24..=12-26".parse::<HolidayDates>().unwrap().to_string(),
+        "12-24..12-26"
+    );
+    assert_eq!(
+        "02-29".parse::<HolidayDates>().unwrap().to_string(),
+        "02-29"
+    );
+
+    assert!("2024-08-16..2024-08-05".parse::<HolidayDates>().is_err());
+    assert!("2024-12-24..12-26".parse::<HolidayDates>().is_err());
+    assert!("13-01".parse::<HolidayDates>().is_err());
+    assert!("02-30".parse::<HolidayDates>().is_err());
+    assert!("christmas".parse::<HolidayDates>().is_err());
+}
+
+#[test]
+fn test_holiday_contains() {
+    let vacation: Holiday = "2024-08-05..2024-08-16 Summer vacation".parse().unwrap();
+    assert_eq!(vacation.name.as_deref(), Some("Summer vacation"));
+    assert!(vacation.dates.contains(date(2024, 8, 5)));
+    assert!(vacation.dates.contains(date(2024, 8, 16)));
+    assert!(!vacation.dates.contains(date(2024, 8, 17)));
+    assert!(!vacation.dates.contains(date(2025, 8, 10)));
+
+    // Yearly entries repeat, and can span New Year
+    let new_year: Holiday = "12-31..01-01".parse().unwrap();
+    assert_eq!(new_year.name, None);
+    assert!(new_year.dates.contains(date(2024, 12, 31)));
+    assert!(new_year.dates.contains(date(2030, 1, 1)));
+    assert!(!new_year.dates.contains(date(2030, 1, 2)));
+    assert!(!new_year.dates.contains(date(2030, 12, 30)));
+}
+
+#[test]
+fn test_holidays_file() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/office.holidays");
+    let holidays = Holidays::from_file(&path).unwrap();
+
+    assert_eq!(holidays.entries().len(), 5);
+    assert_eq!(holidays.to_string(), "5 entries");
+    assert_eq!(
+        holidays.on(date(2025, 12, 25)).unwrap().name.as_deref(),
+        Some("Christmas")
+    );
+    // The first matching entry wins
+    assert_eq!(
+        holidays.on(date(2025, 1, 1)).unwrap().name.as_deref(),
+        Some("New Year's Day")
+    );
+    assert!(holidays.contains(date(2024, 10, 4)));
+    assert!(!holidays.contains(date(2025, 10, 4)));
+    assert!(!holidays.contains(date(2024, 7, 1)));
+}
+
+#[test]
+fn test_holidays_file_errors() {
+    let err = Holidays::from_file("/nonexistent/busycrab.holidays").unwrap_err();
+    assert!(err.to_string().contains("can't read holidays file"));
+
+    let err = "12-25\n\n2024-13-01 Nope\n"
+        .parse::<Holidays>()
+        .unwrap_err();
+    assert!(err.starts_with("line 3:"), "{}", err);
+}
+
+#[test]
+fn test_holiday_action_parsing() {
+    assert_eq!("pause".parse(), Ok(HolidayAction::Pause));
+    assert_eq!("EXIT".parse(), Ok(HolidayAction::Exit));
+    assert_eq!(HolidayAction::default(), HolidayAction::Pause);
+    assert_eq!(HolidayAction::Exit.to_string(), "exit");
+    assert!("ignore".parse::<HolidayAction>().is_err());
+}
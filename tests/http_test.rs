@@ -0,0 +1,250 @@
+use busycrab::control::http::HttpServer;
+use busycrab::control::{ControlHandle, Settings, Status};
+use busycrab::{Error, ValueRange};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Starts the API on a free loopback port.
+fn start(token: Option<&str>) -> (HttpServer, ControlHandle, String) {
+    let handle = ControlHandle::new(
+        Arc::new(AtomicBool::new(true)),
+        Arc::new(AtomicBool::new(false)),
+    );
+    let server = HttpServer::start(
+        "127.0.0.1:0".parse().unwrap(),
+        token.map(str::to_string),
+        handle.clone(),
+    )
+    .unwrap();
+    let base = format!("http://{}", server.addr());
+    (server, handle, base)
+}
+
+/// Sends a request, returning the response for error statuses too.
+fn send(method: &str, url: &str, token: Option<&str>, body: Option<&str>) -> ureq::Response {
+    let mut request = ureq::request(method, url);
+    if let Some(token) = token {
+        request = request.set("Authorization", &format!("Bearer {}", token));
+    }
+    let result = match body {
+        Some(body) => request.send_string(body),
+        None => request.call(),
+    };
+    match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(err) => panic!("{} {} failed: {}", method, url, err),
+    }
+}
+
+/// Gets the error message of a failed request.
+fn error_message(response: ureq::Response) -> String {
+    let body: serde_json::Value = response.into_json().unwrap();
+    body["error"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_status_and_commands() {
+    let (_server, handle, base) = start(None);
+
+    let response = send("GET", &format!("{}/status", base), None, None);
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.content_type(), "application/json");
+    let status: Status = response.into_json().unwrap();
+    assert_eq!(status.pid, std::process::id());
+    assert_eq!(status.state, "active");
+
+    let status: Status = send("POST", &format!("{}/pause", base), None, None)
+        .into_json()
+        .unwrap();
+    assert!(status.paused);
+    assert!(handle.is_paused());
+
+    let status: Status = send("POST", &format!("{}/resume", base), None, None)
+        .into_json()
+        .unwrap();
+    assert!(!status.paused);
+
+    // Query strings don't matter
+    let status: Status = send("POST", &format!("{}/stop?now=1", base), None, None)
+        .into_json()
+        .unwrap();
+    assert_eq!(status.state, "stopping");
+    assert!(!handle.is_running());
+}
+
+#[test]
+fn test_config() {
+    let (_server, handle, base) = start(None);
+    let url = format!("{}/config", base);
+
+    let settings: Settings = send("GET", &url, None, None).into_json().unwrap();
+    assert_eq!(settings, Settings::default());
+
+    let response = send(
+        "PUT",
+        &url,
+        None,
+        Some(r#"{"interval":"45..90","motion":"Clock"}"#),
+    );
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.into_json().unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({"interval": "45..90", "wiggle": "3", "motion": "clock"})
+    );
+
+    // Later changes add to the ones not applied yet
+    let settings: Settings = send("PUT", &url, None, Some(r#"{"wiggle":5}"#))
+        .into_json()
+        .unwrap();
+    assert_eq!(settings.interval, ValueRange::new(45, 90).unwrap());
+    assert_eq!(settings.wiggle, ValueRange::fixed(5));
+    assert_eq!(settings.motion, "clock");
+    assert_eq!(handle.settings(), settings);
+
+    // Invalid changes are rejected as a whole
+    for (body, message) in [
+        (r#"{"interval":0}"#, "interval must be at least 1 second"),
+        (r#"{"wiggle":3,"motion":"disco"}"#, "unknown motion 'disco'"),
+        (r#"{"interval":"90..45"}"#, "starts above its end"),
+        (r#"{"speed":3}"#, "unknown field `speed`"),
+        ("interval=30", "invalid settings"),
+    ] {
+        let response = send("PUT", &url, None, Some(body));
+        assert_eq!(response.status(), 400, "{}", body);
+        let error = error_message(response);
+        assert!(error.contains(message), "{}: {}", body, error);
+    }
+    assert_eq!(handle.settings(), settings);
+}
+
+#[test]
+fn test_metrics() {
+    let (_server, handle, base) = start(None);
+    let url = format!("{}/metrics", base);
+    handle.metrics().record_frame();
+
+    let response = send("GET", &url, None, None);
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.content_type(), "text/plain");
+    let text = response.into_string().unwrap();
+    assert!(text.contains("\nbusycrab_animation_frames_total 1\n"), "{}", text);
+    assert!(text.contains("\nbusycrab_paused 0\n"), "{}", text);
+
+    handle.pause();
+    let text = send("GET", &url, None, None).into_string().unwrap();
+    assert!(text.contains("\nbusycrab_paused 1\n"), "{}", text);
+
+    let response = send("POST", &url, None, None);
+    assert_eq!(response.status(), 405);
+    assert_eq!(response.header("Allow"), Some("GET"));
+}
+
+#[test]
+fn test_unknown_requests() {
+    let (_server, handle, base) = start(None);
+
+    let response = send("GET", &format!("{}/nap", base), None, None);
+    assert_eq!(response.status(), 404);
+    assert_eq!(error_message(response), "no such endpoint: /nap");
+
+    let response = send("GET", &format!("{}/stop", base), None, None);
+    assert_eq!(response.status(), 405);
+    assert_eq!(response.header("Allow"), Some("POST"));
+    assert!(handle.is_running());
+
+    let response = send("DELETE", &format!("{}/config", base), None, None);
+    assert_eq!(response.status(), 405);
+    assert_eq!(response.header("Allow"), Some("GET, PUT"));
+
+    let response = send("POST", &format!("{}/status", base), None, None);
+    assert_eq!(response.header("Allow"), Some("GET"));
+}
+
+#[test]
+fn test_bearer_token() {
+    let (_server, handle, base) = start(Some("s3cret"));
+    let url = format!("{}/pause", base);
+
+    let response = send("POST", &url, None, None);
+    assert_eq!(response.status(), 401);
+    assert_eq!(response.header("WWW-Authenticate"), Some("Bearer"));
+    assert_eq!(send("POST", &url, Some("s3cre"), None).status(), 401);
+    assert_eq!(send("POST", &url, Some("s3cret!"), None).status(), 401);
+    assert!(!handle.is_paused());
+
+    assert_eq!(send("POST", &url, Some("s3cret"), None).status(), 200);
+    assert!(handle.is_paused());
+
+    // The scheme is case-insensitive
+    let response = ureq::get(&format!("{}/status", base))
+        .set("Authorization", "bearer s3cret")
+        .call()
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[test]
+fn test_loopback_only_without_token() {
+    let handle = ControlHandle::new(
+        Arc::new(AtomicBool::new(true)),
+        Arc::new(AtomicBool::new(false)),
+    );
+
+    let err = HttpServer::start("0.0.0.0:0".parse().unwrap(), None, handle.clone())
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Config(_)));
+    assert!(err.to_string().contains("needs a token"), "{}", err);
+
+    let server = HttpServer::start("[::1]:0".parse().unwrap(), None, handle.clone());
+    // Hosts without IPv6 can't listen there, but it isn't refused for being remote
+    if let Err(err) = server {
+        assert!(matches!(err, Error::Control { .. }), "{}", err);
+    }
+
+    let server =
+        HttpServer::start("0.0.0.0:0".parse().unwrap(), Some("token".into()), handle).unwrap();
+    assert!(server.addr().port() > 0);
+}
+
+#[test]
+fn test_browser_requests_rejected_without_token() {
+    let (_server, handle, base) = start(None);
+    let url = format!("{}/stop", base);
+    let port = base.rsplit(':').next().unwrap();
+
+    // A page that rebinds its own name to 127.0.0.1 sends that name as the Host
+    let response = ureq::post(&url)
+        .set("Host", &format!("evil.example:{}", port))
+        .call()
+        .unwrap_err();
+    let ureq::Error::Status(403, _) = response else {
+        panic!("foreign Host wasn't rejected: {:?}", response);
+    };
+    assert!(handle.is_running());
+
+    // A cross-origin fetch carries an Origin header
+    let response = ureq::post(&url)
+        .set("Origin", "https://evil.example")
+        .call()
+        .unwrap_err();
+    let ureq::Error::Status(403, response) = response else {
+        panic!("Origin header wasn't rejected: {:?}", response);
+    };
+    assert!(error_message(response).contains("aren't allowed"));
+    assert!(handle.is_running());
+
+    // Loopback names with and without a port are fine
+    for host in [
+        "localhost",
+        &format!("localhost:{}", port),
+        &format!("[::1]:{}", port),
+    ] {
+        let response = ureq::get(&format!("{}/status", base))
+            .set("Host", host)
+            .call()
+            .unwrap();
+        assert_eq!(response.status(), 200, "{}", host);
+    }
+}
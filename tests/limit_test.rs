@@ -0,0 +1,47 @@
+use busycrab::limit::{RunDuration, StopTime};
+use chrono::{NaiveDate, NaiveTime};
+use std::time::Duration;
+
+#[test]
+fn test_run_duration_parsing() {
+    assert_eq!("2h30m".parse(), Ok(RunDuration(Duration::from_secs(9000))));
+    assert_eq!("90m".parse(), Ok(RunDuration(Duration::from_secs(5400))));
+    assert_eq!("45s".parse(), Ok(RunDuration(Duration::from_secs(45))));
+    assert_eq!("1H 5S".parse(), Ok(RunDuration(Duration::from_secs(3605))));
+
+    assert!("90".parse::<RunDuration>().is_err());
+    assert!("2h30".parse::<RunDuration>().is_err());
+    assert!("0m".parse::<RunDuration>().is_err());
+    assert!("2d".parse::<RunDuration>().is_err());
+    assert!("h".parse::<RunDuration>().is_err());
+}
+
+#[test]
+fn test_run_duration_display() {
+    assert_eq!(RunDuration(Duration::from_secs(9000)).to_string(), "2h30m");
+    assert_eq!(RunDuration(Duration::from_secs(3605)).to_string(), "1h5s");
+    assert_eq!(RunDuration(Duration::from_secs(45)).to_string(), "45s");
+    assert_eq!(RunDuration(Duration::ZERO).to_string(), "0s");
+}
+
+#[test]
+fn test_stop_time() {
+    let stop: StopTime = "17:45".parse().unwrap();
+    assert_eq!(stop, StopTime(NaiveTime::from_hms_opt(17, 45, 0).unwrap()));
+    assert_eq!(stop.to_string(), "17:45");
+    assert!("5:45pm".parse::<StopTime>().is_err());
+    assert!("25:00".parse::<StopTime>().is_err());
+
+    let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    let morning = day.and_hms_opt(9, 15, 0).unwrap();
+    assert_eq!(
+        stop.duration_from(morning),
+        Duration::from_secs(8 * 3600 + 30 * 60)
+    );
+
+    // Already past today: stop tomorrow
+    let evening = day.and_hms_opt(18, 45, 0).unwrap();
+    assert_eq!(stop.duration_from(evening), Duration::from_secs(23 * 3600));
+    let exactly = day.and_hms_opt(17, 45, 0).unwrap();
+    assert_eq!(stop.duration_from(exactly), Duration::from_secs(24 * 3600));
+}
@@ -0,0 +1,142 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use busycrab::control::dbus::{Bus, DbusService, OBJECT_PATH, SERVICE_NAME};
+use busycrab::control::{ControlHandle, CycleOutcome};
+use busycrab::Error;
+use common::TestBus;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use zbus::blocking::fdo::PropertiesProxy;
+use zbus::blocking::{Connection, Proxy};
+use zbus::names::InterfaceName;
+use zbus::proxy::CacheProperties;
+
+/// Creates a handle for a BusyCrab that's running and not paused.
+fn running_handle() -> ControlHandle {
+    ControlHandle::new(
+        Arc::new(AtomicBool::new(true)),
+        Arc::new(AtomicBool::new(false)),
+    )
+}
+
+/// Connects a client to the bus, with a proxy for the BusyCrab object.
+fn connect(bus: &TestBus) -> (Connection, Proxy<'static>) {
+    let client = zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .build()
+        .unwrap();
+    // Read properties from the service every time instead of trusting signals
+    let proxy = zbus::blocking::proxy::Builder::new(&client)
+        .destination(SERVICE_NAME)
+        .unwrap()
+        .path(OBJECT_PATH)
+        .unwrap()
+        .interface(SERVICE_NAME)
+        .unwrap()
+        .cache_properties(CacheProperties::No)
+        .build()
+        .unwrap();
+    (client, proxy)
+}
+
+#[test]
+fn test_methods_and_properties() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let handle = running_handle();
+    let _service = DbusService::start(&Bus::Address(bus.address.clone()), handle.clone()).unwrap();
+    let (_client, proxy) = connect(&bus);
+
+    assert!(proxy.get_property::<bool>("Active").unwrap());
+    assert_eq!(proxy.get_property::<u64>("CycleCount").unwrap(), 0);
+    assert_eq!(proxy.get_property::<u64>("Interval").unwrap(), 0);
+
+    proxy.call_method("Pause", &()).unwrap();
+    assert!(handle.is_paused());
+    assert!(!proxy.get_property::<bool>("Active").unwrap());
+
+    proxy.call_method("Resume", &()).unwrap();
+    assert!(!handle.is_paused());
+    assert!(proxy.get_property::<bool>("Active").unwrap());
+
+    proxy.call_method("Stop", &()).unwrap();
+    assert!(!handle.is_running());
+    assert!(!proxy.get_property::<bool>("Active").unwrap());
+
+    // Properties can't be set from outside
+    assert!(proxy.set_property("CycleCount", 7u64).is_err());
+}
+
+#[test]
+fn test_cycle_signals() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let handle = running_handle();
+    let service = DbusService::start(&Bus::Address(bus.address.clone()), handle.clone()).unwrap();
+    let (client, proxy) = connect(&bus);
+    let mut activities = proxy.receive_signal("ActivityPerformed").unwrap();
+    let properties = PropertiesProxy::builder(&client)
+        .destination(SERVICE_NAME)
+        .unwrap()
+        .path(OBJECT_PATH)
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut changes = properties.receive_properties_changed().unwrap();
+
+    service.announce_cycle(CycleOutcome::Activity, 1);
+    service.announce_cycle(CycleOutcome::UserActive, 1);
+    service.announce_cycle(CycleOutcome::Activity, 2);
+    handle.pause();
+    service.announce_cycle(CycleOutcome::Paused, 2);
+
+    // Only cycles that simulated activity are announced
+    let first: u64 = activities.next().unwrap().body().deserialize().unwrap();
+    let second: u64 = activities.next().unwrap().body().deserialize().unwrap();
+    assert_eq!((first, second), (1, 2));
+
+    // The pause is the first property that changed
+    let change = changes.next().unwrap();
+    let args = change.args().unwrap();
+    assert_eq!(
+        args.interface_name,
+        InterfaceName::try_from(SERVICE_NAME).unwrap()
+    );
+    let active = args.changed_properties.get("Active").unwrap();
+    assert!(!bool::try_from(active).unwrap());
+    assert_eq!(args.changed_properties.len(), 1);
+
+    // Methods announce their changes right away
+    proxy.call_method("Resume", &()).unwrap();
+    let change = changes.next().unwrap();
+    let active = change.args().unwrap().changed_properties["Active"]
+        .try_clone()
+        .unwrap();
+    assert!(bool::try_from(active).unwrap());
+}
+
+#[test]
+fn test_one_service_per_bus() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let address = Bus::Address(bus.address.clone());
+    let service = DbusService::start(&address, running_handle()).unwrap();
+
+    let err = DbusService::start(&address, running_handle())
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Control { .. }));
+    assert!(err.to_string().contains(SERVICE_NAME), "{}", err);
+
+    // The name is free again once the service is dropped
+    drop(service);
+    assert!(DbusService::start(&address, running_handle()).is_ok());
+}
@@ -0,0 +1,286 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use busycrab::idle::{
+    IdleDetector, IdleSource, InputIdleDetector, LogindIdleDetector, X11IdleDetector,
+};
+use busycrab::platform::{Platform, PlatformTrait};
+use busycrab::Error;
+use common::TestBus;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zbus::zvariant::OwnedFd;
+
+/// Creates an empty directory to stand in for `/dev/input`.
+fn fake_input_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("busycrab-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir(&dir).unwrap();
+    dir
+}
+
+/// Encodes an evdev relative motion event that happened `ago` ago.
+fn input_event(ago: Duration) -> Vec<u8> {
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap() - ago;
+    let mut event = Vec::new();
+    event.extend((time.as_secs() as libc::c_ulong).to_ne_bytes());
+    event.extend((time.subsec_micros() as libc::c_ulong).to_ne_bytes());
+    event.extend(2u16.to_ne_bytes()); // EV_REL
+    event.extend(0u16.to_ne_bytes()); // REL_X
+    event.extend(1i32.to_ne_bytes());
+    event
+}
+
+/// Creates a device node stand-in with events that happened the given times ago.
+fn fake_event_node(dir: &Path, name: &str, events: &[Duration]) {
+    let mut file = File::create(dir.join(name)).unwrap();
+    for &ago in events {
+        file.write_all(&input_event(ago)).unwrap();
+    }
+}
+
+/// Reads `CLOCK_MONOTONIC` in microseconds, as logind reports it.
+fn monotonic_micros() -> u64 {
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+    now.tv_sec as u64 * 1_000_000 + now.tv_nsec as u64 / 1_000
+}
+
+/// A stand-in for the `org.freedesktop.login1.Manager` idle properties.
+struct StubLogind {
+    idle_hint: bool,
+    idle_since: u64,
+}
+
+#[zbus::interface(name = "org.freedesktop.login1.Manager")]
+impl StubLogind {
+    #[zbus(property)]
+    fn idle_hint(&self) -> bool {
+        self.idle_hint
+    }
+
+    #[zbus(property)]
+    fn idle_since_hint_monotonic(&self) -> u64 {
+        self.idle_since
+    }
+}
+
+/// A stand-in for logind that, like the real one, keeps the idle hint off while an
+/// inhibitor lock is held.
+struct InhibitingLogind {
+    locks: Arc<Mutex<Vec<UnixStream>>>,
+}
+
+#[zbus::interface(name = "org.freedesktop.login1.Manager")]
+impl InhibitingLogind {
+    fn inhibit(&self, _what: String, _who: String, _why: String, _mode: String) -> OwnedFd {
+        let (ours, theirs) = UnixStream::pair().unwrap();
+        self.locks.lock().unwrap().push(ours);
+        std::os::fd::OwnedFd::from(theirs).into()
+    }
+
+    #[zbus(property)]
+    fn idle_hint(&self) -> bool {
+        self.locks.lock().unwrap().is_empty()
+    }
+
+    #[zbus(property)]
+    fn idle_since_hint_monotonic(&self) -> u64 {
+        1
+    }
+}
+
+/// Publishes the stub logind manager on the given bus.
+fn serve_logind(bus: &TestBus, stub: StubLogind) -> zbus::blocking::Connection {
+    zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .name("org.freedesktop.login1")
+        .unwrap()
+        .serve_at("/org/freedesktop/login1", stub)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_input_idle_uses_newest_event() {
+    let dir = fake_input_dir("idle-input");
+    fake_event_node(&dir, "event0", &[Duration::from_secs(300)]);
+    fake_event_node(
+        &dir,
+        "event3",
+        &[Duration::from_secs(60), Duration::from_secs(40)],
+    );
+    // Only event* nodes count
+    fake_event_node(&dir, "mouse0", &[Duration::ZERO]);
+
+    let mut detector = InputIdleDetector::with_dir(&dir);
+    let idle = detector.idle_time().unwrap();
+    assert!(
+        idle >= Duration::from_secs(40) && idle < Duration::from_secs(45),
+        "idle for {:?}",
+        idle
+    );
+
+    // Events queued later are picked up on the next read
+    let mut node = OpenOptions::new()
+        .append(true)
+        .open(dir.join("event0"))
+        .unwrap();
+    node.write_all(&input_event(Duration::from_secs(5)))
+        .unwrap();
+    let idle = detector.idle_time().unwrap();
+    assert!(
+        idle >= Duration::from_secs(5) && idle < Duration::from_secs(10),
+        "idle for {:?}",
+        idle
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_input_idle_counts_from_opening() {
+    let dir = fake_input_dir("idle-quiet");
+    fake_event_node(&dir, "event0", &[]);
+
+    // No events yet, so the user has been idle since the devices were opened
+    let mut detector = InputIdleDetector::with_dir(&dir);
+    assert!(detector.idle_time().unwrap() < Duration::from_secs(1));
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(detector.idle_time().unwrap() >= Duration::from_millis(300));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_input_idle_without_devices() {
+    let dir = fake_input_dir("idle-empty");
+    let err = InputIdleDetector::with_dir(&dir).idle_time().unwrap_err();
+    assert!(matches!(err, Error::Idle { .. }));
+    assert!(err.to_string().contains("No input devices"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let err = InputIdleDetector::with_dir(&dir).idle_time().unwrap_err();
+    assert!(matches!(err, Error::Idle { .. }));
+}
+
+#[test]
+fn test_logind_idle_hint() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let since = monotonic_micros() - 90_000_000;
+    let _service = serve_logind(
+        &bus,
+        StubLogind {
+            idle_hint: true,
+            idle_since: since,
+        },
+    );
+
+    let mut detector = LogindIdleDetector::with_address(&bus.address);
+    let idle = detector.idle_time().unwrap();
+    assert!(
+        idle >= Duration::from_secs(90) && idle < Duration::from_secs(95),
+        "idle for {:?}",
+        idle
+    );
+}
+
+#[test]
+fn test_logind_not_idle() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    // The timestamp is ignored until the desktop sets the hint
+    let _service = serve_logind(
+        &bus,
+        StubLogind {
+            idle_hint: false,
+            idle_since: 1,
+        },
+    );
+
+    let mut detector = LogindIdleDetector::with_address(&bus.address);
+    assert_eq!(detector.idle_time().unwrap(), Duration::ZERO);
+}
+
+#[test]
+fn test_logind_missing() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+
+    let err = LogindIdleDetector::with_address(&bus.address)
+        .idle_time()
+        .unwrap_err();
+    assert!(matches!(err, Error::Idle { .. }));
+}
+
+#[test]
+fn test_auto_skips_logind_while_inhibited() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let stub = InhibitingLogind {
+        locks: Arc::new(Mutex::new(Vec::new())),
+    };
+    let _service = zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .name("org.freedesktop.login1")
+        .unwrap()
+        .serve_at("/org/freedesktop/login1", stub)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut logind = LogindIdleDetector::with_address(&bus.address);
+    assert!(logind.idle_time().unwrap() > Duration::ZERO);
+
+    // With BusyCrab's own lock held, logind reads as active however long the user
+    // has been away
+    let platform = Platform::with_address(&bus.address);
+    let _guard = platform.acquire().unwrap();
+    assert_eq!(logind.idle_time().unwrap(), Duration::ZERO);
+
+    // So the chain never picks it
+    assert!(!IdleSource::CHAIN.contains(&IdleSource::Logind));
+    if let Ok(detector) = IdleSource::Auto.create() {
+        assert_ne!(detector.name(), "logind");
+    }
+}
+
+#[test]
+fn test_x11_idle_unreachable_display() {
+    let Err(err) = X11IdleDetector::with_display(":4999") else {
+        panic!("connected to a display that shouldn't exist");
+    };
+    assert!(matches!(err, Error::Idle { .. }));
+}
+
+#[test]
+fn test_idle_source_names() {
+    for source in [
+        IdleSource::Auto,
+        IdleSource::X11,
+        IdleSource::Logind,
+        IdleSource::Input,
+    ] {
+        assert_eq!(source.to_string().parse::<IdleSource>(), Ok(source));
+    }
+    assert_eq!("LOGIND".parse::<IdleSource>(), Ok(IdleSource::Logind));
+    assert!("webcam".parse::<IdleSource>().is_err());
+}
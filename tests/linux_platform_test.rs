@@ -0,0 +1,156 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use busycrab::platform::{Backend, InhibitStatus, Platform, PlatformTrait};
+use std::error::Error as _;
+use common::TestBus;
+use std::io::Read;
+use std::os::unix::net::UnixStream;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Arguments received by the stub's `Inhibit` method.
+type InhibitCall = (String, String, String, String);
+
+/// A stand-in for `org.freedesktop.login1.Manager`.
+/// Hands out one end of a socket pair as the lock and keeps the other end,
+/// so tests can tell when the client closes its descriptor.
+struct StubLogind {
+    calls: Arc<Mutex<Vec<InhibitCall>>>,
+    peers: Arc<Mutex<Vec<UnixStream>>>,
+}
+
+#[zbus::interface(name = "org.freedesktop.login1.Manager")]
+impl StubLogind {
+    fn inhibit(&self, what: String, who: String, why: String, mode: String) -> zbus::zvariant::OwnedFd {
+        self.calls.lock().unwrap().push((what, who, why, mode));
+        let (ours, theirs) = UnixStream::pair().unwrap();
+        self.peers.lock().unwrap().push(ours);
+        std::os::fd::OwnedFd::from(theirs).into()
+    }
+}
+
+/// A running stub service and the state it records.
+struct StubService {
+    _connection: zbus::blocking::Connection,
+    calls: Arc<Mutex<Vec<InhibitCall>>>,
+    peers: Arc<Mutex<Vec<UnixStream>>>,
+}
+
+/// Publishes the stub logind service on the given bus.
+fn serve_stub_logind(bus: &TestBus) -> StubService {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let peers = Arc::new(Mutex::new(Vec::new()));
+    let stub = StubLogind {
+        calls: calls.clone(),
+        peers: peers.clone(),
+    };
+    let connection = zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .name("org.freedesktop.login1")
+        .unwrap()
+        .serve_at("/org/freedesktop/login1", stub)
+        .unwrap()
+        .build()
+        .unwrap();
+    StubService {
+        _connection: connection,
+        calls,
+        peers,
+    }
+}
+
+#[test]
+fn test_logind_inhibit_taken_once() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let service = serve_stub_logind(&bus);
+
+    let platform = Platform::with_address(&bus.address);
+    assert_eq!(platform.status(), InhibitStatus::Released);
+
+    assert!(platform.prevent_sleep().is_ok());
+    assert!(platform.prevent_sleep().is_ok());
+    assert_eq!(platform.status(), InhibitStatus::Held);
+
+    // The lock is held across cycles, so logind is only asked once
+    let calls = service.calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(
+        calls[0],
+        (
+            "idle:sleep".to_string(),
+            "BusyCrab".to_string(),
+            "Keeping the system awake".to_string(),
+            "block".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_logind_inhibit_released_on_drop() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let service = serve_stub_logind(&bus);
+
+    let platform = Platform::with_address(&bus.address);
+    platform.prevent_sleep().unwrap();
+
+    let mut peer = service.peers.lock().unwrap().pop().unwrap();
+    peer.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+    drop(platform);
+
+    // Reading zero bytes means every copy of the lock descriptor was closed
+    let mut buf = [0u8; 1];
+    assert_eq!(peer.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn test_logind_release_and_reacquire() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let service = serve_stub_logind(&bus);
+
+    let platform = Platform::with_address(&bus.address);
+    let guard = platform.acquire().unwrap();
+    assert_eq!(platform.status(), InhibitStatus::Held);
+
+    let mut peer = service.peers.lock().unwrap().pop().unwrap();
+    peer.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    guard.release().unwrap();
+    assert_eq!(platform.status(), InhibitStatus::Released);
+
+    let mut buf = [0u8; 1];
+    assert_eq!(peer.read(&mut buf).unwrap(), 0);
+
+    // Refreshing after a release takes a fresh lock
+    platform.refresh().unwrap();
+    assert_eq!(platform.status(), InhibitStatus::Held);
+    assert_eq!(service.calls.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn test_logind_unavailable() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+
+    // Nobody owns org.freedesktop.login1 on this bus
+    let platform = Platform::with_address(&bus.address);
+    let err = platform.prevent_sleep().unwrap_err();
+    assert_eq!(platform.status(), InhibitStatus::Released);
+
+    // The D-Bus error is kept as the source
+    assert_eq!(err.failed_backend(), Some(Backend::Logind));
+    let source = err.source().unwrap().to_string();
+    assert!(source.contains("org.freedesktop.DBus.Error.ServiceUnknown"), "{}", source);
+}
@@ -0,0 +1,184 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use busycrab::platform::linux::{detect_session_services, SessionPlatform, SessionService};
+use busycrab::{InhibitStatus, PlatformTrait};
+use common::TestBus;
+use std::sync::{Arc, Mutex};
+
+/// Calls received by a stub service, as `(method, cookie)` pairs.
+type CallLog = Arc<Mutex<Vec<(&'static str, u32)>>>;
+
+/// A stand-in for `org.freedesktop.ScreenSaver`.
+struct StubScreenSaver {
+    calls: CallLog,
+}
+
+#[zbus::interface(name = "org.freedesktop.ScreenSaver")]
+impl StubScreenSaver {
+    fn inhibit(&self, _application_name: String, _reason: String) -> u32 {
+        self.calls.lock().unwrap().push(("Inhibit", 42));
+        42
+    }
+
+    fn un_inhibit(&self, cookie: u32) {
+        self.calls.lock().unwrap().push(("UnInhibit", cookie));
+    }
+}
+
+/// A stand-in for `org.gnome.SessionManager`.
+struct StubGnomeSession {
+    calls: CallLog,
+}
+
+#[zbus::interface(name = "org.gnome.SessionManager")]
+impl StubGnomeSession {
+    fn inhibit(&self, _app_id: String, _toplevel_xid: u32, _reason: String, flags: u32) -> u32 {
+        self.calls.lock().unwrap().push(("Inhibit", flags));
+        7
+    }
+
+    fn uninhibit(&self, cookie: u32) {
+        self.calls.lock().unwrap().push(("Uninhibit", cookie));
+    }
+}
+
+/// Publishes the stub ScreenSaver service on the given bus.
+fn serve_screensaver(bus: &TestBus, calls: CallLog) -> zbus::blocking::Connection {
+    zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .name("org.freedesktop.ScreenSaver")
+        .unwrap()
+        .serve_at("/org/freedesktop/ScreenSaver", StubScreenSaver { calls })
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+/// Publishes the stub GNOME session manager on the given bus.
+fn serve_gnome_session(bus: &TestBus, calls: CallLog) -> zbus::blocking::Connection {
+    zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .name("org.gnome.SessionManager")
+        .unwrap()
+        .serve_at("/org/gnome/SessionManager", StubGnomeSession { calls })
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_detect_session_services() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let client = zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert!(detect_session_services(&client).is_empty());
+    assert!(SessionPlatform::detect_at(&bus.address).is_none());
+
+    let _screensaver = serve_screensaver(&bus, CallLog::default());
+    assert_eq!(
+        detect_session_services(&client),
+        vec![SessionService::FreedesktopScreenSaver]
+    );
+
+    // GNOME is preferred when both are present
+    let _gnome = serve_gnome_session(&bus, CallLog::default());
+    assert_eq!(
+        detect_session_services(&client),
+        vec![
+            SessionService::GnomeSessionManager,
+            SessionService::FreedesktopScreenSaver
+        ]
+    );
+    let platform = SessionPlatform::detect_at(&bus.address).unwrap();
+    assert_eq!(platform.service(), SessionService::GnomeSessionManager);
+}
+
+#[test]
+fn test_screensaver_cookie_lifecycle() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let calls = CallLog::default();
+    let _service = serve_screensaver(&bus, calls.clone());
+
+    let platform = SessionPlatform::with_address(SessionService::FreedesktopScreenSaver, &bus.address);
+    assert_eq!(platform.cookie(), None);
+
+    platform.prevent_sleep().unwrap();
+    platform.prevent_sleep().unwrap();
+    assert_eq!(platform.cookie(), Some(42));
+    assert_eq!(*calls.lock().unwrap(), vec![("Inhibit", 42)]);
+
+    drop(platform);
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![("Inhibit", 42), ("UnInhibit", 42)]
+    );
+}
+
+#[test]
+fn test_gnome_session_cookie_lifecycle() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let calls = CallLog::default();
+    let _service = serve_gnome_session(&bus, calls.clone());
+
+    let platform = SessionPlatform::with_address(SessionService::GnomeSessionManager, &bus.address);
+    platform.prevent_sleep().unwrap();
+    assert_eq!(platform.cookie(), Some(7));
+
+    drop(platform);
+    // Inhibit asks to block suspend (4) and idle (8); Uninhibit returns the cookie
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![("Inhibit", 12), ("Uninhibit", 7)]
+    );
+}
+
+#[test]
+fn test_session_explicit_release() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let calls = CallLog::default();
+    let _service = serve_screensaver(&bus, calls.clone());
+
+    let platform = SessionPlatform::with_address(SessionService::FreedesktopScreenSaver, &bus.address);
+    platform.acquire().unwrap().keep();
+    assert_eq!(platform.status(), InhibitStatus::Held);
+
+    platform.release().unwrap();
+    platform.release().unwrap();
+    assert_eq!(platform.status(), InhibitStatus::Released);
+
+    // Dropping after an explicit release doesn't uninhibit twice
+    drop(platform);
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![("Inhibit", 42), ("UnInhibit", 42)]
+    );
+}
+
+#[test]
+fn test_session_service_missing() {
+    let Some(bus) = TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+
+    let platform = SessionPlatform::with_address(SessionService::FreedesktopScreenSaver, &bus.address);
+    assert!(platform.prevent_sleep().is_err());
+    assert_eq!(platform.cookie(), None);
+}
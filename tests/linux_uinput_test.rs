@@ -0,0 +1,101 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use busycrab::input::uinput::UinputMouseController;
+use busycrab::{Error, MouseController};
+use std::fs::File;
+use std::io::Read;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const EV_SYN: u16 = 0x00;
+const EV_REL: u16 = 0x02;
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+
+/// Creates an empty file that isn't a uinput device.
+fn fake_device(name: &str, mode: u32) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("busycrab-{}-{}", name, std::process::id()));
+    File::create(&path).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    path
+}
+
+/// Reads the next `(type, code, value)` event from an evdev node.
+fn read_event(node: &mut File) -> (u16, u16, i32) {
+    let mut event: libc::input_event = unsafe { std::mem::zeroed() };
+    let buf = unsafe {
+        std::slice::from_raw_parts_mut(
+            &mut event as *mut libc::input_event as *mut u8,
+            std::mem::size_of::<libc::input_event>(),
+        )
+    };
+    node.read_exact(buf).unwrap();
+    (event.type_, event.code, event.value)
+}
+
+#[test]
+fn test_uinput_missing_device() {
+    let err = UinputMouseController::with_path("/nonexistent/uinput").err().unwrap();
+    assert!(matches!(err, Error::Input { .. }));
+    assert!(err.to_string().contains("modprobe uinput"));
+}
+
+#[test]
+fn test_uinput_permission_denied() {
+    if unsafe { libc::geteuid() } == 0 {
+        eprintln!("running as root, skipping");
+        return;
+    }
+    let path = fake_device("uinput-readonly", 0o400);
+    let err = UinputMouseController::with_path(&path).err().unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert!(matches!(err, Error::Input { .. }));
+    assert!(err.to_string().contains("udev rule"));
+}
+
+#[test]
+fn test_uinput_not_a_device() {
+    let path = fake_device("uinput-file", 0o600);
+    let err = UinputMouseController::with_path(&path).err().unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert!(matches!(err, Error::Input { .. }));
+    assert!(err.to_string().contains("Failed to create the uinput device"));
+}
+
+#[test]
+fn test_uinput_emits_relative_motion() {
+    let mut mouse = match UinputMouseController::new() {
+        Ok(mouse) => mouse,
+        Err(err) => {
+            eprintln!("{}, skipping", err);
+            return;
+        }
+    };
+
+    // Wait for udev to create the evdev node
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut node = loop {
+        if let Some(node) = mouse.event_node().and_then(|path| File::open(path).ok()) {
+            break node;
+        }
+        if Instant::now() > deadline {
+            eprintln!("evdev node for the uinput device not available, skipping");
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+
+    mouse.mouse_move_relative(5, 0);
+    assert_eq!(read_event(&mut node), (EV_REL, REL_X, 5));
+    assert_eq!(read_event(&mut node), (EV_SYN, 0, 0));
+
+    mouse.mouse_move_relative(-5, 3);
+    assert_eq!(read_event(&mut node), (EV_REL, REL_X, -5));
+    assert_eq!(read_event(&mut node), (EV_REL, REL_Y, 3));
+    assert_eq!(read_event(&mut node), (EV_SYN, 0, 0));
+}
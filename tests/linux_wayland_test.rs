@@ -0,0 +1,98 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use busycrab::platform::linux::WaylandPlatform;
+use busycrab::{InhibitStatus, PlatformTrait};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// A headless `weston` compositor, killed when dropped.
+struct TestCompositor {
+    child: Child,
+    socket: PathBuf,
+}
+
+impl TestCompositor {
+    /// Starts weston with the headless backend on a private socket.
+    /// Returns `None` when weston isn't installed so tests can skip.
+    fn start() -> Option<Self> {
+        let runtime_dir = std::env::temp_dir().join(format!("busycrab-wayland-{}", std::process::id()));
+        std::fs::create_dir_all(&runtime_dir).ok()?;
+        let name = "busycrab-test";
+        let child = Command::new("weston")
+            .env("XDG_RUNTIME_DIR", &runtime_dir)
+            .arg("--backend=headless")
+            .arg(format!("--socket={}", name))
+            .arg("--idle-time=0")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let compositor = Self {
+            child,
+            socket: runtime_dir.join(name),
+        };
+
+        // Wait for the compositor socket to show up
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !compositor.socket.exists() {
+            if Instant::now() > deadline {
+                return None;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Some(compositor)
+    }
+}
+
+impl Drop for TestCompositor {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        if let Some(dir) = self.socket.parent() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+#[test]
+fn test_wayland_missing_display() {
+    // Only this test in the binary reads WAYLAND_DISPLAY, the others use explicit sockets
+    std::env::remove_var("WAYLAND_DISPLAY");
+    let platform = WaylandPlatform::new();
+
+    let result = platform.prevent_sleep();
+    assert!(result.unwrap_err().to_string().contains("WAYLAND_DISPLAY is not set"));
+}
+
+#[test]
+fn test_wayland_unreachable_compositor() {
+    let platform = WaylandPlatform::with_display("/nonexistent/busycrab-wayland-0");
+    assert!(platform.prevent_sleep().is_err());
+    assert_eq!(platform.status(), InhibitStatus::Released);
+}
+
+#[test]
+fn test_wayland_idle_inhibitor_held() {
+    let Some(compositor) = TestCompositor::start() else {
+        eprintln!("weston not available, skipping");
+        return;
+    };
+
+    let platform = WaylandPlatform::with_display(compositor.socket.to_str().unwrap());
+    platform.prevent_sleep().unwrap();
+    platform.prevent_sleep().unwrap();
+    // Held means the compositor configured the window and it got a buffer, so it's
+    // mapped and the inhibitor counts
+    assert_eq!(platform.status(), InhibitStatus::Held);
+
+    // Refreshing answers pings without waiting for events
+    let started = Instant::now();
+    platform.refresh().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    platform.release().unwrap();
+    assert_eq!(platform.status(), InhibitStatus::Released);
+}
@@ -0,0 +1,113 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use busycrab::input::keymap::{self, XK_SCROLL_LOCK};
+use busycrab::platform::linux::X11Platform;
+use busycrab::PlatformTrait;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use x11rb::protocol::{dpms, xproto};
+
+/// A headless `Xvfb` server, killed when dropped.
+struct TestXServer {
+    child: Child,
+    display: String,
+}
+
+impl TestXServer {
+    /// Starts Xvfb on a free display.
+    /// Returns `None` when Xvfb isn't installed so tests can skip.
+    fn start(number: u32) -> Option<Self> {
+        let display = format!(":{}", number);
+        let child = Command::new("Xvfb")
+            .arg(&display)
+            .arg("-nolisten")
+            .arg("tcp")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let server = Self { child, display };
+
+        // Wait for the server socket to show up
+        let socket = format!("/tmp/.X11-unix/X{}", number);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !Path::new(&socket).exists() {
+            if Instant::now() > deadline {
+                return None;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Some(server)
+    }
+}
+
+impl Drop for TestXServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Reads whether DPMS is enabled on the display.
+fn dpms_enabled(display: &str) -> bool {
+    let (connection, _) = x11rb::connect(Some(display)).unwrap();
+    dpms::info(&connection).unwrap().reply().unwrap().state
+}
+
+#[test]
+fn test_x11_missing_display() {
+    // Only this test in the binary reads DISPLAY, the others use explicit displays
+    std::env::remove_var("DISPLAY");
+    let platform = X11Platform::new();
+
+    let result = platform.prevent_sleep();
+    assert!(result.unwrap_err().to_string().contains("DISPLAY is not set"));
+}
+
+#[test]
+fn test_x11_unreachable_display() {
+    let platform = X11Platform::with_display(":4242");
+    assert!(platform.prevent_sleep().is_err());
+    assert!(!platform.is_suspended());
+}
+
+#[test]
+fn test_x11_suspends_and_restores() {
+    let Some(server) = TestXServer::start(97) else {
+        eprintln!("Xvfb not available, skipping");
+        return;
+    };
+
+    let platform = X11Platform::with_display(&server.display);
+    platform.prevent_sleep().unwrap();
+    platform.prevent_sleep().unwrap();
+    assert!(platform.is_suspended());
+    assert!(!dpms_enabled(&server.display));
+
+    drop(platform);
+    assert!(dpms_enabled(&server.display));
+}
+
+#[test]
+fn test_keymap_finds_scroll_lock() {
+    assert_eq!(keymap::keycode_on(":4242", XK_SCROLL_LOCK), None);
+
+    let Some(server) = TestXServer::start(96) else {
+        eprintln!("Xvfb not available, skipping");
+        return;
+    };
+
+    let keycode = keymap::keycode_on(&server.display, XK_SCROLL_LOCK).unwrap();
+    let (connection, _) = x11rb::connect(Some(&server.display)).unwrap();
+    let reply = xproto::get_keyboard_mapping(&connection, keycode, 1)
+        .unwrap()
+        .reply()
+        .unwrap();
+    assert!(reply.keysyms.contains(&XK_SCROLL_LOCK));
+
+    // VoidSymbol is never bound to a key
+    assert_eq!(keymap::keycode_on(&server.display, 0xffffff), None);
+}
@@ -0,0 +1,97 @@
+use busycrab::control::CycleOutcome;
+use busycrab::metrics::Metrics;
+use busycrab::Backend;
+
+/// Gets the value of the sample whose name and labels are `series`.
+fn value(text: &str, series: &str) -> Option<u64> {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| line.strip_prefix(series)?.strip_prefix(' '))
+        .map(|value| value.parse().unwrap())
+}
+
+#[test]
+fn test_empty_metrics() {
+    let text = Metrics::default().render(false);
+
+    assert_eq!(value(&text, "busycrab_activity_cycles_total"), Some(0));
+    for reason in ["idle", "schedule", "holiday", "paused"] {
+        let series = format!("busycrab_skipped_cycles_total{{reason=\"{}\"}}", reason);
+        assert_eq!(value(&text, &series), Some(0), "{}", series);
+    }
+    assert_eq!(value(&text, "busycrab_animation_frames_total"), Some(0));
+    assert_eq!(value(&text, "busycrab_paused"), Some(0));
+    // Backends only show up once they were used
+    assert!(!text.contains("busycrab_sleep_prevention_total{"));
+}
+
+#[test]
+fn test_recorded_metrics() {
+    let metrics = Metrics::default();
+    for outcome in [
+        CycleOutcome::Activity,
+        CycleOutcome::Activity,
+        CycleOutcome::UserActive,
+        CycleOutcome::OffSchedule,
+        CycleOutcome::Holiday,
+        CycleOutcome::Paused,
+        CycleOutcome::Paused,
+    ] {
+        metrics.record_cycle(outcome);
+    }
+    metrics.record_sleep_prevention(Backend::Logind, true);
+    metrics.record_sleep_prevention(Backend::Logind, false);
+    metrics.record_sleep_prevention(Backend::Logind, true);
+    metrics.record_sleep_prevention(Backend::X11, false);
+    metrics.record_frame();
+    metrics.record_frame();
+    metrics.record_frame();
+
+    assert_eq!(metrics.activity_cycles(), 2);
+    assert_eq!(metrics.animation_frames(), 3);
+
+    let text = metrics.render(true);
+    assert_eq!(value(&text, "busycrab_activity_cycles_total"), Some(2));
+    for (reason, count) in [("idle", 1), ("schedule", 1), ("holiday", 1), ("paused", 2)] {
+        let series = format!("busycrab_skipped_cycles_total{{reason=\"{}\"}}", reason);
+        assert_eq!(value(&text, &series), Some(count), "{}", series);
+    }
+    for (backend, result, count) in [
+        ("logind", "success", 2),
+        ("logind", "failure", 1),
+        ("x11", "success", 0),
+        ("x11", "failure", 1),
+    ] {
+        let series = format!(
+            "busycrab_sleep_prevention_total{{backend=\"{}\",result=\"{}\"}}",
+            backend, result
+        );
+        assert_eq!(value(&text, &series), Some(count), "{}", series);
+    }
+    assert_eq!(value(&text, "busycrab_animation_frames_total"), Some(3));
+    assert_eq!(value(&text, "busycrab_paused"), Some(1));
+}
+
+#[test]
+fn test_exposition_format() {
+    let text = Metrics::default().render(false);
+
+    assert!(text.ends_with('\n'));
+    assert!(text.contains(
+        "# HELP busycrab_activity_cycles_total Activity cycles that simulated activity.\n\
+         # TYPE busycrab_activity_cycles_total counter\n\
+         busycrab_activity_cycles_total 0\n"
+    ));
+    assert!(text.contains("# TYPE busycrab_paused gauge\nbusycrab_paused 0\n"));
+
+    // Every sample belongs to the family declared before it
+    let mut family = "";
+    for line in text.lines() {
+        if let Some(declared) = line.strip_prefix("# TYPE ") {
+            family = declared.split(' ').next().unwrap();
+        } else if !line.starts_with('#') {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert_eq!(name, family, "{}", line);
+        }
+    }
+}
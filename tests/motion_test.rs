@@ -1,5 +1,6 @@
 mod common;
 
+use busycrab::motion::clock::{format_countdown, ClockMotion};
 use busycrab::motion::crab::CrabMotion;
 use busycrab::motion::Motion;
 
@@ -54,3 +55,44 @@ fn test_motion_trait_impl() {
     // This verifies we can call methods through the trait object
     motion_box.update();
 }
+
+#[test]
+fn test_countdown_format() {
+    use std::time::Duration;
+
+    assert_eq!(format_countdown(Duration::from_secs(5025)), "1:23:45");
+    assert_eq!(format_countdown(Duration::from_secs(754)), "12:34");
+    assert_eq!(format_countdown(Duration::ZERO), "00:00");
+
+    // Motions without a countdown ignore the deadline
+    let mut motion: Box<dyn Motion> = Box::new(MockMotion::new());
+    motion.set_deadline(Some(std::time::Instant::now()));
+    let mut clock = ClockMotion::new();
+    clock.set_deadline(None);
+}
+
+#[test]
+fn test_motions_show_pause() {
+    use busycrab::motion::mandelbrot::MandelbrotMotion;
+    use busycrab::motion::matrix::MatrixMotion;
+
+    let mut motions: Vec<Box<dyn Motion>> = vec![
+        Box::new(CrabMotion::new()),
+        Box::new(MatrixMotion::new()),
+        Box::new(MandelbrotMotion::new()),
+        Box::new(ClockMotion::new()),
+    ];
+    for motion in &mut motions {
+        motion.update();
+        motion.set_paused(true);
+        motion.update();
+        motion.set_paused(false);
+        motion.update();
+    }
+
+    // Motions without a paused look keep working as before
+    let mut mock = MockMotion::new();
+    mock.set_paused(true);
+    mock.update();
+    assert_eq!(mock.update_count(), 1);
+}
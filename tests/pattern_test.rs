@@ -0,0 +1,146 @@
+use busycrab::pattern::bezier::{ease_in_out, BezierPattern};
+use busycrab::pattern::circle::CirclePattern;
+use busycrab::pattern::figure_eight::FigureEightPattern;
+use busycrab::pattern::horizontal::HorizontalPattern;
+use busycrab::pattern::random_walk::RandomWalkPattern;
+use busycrab::pattern::square::SquarePattern;
+use busycrab::pattern::{steps_through, PatternKind, Step, WigglePattern};
+use std::time::Duration;
+
+/// Positions visited by a list of steps, relative to the start.
+fn positions(steps: &[Step]) -> Vec<(i32, i32)> {
+    steps
+        .iter()
+        .scan((0, 0), |position, step| {
+            *position = (position.0 + step.dx, position.1 + step.dy);
+            Some(*position)
+        })
+        .collect()
+}
+
+#[test]
+fn test_horizontal_pattern_matches_classic_wiggle() {
+    let steps = HorizontalPattern::new().steps(5);
+    assert_eq!(
+        steps,
+        vec![
+            Step::new(5, 0, Duration::from_millis(100)),
+            Step::new(-5, 0, Duration::ZERO),
+        ]
+    );
+}
+
+#[test]
+fn test_circle_pattern_stays_on_its_diameter() {
+    let steps = CirclePattern::new().steps(10);
+    let visited = positions(&steps);
+
+    assert_eq!(visited.last(), Some(&(0, 0)));
+    assert!(visited.contains(&(10, 0)));
+    for (x, y) in visited {
+        assert!((0..=10).contains(&x) && (-5..=5).contains(&y), "({}, {})", x, y);
+    }
+}
+
+#[test]
+fn test_square_pattern_visits_corners() {
+    let steps = SquarePattern::new().steps(4);
+    assert_eq!(positions(&steps), vec![(4, 0), (4, 4), (0, 4), (0, 0)]);
+}
+
+#[test]
+fn test_figure_eight_pattern_crosses_the_start() {
+    let steps = FigureEightPattern::new().steps(6);
+    let visited = positions(&steps);
+
+    assert!(visited.contains(&(6, 0)));
+    assert!(visited.contains(&(-6, 0)));
+    assert_eq!(visited.last(), Some(&(0, 0)));
+}
+
+#[test]
+fn test_random_walk_stays_within_radius() {
+    let mut pattern = RandomWalkPattern::with_seed(7);
+    for _ in 0..20 {
+        let visited = positions(&pattern.steps(6));
+        assert_eq!(visited.last(), Some(&(0, 0)));
+        for (x, y) in visited {
+            assert!(x * x + y * y <= 36, "({}, {}) is outside the radius", x, y);
+        }
+    }
+}
+
+#[test]
+fn test_random_walk_is_repeatable_with_seed() {
+    let first = RandomWalkPattern::with_seed(42).steps(10);
+    let second = RandomWalkPattern::with_seed(42).steps(10);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_steps_through_skips_standing_still() {
+    let delay = Duration::from_millis(1);
+    let steps = steps_through(&[(0, 0), (1, 0), (1, 0)], delay);
+    // The pauses at repeated points are kept
+    assert_eq!(
+        steps,
+        vec![Step::new(1, 0, delay * 3), Step::new(-1, 0, delay)]
+    );
+}
+
+#[test]
+fn test_pattern_kind_names() {
+    for kind in PatternKind::ALL {
+        assert_eq!(kind.name().parse::<PatternKind>(), Ok(kind));
+        assert_eq!(kind.create().name(), kind.name());
+    }
+    assert_eq!("figure_eight".parse::<PatternKind>(), Ok(PatternKind::FigureEight));
+    assert!("spiral".parse::<PatternKind>().is_err());
+}
+
+#[test]
+fn test_bezier_snapshot() {
+    let mut pattern = BezierPattern::with_seed(1, Duration::from_millis(80));
+    let moves: Vec<(i32, i32)> = pattern.steps(10).iter().map(|step| (step.dx, step.dy)).collect();
+    assert_eq!(
+        moves,
+        vec![(1, -1), (-2, 4), (-2, 0), (-1, 0), (-1, 0), (2, -1), (4, -1), (-1, -1)]
+    );
+}
+
+#[test]
+fn test_bezier_glides_for_the_duration() {
+    let duration = Duration::from_millis(600);
+    let mut pattern = BezierPattern::with_seed(9, duration);
+    for _ in 0..10 {
+        let steps = pattern.steps(40);
+        let total: Duration = steps.iter().map(|step| step.delay).sum();
+
+        assert!(steps.len() > 20, "only {} steps", steps.len());
+        assert_eq!(total, duration);
+        assert_eq!(positions(&steps).last(), Some(&(0, 0)));
+        // Steps stay small, the cursor never jumps
+        assert!(steps.iter().all(|step| step.dx.abs() <= 10 && step.dy.abs() <= 10));
+    }
+}
+
+#[test]
+fn test_bezier_is_repeatable_with_seed() {
+    let duration = Duration::from_millis(300);
+    let first = BezierPattern::with_seed(5, duration).steps(20);
+    let second = BezierPattern::with_seed(5, duration).steps(20);
+    let other = BezierPattern::with_seed(6, duration).steps(20);
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+#[test]
+fn test_ease_in_out() {
+    assert_eq!(ease_in_out(0.0), 0.0);
+    assert_eq!(ease_in_out(0.5), 0.5);
+    assert_eq!(ease_in_out(1.0), 1.0);
+    // Slow at both ends, fast in the middle
+    assert!(ease_in_out(0.1) < 0.1);
+    assert!(ease_in_out(0.9) > 0.9);
+    assert!(ease_in_out(0.55) - ease_in_out(0.45) > 0.1);
+}
@@ -0,0 +1,116 @@
+use busycrab::schedule::{Days, Schedule, TimeWindow};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::path::Path;
+
+/// Builds a time in the week of Monday 2024-01-01.
+fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, day)
+        .unwrap()
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+}
+
+#[test]
+fn test_days_parsing() {
+    assert_eq!("Mon-Fri".parse(), Ok(Days::WEEKDAYS));
+    assert_eq!("weekdays".parse(), Ok(Days::WEEKDAYS));
+    assert_eq!("sat,SUN".parse(), Ok(Days::WEEKEND));
+    assert_eq!("daily".parse(), Ok(Days::ALL));
+    assert_eq!("Mon-Sun".parse(), Ok(Days::ALL));
+    assert!("Mon-Funday".parse::<Days>().is_err());
+
+    // Ranges wrap around the week
+    let days: Days = "Fri-Mon".parse().unwrap();
+    assert_eq!(days.to_string(), "Mon,Fri-Sun");
+}
+
+#[test]
+fn test_window_contains() {
+    let window: TimeWindow = "Mon-Fri 09:00-18:00".parse().unwrap();
+    assert!(window.contains(at(1, 9, 0)));
+    assert!(window.contains(at(5, 17, 59)));
+    assert!(!window.contains(at(1, 18, 0)));
+    assert!(!window.contains(at(1, 8, 59)));
+    // Saturday
+    assert!(!window.contains(at(6, 12, 0)));
+}
+
+#[test]
+fn test_window_past_midnight() {
+    let window: TimeWindow = "Fri 22:00-06:00".parse().unwrap();
+    assert!(window.contains(at(5, 23, 0)));
+    assert!(window.contains(at(6, 5, 59)));
+    assert!(!window.contains(at(6, 6, 0)));
+    // Thursday night isn't in it
+    assert!(!window.contains(at(5, 1, 0)));
+
+    let whole_day: TimeWindow = "Sun 00:00-24:00".parse().unwrap();
+    assert!(whole_day.contains(at(7, 0, 0)));
+    assert!(whole_day.contains(at(7, 23, 59)));
+    assert!(!whole_day.contains(at(8, 0, 0)));
+}
+
+#[test]
+fn test_window_parsing() {
+    let window: TimeWindow = "Mon - Wed 08:30-24:00".parse().unwrap();
+    assert_eq!(
+        window,
+        TimeWindow::new(
+            "Mon-Wed".parse().unwrap(),
+            NaiveTime::from_hms_opt(8, 30, 0).unwrap(),
+            NaiveTime::MIN
+        )
+    );
+    assert_eq!(window.to_string(), "Mon-Wed 08:30-24:00");
+    assert_eq!("07:00-08:00".parse::<TimeWindow>().unwrap().days, Days::ALL);
+
+    assert!("Mon-Fri".parse::<TimeWindow>().is_err());
+    assert!("Mon 25:00-26:00".parse::<TimeWindow>().is_err());
+}
+
+#[test]
+fn test_schedule_with_breaks() {
+    let schedule: Schedule = "Mon-Fri 09:00-18:00; break Mon-Fri 12:00-13:00"
+        .parse()
+        .unwrap();
+    assert!(schedule.is_active(at(2, 10, 0)));
+    assert!(!schedule.is_active(at(2, 12, 30)));
+    assert!(schedule.is_active(at(2, 13, 0)));
+    assert!(!schedule.is_active(at(2, 20, 0)));
+    assert_eq!(
+        schedule.to_string(),
+        "Mon-Fri 09:00-18:00; break Mon-Fri 12:00-13:00"
+    );
+
+    // Breaks alone pause an otherwise always-on schedule
+    let schedule: Schedule = "break 00:00-07:00".parse().unwrap();
+    assert!(!schedule.is_active(at(3, 3, 0)));
+    assert!(schedule.is_active(at(3, 9, 0)));
+
+    assert!(" ; ".parse::<Schedule>().is_err());
+    assert!("break".parse::<Schedule>().is_err());
+}
+
+#[test]
+fn test_schedule_file() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/office.schedule");
+    let schedule = Schedule::from_file(&path).unwrap();
+
+    assert_eq!(schedule.windows().len(), 2);
+    assert_eq!(schedule.breaks().len(), 1);
+    assert!(schedule.is_active(at(6, 11, 0)));
+    assert!(!schedule.is_active(at(4, 12, 15)));
+    assert!(!schedule.is_active(at(7, 11, 0)));
+}
+
+#[test]
+fn test_schedule_file_errors() {
+    let err = Schedule::from_file("/nonexistent/busycrab.schedule").unwrap_err();
+    assert!(err.to_string().contains("can't read schedule file"));
+
+    let path = std::env::temp_dir().join(format!("busycrab-bad-{}.schedule", std::process::id()));
+    std::fs::write(&path, "Mon-Fri 09:00-18:00\nTue 9am-5pm\n").unwrap();
+    let err = Schedule::from_file(&path).unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{}", err);
+    std::fs::remove_file(&path).unwrap();
+}
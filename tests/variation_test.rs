@@ -0,0 +1,74 @@
+use busycrab::variation::{jitter_secs, ValueRange};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn test_value_range_parsing() {
+    assert_eq!("60".parse(), Ok(ValueRange::fixed(60u64)));
+    assert_eq!("45..90".parse(), Ok(ValueRange::new(45u64, 90).unwrap()));
+    assert_eq!("45..=90".parse(), Ok(ValueRange::new(45u64, 90).unwrap()));
+    assert_eq!("-2..2".parse(), Ok(ValueRange::new(-2i32, 2).unwrap()));
+
+    assert!("90..45".parse::<ValueRange<u64>>().is_err());
+    assert!("45..".parse::<ValueRange<u64>>().is_err());
+    assert!("-5".parse::<ValueRange<u64>>().is_err());
+}
+
+#[test]
+fn test_value_range_display() {
+    assert_eq!(ValueRange::fixed(60).to_string(), "60");
+    assert_eq!(ValueRange::new(1, 4).unwrap().to_string(), "1..4");
+    assert_eq!(format!("{:>6}", ValueRange::new(1, 4).unwrap()), "  1..4");
+}
+
+#[test]
+fn test_value_range_serde() {
+    let range = ValueRange::new(45u64, 90).unwrap();
+    assert_eq!(serde_json::to_string(&range).unwrap(), r#""45..90""#);
+    assert_eq!(serde_json::to_string(&ValueRange::fixed(-3)).unwrap(), r#""-3""#);
+
+    assert_eq!(serde_json::from_str::<ValueRange<u64>>(r#""45..90""#).unwrap(), range);
+    assert_eq!(
+        serde_json::from_str::<ValueRange<i32>>("-3").unwrap(),
+        ValueRange::fixed(-3)
+    );
+    assert!(serde_json::from_str::<ValueRange<u64>>("-3").is_err());
+    assert!(serde_json::from_str::<ValueRange<u64>>(r#""90..45""#).is_err());
+    assert!(serde_json::from_str::<ValueRange<u64>>("true").is_err());
+}
+
+#[test]
+fn test_value_range_sample() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let range = ValueRange::new(45u64, 90).unwrap();
+    let values: Vec<u64> = (0..200).map(|_| range.sample(&mut rng)).collect();
+
+    assert!(values.iter().all(|value| (45..=90).contains(value)));
+    assert!(values.contains(&45) && values.contains(&90));
+    assert_eq!(ValueRange::fixed(3).sample(&mut rng), 3);
+}
+
+#[test]
+fn test_jitter_secs() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    for _ in 0..200 {
+        let secs = jitter_secs(60, 20, &mut rng);
+        assert!((48..=72).contains(&secs), "{} out of range", secs);
+    }
+    assert_eq!(jitter_secs(60, 0, &mut rng), 60);
+    // Too short to jitter by whole seconds
+    assert_eq!(jitter_secs(4, 10, &mut rng), 4);
+}
+
+#[test]
+fn test_jitter_secs_never_reaches_zero() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let values: Vec<u64> = (0..500).map(|_| jitter_secs(10, 100, &mut rng)).collect();
+    assert!(values.iter().all(|value| (1..=20).contains(value)));
+    assert!(values.contains(&1));
+
+    // Beyond 100% the low end still stops at 1 second
+    for _ in 0..200 {
+        assert!(jitter_secs(2, 500, &mut rng) >= 1);
+    }
+}
@@ -3,7 +3,16 @@ mod common;
 use busycrab::BusyCrab;
 use std::time::Duration;
 use std::cell::RefCell;
-use busycrab::{MouseController, PlatformTrait};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use busycrab::idle::IdleDetector;
+use busycrab::pattern::PatternKind;
+use busycrab::schedule::{Calendar, Clock, HolidayAction, Holidays, Schedule};
+use chrono::{NaiveDate, NaiveDateTime};
+use busycrab::{
+    ActivityKey, ActivityMode, Backend, Error, FailurePolicy, FailureStats, InhibitGuard,
+    InhibitStatus, KeyboardController, MouseController, PlatformTrait, Positioning, ValueRange,
+};
 
 /// A mock mouse controller for testing.
 pub struct MockMouseController {
@@ -36,12 +45,126 @@ impl MouseController for MockMouseController {
     }
 }
 
+/// A mock mouse on a screen with edges, for testing positioning.
+pub struct MockScreenMouse {
+    /// Current cursor position
+    pub position: RefCell<(i32, i32)>,
+    /// Screen size; moves are clamped to it
+    pub size: (i32, i32),
+    /// Whether location reports the position
+    pub knows_location: bool,
+}
+
+impl MockScreenMouse {
+    /// Creates a MockScreenMouse on a 1920x1080 screen.
+    pub fn at(x: i32, y: i32, knows_location: bool) -> Self {
+        Self {
+            position: RefCell::new((x, y)),
+            size: (1920, 1080),
+            knows_location,
+        }
+    }
+}
+
+impl MouseController for MockScreenMouse {
+    fn mouse_move_relative(&mut self, x: i32, y: i32) {
+        let (current_x, current_y) = *self.position.borrow();
+        self.mouse_move_to(current_x + x, current_y + y);
+    }
+
+    fn location(&self) -> Option<(i32, i32)> {
+        self.knows_location.then(|| *self.position.borrow())
+    }
+
+    fn mouse_move_to(&mut self, x: i32, y: i32) {
+        *self.position.borrow_mut() = (x.clamp(0, self.size.0 - 1), y.clamp(0, self.size.1 - 1));
+    }
+}
+
+/// A mock keyboard controller for testing.
+#[derive(Default)]
+pub struct MockKeyboardController {
+    /// Keys passed to key_tap, in order
+    pub taps: RefCell<Vec<ActivityKey>>,
+}
+
+impl KeyboardController for MockKeyboardController {
+    fn key_tap(&mut self, key: ActivityKey) {
+        self.taps.borrow_mut().push(key);
+    }
+}
+
+/// A mock idle detector for testing.
+#[derive(Default)]
+pub struct MockIdleDetector {
+    /// Idle times to report, in order; an empty queue reports an error
+    pub idle_times: RefCell<Vec<Duration>>,
+}
+
+impl MockIdleDetector {
+    /// Creates a MockIdleDetector that reports `idle_times` in order.
+    pub fn new(idle_times: &[Duration]) -> Self {
+        Self {
+            idle_times: RefCell::new(idle_times.iter().rev().copied().collect()),
+        }
+    }
+}
+
+impl IdleDetector for MockIdleDetector {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn idle_time(&mut self) -> busycrab::Result<Duration> {
+        self.idle_times
+            .borrow_mut()
+            .pop()
+            .ok_or_else(|| Error::idle("No idle time left"))
+    }
+}
+
+/// A mock clock for testing schedules.
+pub struct MockClock {
+    /// Time to report
+    pub now: RefCell<NaiveDateTime>,
+}
+
+impl MockClock {
+    /// Creates a MockClock set to a time on Monday 2024-01-01.
+    pub fn monday(hour: u32, minute: u32) -> Self {
+        Self {
+            now: RefCell::new(
+                NaiveDate::from_ymd_opt(2024, 1, 1)
+                    .unwrap()
+                    .and_hms_opt(hour, minute, 0)
+                    .unwrap(),
+            ),
+        }
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> NaiveDateTime {
+        *self.now.borrow()
+    }
+}
+
 /// A mock platform implementation for testing.
 pub struct MockPlatform {
-    /// Whether prevent_sleep was called
+    /// Whether acquire or refresh was called
     pub prevent_sleep_called: RefCell<bool>,
-    /// The result to return from prevent_sleep
+    /// The result to return from acquire and refresh
     pub prevent_sleep_result: RefCell<Result<(), &'static str>>,
+    /// Number of times release was called
+    pub release_count: RefCell<usize>,
+    /// Whether the inhibit is currently held
+    pub held: RefCell<bool>,
+    /// Flag cleared on refresh, to simulate Ctrl+C during a cycle
+    pub stop_on_refresh: RefCell<Option<Arc<AtomicBool>>>,
+    /// Number of upcoming calls that fail before the result above is used
+    pub transient_failures: RefCell<u32>,
+    /// Number of live guards
+    guards: AtomicUsize,
 }
 
 impl MockPlatform {
@@ -50,22 +173,50 @@ impl MockPlatform {
         Self {
             prevent_sleep_called: RefCell::new(false),
             prevent_sleep_result: RefCell::new(Ok(())),
+            release_count: RefCell::new(0),
+            held: RefCell::new(false),
+            stop_on_refresh: RefCell::new(None),
+            transient_failures: RefCell::new(0),
+            guards: AtomicUsize::new(0),
         }
     }
 
     /// Creates a MockPlatform that returns an error.
     pub fn with_error(error: &'static str) -> Self {
-        Self {
-            prevent_sleep_called: RefCell::new(false),
-            prevent_sleep_result: RefCell::new(Err(error)),
-        }
+        let platform = Self::new();
+        *platform.prevent_sleep_result.borrow_mut() = Err(error);
+        platform
     }
 }
 
 impl PlatformTrait for MockPlatform {
-    fn prevent_sleep(&self) -> Result<(), &'static str> {
+    fn acquire(&self) -> busycrab::Result<InhibitGuard<'_>> {
         *self.prevent_sleep_called.borrow_mut() = true;
-        *self.prevent_sleep_result.borrow()
+        if let Some(running) = self.stop_on_refresh.borrow().as_ref() {
+            running.store(false, Ordering::SeqCst);
+        }
+        if *self.transient_failures.borrow() > 0 {
+            *self.transient_failures.borrow_mut() -= 1;
+            return Err(Error::backend(Backend::default(), "Transient error"));
+        }
+        (*self.prevent_sleep_result.borrow())
+            .map_err(|message| Error::backend(Backend::default(), message))?;
+        *self.held.borrow_mut() = true;
+        Ok(InhibitGuard::new(self, &self.guards))
+    }
+
+    fn release(&self) -> busycrab::Result<()> {
+        *self.release_count.borrow_mut() += 1;
+        *self.held.borrow_mut() = false;
+        Ok(())
+    }
+
+    fn status(&self) -> InhibitStatus {
+        if *self.held.borrow() {
+            InhibitStatus::Held
+        } else {
+            InhibitStatus::Released
+        }
     }
 }
 
@@ -100,6 +251,8 @@ fn test_motion_selection() {
     
     let matrix_motion = BusyCrab::new(60, 3).with_motion("matrix");
     assert_eq!(matrix_motion.has_motion(), true);
+    assert_eq!(matrix_motion.get_motion(), "matrix");
+    assert_eq!(BusyCrab::new(60, 3).with_motion("Clock").get_motion(), "clock");
     
     let no_motion = BusyCrab::new(60, 3).with_motion("none");
     assert_eq!(no_motion.has_motion(), false);
@@ -107,6 +260,7 @@ fn test_motion_selection() {
     // Invalid motion type should default to none
     let invalid_motion = BusyCrab::new(60, 3).with_motion("invalid");
     assert_eq!(invalid_motion.has_motion(), false);
+    assert_eq!(invalid_motion.get_motion(), "none");
 }
 
 // Basic test to demonstrate mocking
@@ -160,7 +314,955 @@ fn test_platform_error() {
     let mut count = 0;
     let result = busycrab.execute_activity_cycle(&mut count);
     
-    assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), "Test error");
+    let err = result.unwrap_err();
+    assert_eq!(err.failed_backend(), Some(Backend::default()));
+    assert!(err.to_string().ends_with("Test error"));
     assert_eq!(count, 0); // Count should not be incremented on error
 }
+
+// Test that the inhibit guard releases when dropped
+#[test]
+fn test_inhibit_guard_releases_on_drop() {
+    let platform = MockPlatform::new();
+
+    let guard = platform.acquire().unwrap();
+    assert_eq!(platform.status(), InhibitStatus::Held);
+    drop(guard);
+    assert_eq!(platform.status(), InhibitStatus::Released);
+    assert_eq!(*platform.release_count.borrow(), 1);
+
+    // Keeping the guard leaves the inhibit held
+    platform.acquire().unwrap().keep();
+    assert_eq!(platform.status(), InhibitStatus::Held);
+    assert_eq!(*platform.release_count.borrow(), 1);
+}
+
+// Test that the inhibit stays held until the last of several guards is dropped
+#[test]
+fn test_inhibit_guards_share_the_inhibit() {
+    let platform = MockPlatform::new();
+
+    let first = platform.acquire().unwrap();
+    let second = platform.acquire().unwrap();
+    drop(first);
+    assert_eq!(platform.status(), InhibitStatus::Held);
+    assert_eq!(*platform.release_count.borrow(), 0);
+
+    let third = platform.acquire().unwrap();
+    second.release().unwrap();
+    assert_eq!(platform.status(), InhibitStatus::Held);
+    drop(third);
+    assert_eq!(platform.status(), InhibitStatus::Released);
+    assert_eq!(*platform.release_count.borrow(), 1);
+
+    // Guards of another platform don't count
+    let other = MockPlatform::new();
+    let guard = platform.acquire().unwrap();
+    let other_guard = other.acquire().unwrap();
+    drop(guard);
+    assert_eq!(platform.status(), InhibitStatus::Released);
+    assert_eq!(other.status(), InhibitStatus::Held);
+    drop(other_guard);
+}
+
+// Test that run releases the inhibit when a cycle fails
+#[test]
+fn test_run_releases_on_error() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mock_platform = Box::new(MockPlatform::with_error("Test error"));
+    let platform_ptr = &*mock_platform as *const MockPlatform;
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform);
+    let result = busycrab.run();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let platform = unsafe { &*platform_ptr };
+
+    assert!(result.is_err());
+    assert_eq!(*platform.release_count.borrow(), 1);
+}
+
+// Test that run releases the inhibit on shutdown
+#[test]
+fn test_run_releases_on_shutdown() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mock_platform = Box::new(MockPlatform::new());
+    let platform_ptr = &*mock_platform as *const MockPlatform;
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform);
+
+    // Safe because we know the mock is still alive inside busycrab
+    let platform = unsafe { &*platform_ptr };
+    *platform.stop_on_refresh.borrow_mut() = Some(busycrab.shutdown_handle());
+
+    let result = busycrab.run();
+
+    assert!(result.is_ok());
+    assert_eq!(*platform.release_count.borrow(), 1);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Released);
+}
+
+// Test that the warn policy keeps the cycle going
+#[test]
+fn test_warn_policy_continues() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::with_error("Test error"));
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_failure_policy(FailurePolicy::Warn);
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+
+    assert_eq!(count, 2);
+    assert_eq!(mouse.call_count(), 4);
+    assert_eq!(busycrab.failure_stats().failures, 2);
+}
+
+// Test that the retry policy recovers from transient failures
+#[test]
+fn test_retry_policy_recovers() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mock_platform = Box::new(MockPlatform::new());
+    *mock_platform.transient_failures.borrow_mut() = 2;
+
+    let policy = FailurePolicy::Retry {
+        max_retries: 3,
+        initial_delay: Duration::from_millis(1),
+    };
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_failure_policy(policy);
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+
+    assert_eq!(count, 1);
+    assert_eq!(
+        busycrab.failure_stats(),
+        FailureStats {
+            failures: 2,
+            retries: 2,
+            recovered: 1,
+        }
+    );
+}
+
+// Test that the retry policy gives up after the last retry
+#[test]
+fn test_retry_policy_gives_up() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::with_error("Test error"));
+
+    let policy = FailurePolicy::Retry {
+        max_retries: 2,
+        initial_delay: Duration::from_millis(1),
+    };
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_failure_policy(policy);
+    let mut count = 0;
+    let result = busycrab.execute_activity_cycle(&mut count);
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+
+    assert!(result.is_err());
+    assert_eq!(count, 0);
+    assert_eq!(mouse.call_count(), 0);
+    assert_eq!(busycrab.failure_stats().failures, 3);
+    assert_eq!(busycrab.failure_stats().retries, 2);
+}
+
+// Test that shutting down while waiting to retry ends the cycle without activity
+#[test]
+fn test_retry_policy_stops_on_shutdown() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::with_error("Test error"));
+
+    let policy = FailurePolicy::Retry {
+        max_retries: 3,
+        initial_delay: Duration::from_millis(1),
+    };
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_failure_policy(policy);
+    busycrab.control_handle().stop();
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+
+    assert_eq!(count, 0);
+    assert_eq!(mouse.call_count(), 0);
+    assert_eq!(busycrab.failure_stats().failures, 1);
+    assert_eq!(busycrab.failure_stats().retries, 0);
+}
+
+// Test that keyboard activity taps the key without moving the mouse
+#[test]
+fn test_keyboard_activity() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_keyboard = Box::new(MockKeyboardController::default());
+    let keyboard_ptr = &*mock_keyboard as *const MockKeyboardController;
+
+    let mut busycrab = BusyCrab::with_mouse_controller(60, 5, mock_mouse)
+        .with_keyboard_controller(mock_keyboard)
+        .with_activity(ActivityMode::Keyboard, ActivityKey::Shift);
+    busycrab.simulate_activity();
+
+    // Safe because we know the mocks are still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    let keyboard = unsafe { &*keyboard_ptr };
+
+    assert_eq!(mouse.call_count(), 0);
+    assert_eq!(*keyboard.taps.borrow(), vec![ActivityKey::Shift]);
+    assert_eq!(busycrab.get_activity(), ActivityMode::Keyboard);
+}
+
+// Test that both mode moves the mouse and taps the key
+#[test]
+fn test_mouse_and_keyboard_activity() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_keyboard = Box::new(MockKeyboardController::default());
+    let keyboard_ptr = &*mock_keyboard as *const MockKeyboardController;
+
+    let mut busycrab = BusyCrab::with_mouse_controller(60, 5, mock_mouse)
+        .with_keyboard_controller(mock_keyboard)
+        .with_activity(ActivityMode::Both, ActivityKey::ScrollLock);
+    busycrab.simulate_activity();
+
+    // Safe because we know the mocks are still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    let keyboard = unsafe { &*keyboard_ptr };
+
+    assert_eq!(mouse.call_count(), 2);
+    // Scroll Lock is tapped twice to leave the lock state unchanged
+    assert_eq!(
+        *keyboard.taps.borrow(),
+        vec![ActivityKey::ScrollLock, ActivityKey::ScrollLock]
+    );
+}
+
+// Test that every pattern leaves the mouse where it started
+#[test]
+fn test_patterns_return_to_start() {
+    for kind in PatternKind::ALL {
+        let mock_mouse = Box::new(MockMouseController::new());
+        let mouse_ptr = &*mock_mouse as *const MockMouseController;
+
+        let mut busycrab =
+            BusyCrab::with_mouse_controller(60, 8, mock_mouse).with_pattern(kind.create());
+        busycrab.simulate_activity();
+
+        // Safe because we know the mock is still alive inside busycrab
+        let mouse = unsafe { &*mouse_ptr };
+        let calls = mouse.move_calls.borrow();
+        let net = calls
+            .iter()
+            .fold((0, 0), |(x, y), (dx, dy)| (x + dx, y + dy));
+
+        assert!(calls.len() >= 2, "{} made {} moves", kind, calls.len());
+        assert_eq!(net, (0, 0), "{} drifted", kind);
+    }
+}
+
+// Test that cycles are skipped while the user is active, but still refresh sleep prevention
+#[test]
+fn test_idle_detector_skips_active_user() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+    let platform_ptr = &*mock_platform as *const MockPlatform;
+    let detector = MockIdleDetector::new(&[Duration::from_secs(5)]);
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_idle_detector(Box::new(detector), Duration::from_secs(60));
+    assert_eq!(busycrab.get_idle_threshold(), Some(Duration::from_secs(60)));
+
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+
+    // Safe because we know the mocks are still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    let platform = unsafe { &*platform_ptr };
+
+    assert_eq!(count, 0);
+    assert_eq!(mouse.call_count(), 0);
+    assert!(*platform.prevent_sleep_called.borrow());
+}
+
+// Test that cycles run once the user has been idle for the threshold
+#[test]
+fn test_idle_detector_runs_when_idle() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let detector = MockIdleDetector::new(&[Duration::from_secs(120)]);
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, Box::new(MockPlatform::new()))
+        .with_idle_detector(Box::new(detector), Duration::from_secs(60));
+
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    assert_eq!(count, 1);
+    assert_eq!(mouse.call_count(), 2);
+}
+
+// Test that the input a cycle makes isn't mistaken for the user's
+#[test]
+fn test_idle_detector_ignores_own_input() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    // Right after a cycle, the detector sees the wiggle as fresh input
+    let detector = MockIdleDetector::new(&[Duration::from_secs(120), Duration::ZERO]);
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, Box::new(MockPlatform::new()))
+        .with_idle_detector(Box::new(detector), Duration::from_secs(60));
+
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    assert_eq!(count, 2);
+    assert_eq!(mouse.call_count(), 4);
+}
+
+// Test that activity is still simulated when the idle time can't be read
+#[test]
+fn test_idle_detector_error_counts_as_idle() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, Box::new(MockPlatform::new()))
+        .with_idle_detector(Box::new(MockIdleDetector::new(&[])), Duration::from_secs(60));
+
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    assert_eq!(count, 1);
+    assert_eq!(mouse.call_count(), 2);
+}
+
+// Test that relative moves drift at a screen edge, which absolute positioning avoids
+#[test]
+fn test_relative_positioning_drifts_at_edge() {
+    let mock_mouse = Box::new(MockScreenMouse::at(1917, 500, true));
+    let mouse_ptr = &*mock_mouse as *const MockScreenMouse;
+
+    // The horizontal pattern moves right first, which the edge clamps
+    let mut busycrab = BusyCrab::with_mouse_controller(60, 5, mock_mouse);
+    busycrab.simulate_activity();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    assert_eq!(*mouse.position.borrow(), (1914, 500));
+}
+
+// Test that absolute positioning puts the cursor back exactly, even at an edge
+#[test]
+fn test_absolute_positioning_restores_position() {
+    for kind in PatternKind::ALL {
+        for start in [(1, 500), (1918, 1079), (0, 0), (960, 540)] {
+            let mock_mouse = Box::new(MockScreenMouse::at(start.0, start.1, true));
+            let mouse_ptr = &*mock_mouse as *const MockScreenMouse;
+
+            let mut busycrab = BusyCrab::with_mouse_controller(60, 8, mock_mouse)
+                .with_pattern(kind.create())
+                .with_positioning(Positioning::Absolute);
+            busycrab.simulate_activity();
+
+            // Safe because we know the mock is still alive inside busycrab
+            let mouse = unsafe { &*mouse_ptr };
+            assert_eq!(*mouse.position.borrow(), start, "{} from {:?}", kind, start);
+        }
+    }
+}
+
+// Test that absolute positioning falls back to relative moves without a location
+#[test]
+fn test_absolute_positioning_falls_back() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+
+    let mut busycrab = BusyCrab::with_mouse_controller(60, 5, mock_mouse)
+        .with_positioning(Positioning::Absolute);
+    assert_eq!(busycrab.get_positioning(), Positioning::Absolute);
+    busycrab.simulate_activity();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    assert_eq!(mouse.call_count(), 2);
+    assert_eq!(mouse.call_args(0), Some((5, 0)));
+    assert_eq!(mouse.call_args(1), Some((-5, 0)));
+}
+
+// Test the fallback with a mouse that, like DefaultMouseController, moves to exact
+// positions but can't always tell where the cursor is
+#[test]
+fn test_absolute_positioning_unknown_location_stays_put() {
+    for kind in PatternKind::ALL {
+        let mock_mouse = Box::new(MockScreenMouse::at(960, 540, false));
+        let mouse_ptr = &*mock_mouse as *const MockScreenMouse;
+
+        let mut busycrab = BusyCrab::with_mouse_controller(60, 8, mock_mouse)
+            .with_pattern(kind.create())
+            .with_positioning(Positioning::Absolute);
+        busycrab.simulate_activity();
+
+        // Safe because we know the mock is still alive inside busycrab
+        let mouse = unsafe { &*mouse_ptr };
+        assert_eq!(*mouse.position.borrow(), (960, 540), "{}", kind);
+    }
+}
+
+// Test that the real controller reports no location when the display can't be reached
+#[cfg(target_os = "linux")]
+#[test]
+fn test_default_mouse_location_unavailable() {
+    let mouse = busycrab::DefaultMouseController::with_display(":4999");
+    assert_eq!(mouse.location(), None);
+}
+
+/// Runs `cycles` cycles and collects the interval and wiggle distance drawn for each.
+fn drawn_values(seed: u64, cycles: usize) -> Vec<(Duration, i32)> {
+    let mut busycrab = BusyCrab::for_testing(
+        60,
+        3,
+        Box::new(MockMouseController::new()),
+        Box::new(MockPlatform::new()),
+    )
+    .with_interval_range(ValueRange::new(45, 90).unwrap())
+    .with_wiggle_range(ValueRange::new(1, 4).unwrap())
+    .with_jitter(10)
+    .with_seed(seed);
+
+    let mut count = 0;
+    (0..cycles)
+        .map(|_| {
+            busycrab.execute_activity_cycle(&mut count).unwrap();
+            (busycrab.get_interval(), busycrab.get_wiggle_distance())
+        })
+        .collect()
+}
+
+// Test that each cycle draws its interval and wiggle distance from the ranges
+#[test]
+fn test_cycle_values_vary_within_ranges() {
+    let values = drawn_values(42, 50);
+
+    for (interval, wiggle) in &values {
+        // 45..90 seconds, then up to 10% either way
+        assert!((40..=99).contains(&interval.as_secs()), "{:?}", interval);
+        assert!((1..=4).contains(wiggle), "{}", wiggle);
+    }
+    assert!(values.windows(2).any(|pair| pair[0].0 != pair[1].0));
+
+    // The same seed repeats the same values
+    assert_eq!(values, drawn_values(42, 50));
+    assert_ne!(values, drawn_values(43, 50));
+}
+
+// Test that fixed values are used as is
+#[test]
+fn test_fixed_cycle_values() {
+    let mut busycrab = BusyCrab::for_testing(
+        30,
+        4,
+        Box::new(MockMouseController::new()),
+        Box::new(MockPlatform::new()),
+    );
+    assert_eq!(busycrab.get_interval_range(), ValueRange::fixed(30));
+    assert_eq!(busycrab.get_wiggle_range(), ValueRange::fixed(4));
+
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(busycrab.get_interval(), Duration::from_secs(30));
+    assert_eq!(busycrab.get_wiggle_distance(), 4);
+}
+
+// Test that sleep prevention is released outside the schedule and resumes inside it
+#[test]
+fn test_schedule_pauses_and_resumes() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+    let platform_ptr = &*mock_platform as *const MockPlatform;
+    let mock_clock = Box::new(MockClock::monday(10, 0));
+    let clock_ptr = &*mock_clock as *const MockClock;
+    let schedule: Schedule = "Mon-Fri 09:00-18:00".parse().unwrap();
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_schedule(schedule)
+        .with_clock(mock_clock);
+    assert!(busycrab.get_schedule().is_some());
+
+    // Safe because we know the mocks are still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    let platform = unsafe { &*platform_ptr };
+    let clock = unsafe { &*clock_ptr };
+
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Held);
+
+    // Evening: released once, no activity
+    *clock.now.borrow_mut() = NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(19, 0, 0)
+        .unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(mouse.call_count(), 2);
+    assert_eq!(*platform.release_count.borrow(), 1);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Released);
+
+    // Next morning: back to work
+    *clock.now.borrow_mut() = NaiveDate::from_ymd_opt(2024, 1, 2)
+        .unwrap()
+        .and_hms_opt(9, 0, 0)
+        .unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(mouse.call_count(), 4);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Held);
+}
+
+// Test that activity only happens during matching calendar events
+#[test]
+fn test_calendar_limits_activity() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+    let mock_clock = Box::new(MockClock::monday(10, 0));
+    let clock_ptr = &*mock_clock as *const MockClock;
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/calendar/work.ics");
+    let calendar = Calendar::load(path).unwrap().with_filter("Focus");
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_calendar(calendar)
+        .with_clock(mock_clock);
+    assert_eq!(busycrab.get_calendar().unwrap().filter(), Some("Focus"));
+
+    // Safe because we know the mocks are still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    let clock = unsafe { &*clock_ptr };
+
+    // Monday 10:00 is inside the weekly focus block
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Held);
+
+    // Tuesday has no focus block
+    *clock.now.borrow_mut() = NaiveDate::from_ymd_opt(2024, 1, 2)
+        .unwrap()
+        .and_hms_opt(10, 0, 0)
+        .unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(mouse.call_count(), 2);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Released);
+
+    // Wednesday's block
+    *clock.now.borrow_mut() = NaiveDate::from_ymd_opt(2024, 1, 10)
+        .unwrap()
+        .and_hms_opt(9, 30, 0)
+        .unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Held);
+}
+
+// Test that nothing happens on a holiday and activity resumes the day after
+#[test]
+fn test_holiday_pauses() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+    let platform_ptr = &*mock_platform as *const MockPlatform;
+    let mock_clock = Box::new(MockClock::monday(10, 0));
+    let clock_ptr = &*mock_clock as *const MockClock;
+    let holidays: Holidays = "12-31..01-01 New Year".parse().unwrap();
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_holidays(holidays, HolidayAction::Pause)
+        .with_clock(mock_clock);
+    assert_eq!(busycrab.get_holidays().unwrap().entries().len(), 1);
+    assert_eq!(busycrab.get_holiday_action(), HolidayAction::Pause);
+
+    // Safe because we know the mocks are still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    let platform = unsafe { &*platform_ptr };
+    let clock = unsafe { &*clock_ptr };
+
+    // January 1st
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 0);
+    assert_eq!(mouse.call_count(), 0);
+    assert_eq!(*platform.release_count.borrow(), 1);
+    assert!(busycrab.shutdown_handle().load(Ordering::SeqCst));
+
+    *clock.now.borrow_mut() = NaiveDate::from_ymd_opt(2024, 1, 2)
+        .unwrap()
+        .and_hms_opt(9, 0, 0)
+        .unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Held);
+}
+
+// Test that a holiday shuts BusyCrab down with the exit action
+#[test]
+fn test_holiday_exits() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+    let holidays: Holidays = "2024-01-01".parse().unwrap();
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_holidays(holidays, HolidayAction::Exit)
+        .with_clock(Box::new(MockClock::monday(10, 0)));
+
+    let started = std::time::Instant::now();
+    let result = busycrab.run();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+
+    assert!(result.is_ok());
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(mouse.call_count(), 0);
+    assert!(!busycrab.shutdown_handle().load(Ordering::SeqCst));
+}
+
+// Test that pausing releases sleep prevention and stops activity until resumed
+#[test]
+fn test_pause_and_resume() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+    let platform_ptr = &*mock_platform as *const MockPlatform;
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform);
+    let control = busycrab.control_handle();
+
+    // Safe because we know the mocks are still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    let platform = unsafe { &*platform_ptr };
+
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Held);
+
+    control.pause();
+    assert!(busycrab.is_paused());
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(mouse.call_count(), 2);
+    assert_eq!(*platform.release_count.borrow(), 1);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Released);
+
+    control.resume();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(mouse.call_count(), 4);
+    assert_eq!(busycrab.sleep_prevention_status(), InhibitStatus::Held);
+}
+
+// Test that a paused run keeps going without activity until it's stopped
+#[test]
+fn test_run_while_paused() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+
+    let mut busycrab = BusyCrab::for_testing(1, 5, mock_mouse, mock_platform)
+        .with_time_limit(Duration::from_millis(1500));
+    busycrab.control_handle().pause();
+
+    let result = busycrab.run();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+
+    assert!(result.is_ok());
+    assert_eq!(mouse.call_count(), 0);
+    assert!(busycrab.is_paused());
+}
+
+// Test that run serves the control socket, which can check on it and stop it
+#[cfg(unix)]
+#[test]
+fn test_run_serves_control_socket() {
+    use busycrab::control::socket;
+
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mock_platform = Box::new(MockPlatform::new());
+    let path = std::env::temp_dir().join(format!("busycrab-run-{}.sock", std::process::id()));
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_control_socket(&path)
+        .with_time_limit(Duration::from_secs(10));
+
+    let client_path = path.clone();
+    let client = std::thread::spawn(move || {
+        let started = std::time::Instant::now();
+        while !client_path.exists() && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        // Wait for the first cycle
+        let mut status = socket::send(&client_path, "status").unwrap();
+        while status.cycles == 0 && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(20));
+            status = socket::send(&client_path, "status").unwrap();
+        }
+        socket::send(&client_path, "stop").unwrap();
+        status
+    });
+
+    let started = std::time::Instant::now();
+    let result = busycrab.run();
+    let status = client.join().unwrap();
+
+    assert!(result.is_ok());
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(status.pid, std::process::id());
+    assert_eq!(status.state, "active");
+    assert_eq!(status.cycles, 1);
+    assert_eq!(status.backend, Backend::default().to_string());
+    assert_eq!(status.interval_secs, 60);
+    assert!(status.next_activity_secs.unwrap() <= 60);
+    assert!(!path.exists());
+}
+
+/// Finds a loopback address with a port nothing listens on.
+fn free_loopback_addr() -> std::net::SocketAddr {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+// Test that run serves the HTTP API, whose settings take effect without waiting
+// for the current interval
+#[test]
+fn test_run_serves_http_api() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+    let addr = free_loopback_addr();
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_http_api(addr, Some("token".to_string()))
+        .with_time_limit(Duration::from_secs(10));
+
+    let client = std::thread::spawn(move || {
+        let base = format!("http://{}", addr);
+        let get_status = || -> Option<busycrab::control::Status> {
+            ureq::get(&format!("{}/status", base))
+                .set("Authorization", "Bearer token")
+                .call()
+                .ok()?
+                .into_json()
+                .ok()
+        };
+        let wait_for_cycles = |cycles: u64| {
+            let started = std::time::Instant::now();
+            while started.elapsed() < Duration::from_secs(5) {
+                if get_status().is_some_and(|status| status.cycles >= cycles) {
+                    return true;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            false
+        };
+
+        let first_cycle = wait_for_cycles(1);
+        let settings: serde_json::Value = ureq::put(&format!("{}/config", base))
+            .set("Authorization", "Bearer token")
+            .send_string(r#"{"interval":1,"wiggle":"7"}"#)
+            .unwrap()
+            .into_json()
+            .unwrap();
+        let second_cycle = wait_for_cycles(2);
+        ureq::post(&format!("{}/stop", base))
+            .set("Authorization", "Bearer token")
+            .call()
+            .unwrap();
+        (first_cycle, settings, second_cycle)
+    });
+
+    let started = std::time::Instant::now();
+    let result = busycrab.run();
+    let (first_cycle, settings, second_cycle) = client.join().unwrap();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+
+    assert!(result.is_ok());
+    assert!(first_cycle);
+    assert_eq!(settings["interval"], "1");
+    assert_eq!(settings["wiggle"], "7");
+    assert_eq!(settings["motion"], "none");
+    assert!(second_cycle);
+    assert!(started.elapsed() < Duration::from_secs(8));
+    assert_eq!(busycrab.get_interval_range(), ValueRange::fixed(1));
+    assert_eq!(busycrab.get_wiggle_range(), ValueRange::fixed(7));
+    assert_eq!(mouse.call_args(0), Some((5, 0)));
+    assert_eq!(mouse.call_args(2), Some((7, 0)));
+}
+
+// Test that run publishes the D-Bus service, announcing each activity
+#[cfg(target_os = "linux")]
+#[test]
+fn test_run_publishes_dbus_service() {
+    use busycrab::control::dbus::{Bus, OBJECT_PATH, SERVICE_NAME};
+
+    let Some(bus) = common::TestBus::start() else {
+        eprintln!("dbus-daemon not available, skipping");
+        return;
+    };
+    let client = zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .build()
+        .unwrap();
+    let proxy = zbus::blocking::Proxy::new(&client, SERVICE_NAME, OBJECT_PATH, SERVICE_NAME)
+        .unwrap();
+    let activities = proxy.receive_signal("ActivityPerformed").unwrap();
+
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mock_platform = Box::new(MockPlatform::new());
+    let mut busycrab = BusyCrab::for_testing(1, 5, mock_mouse, mock_platform)
+        .with_dbus_service(Bus::Address(bus.address.clone()))
+        .with_time_limit(Duration::from_millis(2500));
+    busycrab.run().unwrap();
+
+    let counts: Vec<u64> = activities
+        .take(3)
+        .map(|signal| signal.body().deserialize().unwrap())
+        .collect();
+    assert_eq!(counts, vec![1, 2, 3]);
+
+    // The service leaves the bus with run
+    let dbus = zbus::blocking::fdo::DBusProxy::new(&client).unwrap();
+    assert!(!dbus.name_has_owner(SERVICE_NAME.try_into().unwrap()).unwrap());
+}
+
+// Test that run fails before doing anything when the HTTP API can't start
+#[test]
+fn test_run_fails_without_http_api() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_http_api("0.0.0.0:0".parse().unwrap(), None);
+    let err = busycrab.run().unwrap_err();
+
+    // Safe because we know the mock is still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+
+    assert!(matches!(err, Error::Config(_)));
+    assert_eq!(mouse.call_count(), 0);
+}
+
+// Test that cycles and sleep prevention refreshes are counted in the metrics
+#[test]
+fn test_cycles_update_metrics() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mock_platform = Box::new(MockPlatform::new());
+    *mock_platform.transient_failures.borrow_mut() = 1;
+
+    let policy = FailurePolicy::Retry {
+        max_retries: 3,
+        initial_delay: Duration::from_millis(1),
+    };
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_failure_policy(policy);
+    let handle = busycrab.control_handle();
+    let mut count = 0;
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+    handle.pause();
+    busycrab.execute_activity_cycle(&mut count).unwrap();
+
+    assert_eq!(handle.metrics().activity_cycles(), 1);
+    let text = handle.metrics().render(handle.is_paused());
+    let refreshes = |result: &str| {
+        format!(
+            "busycrab_sleep_prevention_total{{backend=\"{}\",result=\"{}\"}} 1",
+            Backend::default(),
+            result
+        )
+    };
+    for sample in [
+        refreshes("success"),
+        refreshes("failure"),
+        "busycrab_skipped_cycles_total{reason=\"paused\"} 1".to_string(),
+        "busycrab_paused 1".to_string(),
+    ] {
+        assert!(text.lines().any(|line| line == sample), "{}", sample);
+    }
+}
+
+// Test that the animation thread counts the frames it renders
+#[test]
+fn test_run_counts_animation_frames() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mock_platform = Box::new(MockPlatform::new());
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_motion("clock")
+        .with_time_limit(Duration::from_millis(300));
+    let handle = busycrab.control_handle();
+    assert_eq!(handle.metrics().animation_frames(), 0);
+    busycrab.run().unwrap();
+
+    assert!(handle.metrics().animation_frames() > 0);
+}
+
+// Test that run stops cleanly once the time limit is up
+#[test]
+fn test_run_stops_at_time_limit() {
+    let mock_mouse = Box::new(MockMouseController::new());
+    let mouse_ptr = &*mock_mouse as *const MockMouseController;
+    let mock_platform = Box::new(MockPlatform::new());
+    let platform_ptr = &*mock_platform as *const MockPlatform;
+
+    let mut busycrab = BusyCrab::for_testing(60, 5, mock_mouse, mock_platform)
+        .with_time_limit(Duration::from_secs(30))
+        .with_time_limit(Duration::from_millis(300));
+    assert_eq!(busycrab.get_time_limit(), Some(Duration::from_millis(300)));
+    assert_eq!(busycrab.remaining_time(), None);
+
+    let started = std::time::Instant::now();
+    let result = busycrab.run();
+
+    // Safe because we know the mocks are still alive inside busycrab
+    let mouse = unsafe { &*mouse_ptr };
+    let platform = unsafe { &*platform_ptr };
+
+    assert!(result.is_ok());
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(mouse.call_count(), 2);
+    assert_eq!(*platform.release_count.borrow(), 1);
+    assert!(!busycrab.shutdown_handle().load(Ordering::SeqCst));
+    assert_eq!(busycrab.remaining_time(), Some(Duration::ZERO));
+}